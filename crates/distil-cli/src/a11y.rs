@@ -0,0 +1,89 @@
+//! The contrast report printed by `distil a11y`.
+
+use std::fmt::Write;
+
+use distil_core::{contrast_ratio, Color};
+
+/// The contrast ratios WCAG requires, as `(name, minimum ratio)`, for normal
+/// and large text.
+static LEVELS: [(&str, f32); 4] = [("AA", 4.5), ("AAA", 7.0), ("AA large", 3.0), ("AAA large", 4.5)];
+
+/// Returns a matrix of the contrast ratios between every pair of `colors`.
+pub fn matrix(colors: &[Color]) -> String {
+    let mut report = format!("{:7}", "");
+
+    for color in colors {
+        let _ = write!(report, "  {:>7}", color.to_string());
+    }
+
+    for &row in colors {
+        let _ = write!(report, "\n{:7}", row.to_string());
+
+        for &column in colors {
+            if row == column {
+                let _ = write!(report, "  {:>7}", "-");
+            } else {
+                let _ = write!(report, "  {:>7.2}", contrast_ratio(row, column));
+            }
+        }
+    }
+
+    report
+}
+
+/// Returns whether each pair of `colors` passes each WCAG level, from the
+/// pair with the most contrast to the one with the least.
+pub fn pairs(colors: &[Color]) -> String {
+    let mut pairs: Vec<(Color, Color, f32)> = Vec::new();
+
+    for (i, &a) in colors.iter().enumerate() {
+        for &b in &colors[i + 1..] {
+            pairs.push((a, b, contrast_ratio(a, b)));
+        }
+    }
+
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let mut header = format!("{:15}  {:>8}", "Pair", "Ratio");
+
+    for (name, _) in LEVELS {
+        let _ = write!(header, "  {:<9}", name);
+    }
+
+    let mut report = header.trim_end().to_owned();
+
+    for (a, b, ratio) in pairs {
+        let mut line = format!("{} {}  {:>6.2}:1", a, b, ratio);
+
+        for (_, min) in LEVELS {
+            let _ = write!(line, "  {:<9}", if ratio >= min { "pass" } else { "fail" });
+        }
+
+        report.push('\n');
+        report.push_str(line.trim_end());
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::Color;
+
+    use super::{matrix, pairs};
+
+    #[test]
+    fn report() {
+        let colors = [Color::new(255, 255, 255), Color::new(0, 0, 0), Color::new(118, 118, 118)];
+
+        assert_eq!(matrix(&colors[..2]),
+                   "         #ffffff  #000000\n#ffffff        -    21.00\n#000000    21.00        -");
+
+        assert_eq!(pairs(&colors).lines().collect::<Vec<_>>(), [
+            "Pair                Ratio  AA         AAA        AA large   AAA large",
+            "#ffffff #000000   21.00:1  pass       pass       pass       pass",
+            "#000000 #767676    4.62:1  pass       fail       pass       pass",
+            "#ffffff #767676    4.54:1  pass       fail       pass       pass",
+        ]);
+    }
+}
@@ -0,0 +1,61 @@
+//! The errors of `distil` commands and the exit codes they map to, so that
+//! scripts can tell failures apart without parsing messages.
+
+use distil_core::DistilError;
+
+/// The exit code of invalid arguments.
+pub static USAGE_ERROR: i32 = 2;
+
+/// A failed command: the message printed to stderr and the exit code.
+#[derive(Debug)]
+pub struct CliError {
+    pub message: String,
+    pub code: i32,
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> CliError {
+        CliError {
+            message,
+            code: USAGE_ERROR,
+        }
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(message: &str) -> CliError {
+        CliError::from(message.to_owned())
+    }
+}
+
+impl From<DistilError> for CliError {
+    fn from(err: DistilError) -> CliError {
+        let code = match err {
+            DistilError::InvalidColor(_) => USAGE_ERROR,
+            DistilError::Io(..) => 3,
+            DistilError::UnsupportedFormat => 4,
+            DistilError::Uninteresting => 5,
+            DistilError::Output(..) => 6,
+            DistilError::InvalidBuffer | DistilError::TooFewColors(..) => 7,
+        };
+
+        CliError {
+            message: err.to_string(),
+            code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{Distil, DistilError};
+
+    use super::CliError;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(CliError::from("Unexpected argument").code, 2);
+        assert_eq!(CliError::from(Distil::from_path_str("./missing.png").unwrap_err()).code, 3);
+        assert_eq!(CliError::from(DistilError::Uninteresting).code, 5);
+    }
+}
@@ -0,0 +1,94 @@
+//! The narrative printed by `distil explain`.
+
+use std::fmt::Write;
+
+use distil_core::extract::{Artifacts, Filtered};
+use distil_core::Color;
+
+/// Describes each stage of distilling an image of `dimensions` from its
+/// `artifacts`.
+pub fn explain(dimensions: (u32, u32), artifacts: &Artifacts) -> String {
+    let (width, height) = artifacts.scaled.dimensions();
+    let total = artifacts.mask.len();
+    let sampled = artifacts.mask.iter().filter(|&&sampled| sampled).count();
+    let mut report = String::new();
+
+    let _ = writeln!(report, "Sampling");
+    let _ = writeln!(report,
+                     "  Scaled {}×{} down to {}×{} ({} pixels)",
+                     dimensions.0,
+                     dimensions.1,
+                     width,
+                     height,
+                     total);
+    let _ = writeln!(report, "  Sampled {} pixels ({:.1}%)", sampled, percentage(sampled, total));
+
+    for &(reason, count) in &artifacts.filtered {
+        let _ = writeln!(report, "  Filtered {} pixels ({:.1}%) {}", count, percentage(count, total), describe(reason));
+    }
+
+    let _ = writeln!(report, "\nQuantizing");
+    let _ = writeln!(report,
+                     "  {} reduced the sampled pixels to {} colors, {} of them unique",
+                     artifacts.quantizer,
+                     artifacts.quantized.len(),
+                     artifacts.histogram.len());
+
+    let _ = writeln!(report, "\nMerging");
+    let _ = writeln!(report, "  Merged {} similar colors", artifacts.merges.len());
+
+    for merge in &artifacts.merges {
+        let _ = writeln!(report,
+                         "  {} ×{} into {} (ΔE {:.1})",
+                         Color::from(merge.color),
+                         merge.count,
+                         Color::from(merge.into),
+                         merge.distance);
+    }
+
+    let _ = writeln!(report, "\nPalette");
+
+    for (i, color) in artifacts.distil.colors.iter().enumerate() {
+        let _ = writeln!(report,
+                         "  {}  {:>5.1}% of sampled pixels, spread {:.1}",
+                         color,
+                         artifacts.distil.weights.get(i).unwrap_or(&0.0) * 100.0,
+                         artifacts.distil.spreads.get(i).unwrap_or(&0.0));
+    }
+
+    report
+}
+
+fn describe(reason: Filtered) -> &'static str {
+    match reason {
+        Filtered::Transparent => "as transparent",
+        Filtered::Black => "as too dark",
+        Filtered::White => "as too light",
+        Filtered::Excluded => "as excluded colors",
+        Filtered::Region => "in excluded regions",
+        Filtered::Custom => "by the pixel filter",
+    }
+}
+
+fn percentage(count: usize, total: usize) -> f32 {
+    count as f32 / total.max(1) as f32 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{gen, Color, Distil};
+
+    use super::explain;
+
+    #[test]
+    fn narrative() {
+        let img = gen::blocks(30, 10, &[(Color::new(200, 60, 60), 2), (Color::new(252, 252, 252), 1)]);
+        let report = explain(img.dimensions(), &Distil::builder().debug(&img).unwrap());
+
+        assert!(report.starts_with("Sampling\n  Scaled 30×10 down to 30×10 (300 pixels)\n  Sampled 200 pixels (66.7%)\n"));
+        assert!(report.contains("  Filtered 100 pixels (33.3%) as too light\n"));
+        assert!(report.contains("\nQuantizing\n  NeuQuant reduced the sampled pixels to 256 colors"));
+        assert!(report.contains("\nMerging\n  Merged "));
+        assert!(report.contains("\nPalette\n  #"));
+    }
+}
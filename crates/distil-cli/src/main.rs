@@ -0,0 +1,566 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use distil_core::render::{compliance_heatmap, half_blocks, remap_to_palette, Grade};
+use distil_core::extract::StreamDistiller;
+use distil_core::{Color, DirConfig, Distil, DistilError, Image};
+use distil_export::{EngineExport, GradeExport, StylesheetExport};
+
+use crate::error::CliError;
+
+mod a11y;
+mod error;
+mod explain;
+mod theme;
+mod ticker;
+
+static USAGE: &str = "Usage:
+    distil [palette] <image|-> [--size <colors>] [--format <hex|json|css|png|unity|unity-json|godot|bevy>]
+                             [--out <path>] [--preview]
+    distil [palette] <directory> [--recursive] [--size <colors>] [--format <hex|json>] [--out <path>]
+    distil check <image> --brand <color>[,<color>…] [--tolerance <delta-e>]
+                         [--min-coverage <percent>] [--heatmap <out.png>] [--block-size <pixels>]
+    distil transfer <source> <target> [--out <out.png>] [--lut <out.cube>]
+    distil theme-color <image> [--write <index.html|manifest.json>]…
+    distil a11y <image>
+    distil explain <image>
+    distil preview <image> [--width <columns>] [--size <colors>]
+    distil ticker <frame|directory|->… [--fps <rate>] [--decay <0-1>] [--size <colors>]
+
+Commands:
+    palette        Print the palette of an image, or of the image on stdin, or write it to a file.
+                   Given a directory, print the palette of each image in it, one per line. `--preview`
+                   prints swatches in the terminal's colors with their hex codes instead
+    check          Check that the colors of an image are within tolerance of a brand palette. Colors
+                   covering less than `--min-coverage` percent of the image (1 by default) are ignored
+    transfer       Transfer the colors of the source image onto the target image
+    theme-color    Print the theme color of an image and patch it into HTML pages or manifests
+    a11y           Print the contrast between the palette colors of an image and the WCAG levels they pass
+    explain        Print what happens to an image at each stage of distilling it, e.g. for bug reports
+    preview        Print an image recolored with its own palette in a true color terminal
+    ticker         Print a line of JSON with the timestamp, dominant color and palette of each frame as it's
+                   distilled, e.g. for live overlays. Frames are read from directories in name order, or
+                   their paths from stdin as they're written
+
+Exit codes:
+    0    Success
+    1    `check` found colors outside of the brand palette
+    2    Invalid arguments
+    3    The image couldn't be read
+    4    The image isn't in a supported format
+    5    The image has no interesting colors
+    6    The output couldn't be written
+    7    Any other error";
+
+static DEFAULT_TOLERANCE: f32 = 10.0;
+static DEFAULT_MIN_COVERAGE: f32 = 1.0;
+static DEFAULT_BLOCK_SIZE: u32 = 16;
+static LUT_SIZE: usize = 33;
+static PREVIEW_WIDTH: u32 = 80;
+static TICKER_FPS: f32 = 1.0;
+static FRAME_EXTENSIONS: [&str; 8] = ["bmp", "gif", "jpeg", "jpg", "png", "tif", "tiff", "webp"];
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        Some("palette") => palette(&args[1..]),
+        Some("check") => check(&args[1..]),
+        Some("transfer") => transfer(&args[1..]),
+        Some("theme-color") => theme_color(&args[1..]),
+        Some("a11y") => accessibility(&args[1..]),
+        Some("explain") => explain(&args[1..]),
+        Some("preview") => preview(&args[1..]),
+        Some("ticker") => ticker(&args[1..]),
+        Some(_) => palette(&args),
+        None => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(code) => process::exit(code),
+        Err(err) => {
+            eprintln!("{}", err.message);
+            process::exit(err.code);
+        }
+    }
+}
+
+/// `distil palette`: prints the palette of an image, or of the image piped
+/// to stdin when the path is `-`, as hex colors, JSON, CSS variables or a
+/// Unity, Godot or Bevy asset, or writes it to `--out`, e.g. as a PNG of
+/// swatches. `--preview` prints ANSI swatches in its place, or alongside
+/// the file.
+fn palette(args: &[String]) -> Result<i32, CliError> {
+    let mut image = None;
+    let mut size = None;
+    let mut format = None;
+    let mut out = None;
+    let mut recursive = false;
+    let mut preview = false;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => size = Some(parse_number(arg, option_value(arg, args.next())?)?.max(1.0) as usize),
+            "--recursive" => recursive = true,
+            "--preview" => preview = true,
+            "--format" => format = Some(option_value(arg, args.next())?),
+            "--out" => out = Some(option_value(arg, args.next())?),
+            _ if image.is_none() => image = Some(arg.as_str()),
+            _ => return Err(CliError::from(format!("Unexpected argument `{}`\n\n{}", arg, USAGE))),
+        }
+    }
+
+    let is_png = out.is_some_and(|out| out.to_lowercase().ends_with(".png"));
+    let format = format.unwrap_or(if is_png { "png" } else { "hex" });
+
+    if !["hex", "json", "css", "png", "unity", "unity-json", "godot", "bevy"].contains(&format) {
+        return Err(CliError::from(format!("Unknown format `{}`, expected hex, json, css, png, unity, unity-json, godot or bevy", format)));
+    }
+
+    if format == "png" && out.is_none() {
+        return Err("`--format png` requires `--out`".into());
+    }
+
+    let image = image.ok_or(USAGE)?;
+
+    if Path::new(image).is_dir() {
+        if preview {
+            return Err("`--preview` only works with a single image".into());
+        }
+
+        return palettes(Path::new(image), recursive, size, format, out);
+    }
+
+    let distilled = match image {
+        "-" => Distil::from_reader(io::stdin().lock())?,
+        image => Distil::from_path(Path::new(image))?,
+    };
+
+    let distilled = truncate(distilled, size.unwrap_or(usize::MAX));
+
+    if preview {
+        distilled.print_terminal()?;
+    }
+
+    let output = match format {
+        "hex" => distilled.colors.iter().map(|color| format!("{}\n", color)).collect(),
+        "json" => format!("{}\n", distilled.to_json()),
+        "css" => distilled.as_css("palette"),
+        "unity" => distilled.as_unity_colors(),
+        "unity-json" => distilled.as_unity_json("palette"),
+        "godot" => distilled.as_godot_gradient(),
+        "bevy" => distilled.as_bevy("palette"),
+        _ => {
+            let palette_size = distilled.colors.len().min(u8::MAX as usize) as u8;
+
+            distilled.swatch_img(palette_size).save(Path::new(out.unwrap_or_default()))?;
+            return Ok(0);
+        }
+    };
+
+    match out {
+        Some(out) => fs::write(out, output).map_err(|err| DistilError::Output(out.to_owned(), err))?,
+        None if preview => {}
+        None => print!("{}", output),
+    }
+
+    Ok(0)
+}
+
+/// `distil palette <directory>`: prints the palette of each image in the
+/// directory, one image per line. Images that fail are reported on stderr
+/// and the exit code is that of the first failure.
+fn palettes(dir: &Path, recursive: bool, size: Option<usize>, format: &str, out: Option<&str>) -> Result<i32, CliError> {
+    if !["hex", "json"].contains(&format) {
+        return Err(CliError::from(format!("Directories can only be printed as hex or json, not `{}`", format)));
+    }
+
+    let config = DirConfig {
+        recursive,
+        ..DirConfig::default()
+    };
+
+    let mut output = String::new();
+    let mut code = 0;
+
+    for (path, distilled) in Distil::from_dir(dir, &config)? {
+        let distilled = match distilled {
+            Ok(distilled) => truncate(distilled, size.unwrap_or(usize::MAX)),
+            Err(err) => {
+                eprintln!("{}: {}", path.display(), err);
+                code = if code == 0 { CliError::from(err).code } else { code };
+                continue;
+            }
+        };
+
+        output += &match format {
+            "hex" => {
+                let colors: Vec<String> = distilled.colors.iter().map(Color::to_string).collect();
+                format!("{}: {}\n", path.display(), colors.join(" "))
+            }
+            _ => format!("{{\"path\":{},\"palette\":{}}}\n", json_string(&path.display().to_string()), distilled.to_json()),
+        };
+    }
+
+    match out {
+        Some(out) => fs::write(out, output).map_err(|err| DistilError::Output(out.to_owned(), err))?,
+        None => print!("{}", output),
+    }
+
+    Ok(code)
+}
+
+/// `distil check`: prints the palette colors that aren't close enough to the
+/// brand colors and exits with `1` if there are any.
+fn check(args: &[String]) -> Result<i32, CliError> {
+    let mut image = None;
+    let mut brand = Vec::new();
+    let mut tolerance = DEFAULT_TOLERANCE;
+    let mut min_coverage = DEFAULT_MIN_COVERAGE;
+    let mut heatmap = None;
+    let mut block_size = DEFAULT_BLOCK_SIZE;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--brand" => brand = parse_colors(option_value(arg, args.next())?)?,
+            "--tolerance" => tolerance = parse_number(arg, option_value(arg, args.next())?)?,
+            "--min-coverage" => min_coverage = parse_number(arg, option_value(arg, args.next())?)?,
+            "--heatmap" => heatmap = Some(option_value(arg, args.next())?),
+            "--block-size" => block_size = parse_number(arg, option_value(arg, args.next())?)? as u32,
+            _ if image.is_none() => image = Some(arg),
+            _ => return Err(CliError::from(format!("Unexpected argument `{}`\n\n{}", arg, USAGE))),
+        }
+    }
+
+    let image = image.ok_or(USAGE)?;
+
+    if brand.is_empty() {
+        return Err(CliError::from(format!("At least one brand color is required\n\n{}", USAGE)));
+    }
+
+    let distilled = Distil::from_path(Path::new(image))?;
+    let report = distilled.check_against(&brand, tolerance, min_coverage / 100.0);
+
+    if let Some(out_path) = heatmap {
+        let img = Image::open(Path::new(image))?;
+
+        compliance_heatmap(&img, &brand, tolerance, block_size).save(Path::new(out_path))?;
+    }
+
+    for violation in &report.violations {
+        let nearest = violation.nearest.map_or(String::new(), |nearest| nearest.to_string());
+
+        println!("{}  {:>5.1}%  nearest {} (ΔE {:.1})",
+                 violation.color,
+                 violation.coverage * 100.0,
+                 nearest,
+                 violation.distance);
+    }
+
+    println!("{:.1}% of the image is within tolerance of the brand colors",
+             report.compliant_coverage * 100.0);
+
+    Ok(if report.is_compliant() { 0 } else { 1 })
+}
+
+/// `distil transfer`: writes the target image recolored with the color
+/// statistics of the source image, and/or the transfer as a `.cube` LUT.
+fn transfer(args: &[String]) -> Result<i32, CliError> {
+    let mut images = Vec::new();
+    let mut out = None;
+    let mut lut = None;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(option_value(arg, args.next())?),
+            "--lut" => lut = Some(option_value(arg, args.next())?),
+            _ if images.len() < 2 => images.push(arg),
+            _ => return Err(CliError::from(format!("Unexpected argument `{}`\n\n{}", arg, USAGE))),
+        }
+    }
+
+    if images.len() != 2 || (out.is_none() && lut.is_none()) {
+        return Err(USAGE.into());
+    }
+
+    let source = Image::open(Path::new(images[0]))?;
+    let target = Image::open(Path::new(images[1]))?;
+    let grade = Grade::transfer(&source, &target);
+
+    if let Some(out_path) = out {
+        grade.apply(&target).save(Path::new(out_path))?;
+    }
+
+    if let Some(lut_path) = lut {
+        grade.to_lut(LUT_SIZE)
+            .write_cube(Path::new(lut_path), images[0])
+            .map_err(|err| DistilError::Output(lut_path.to_owned(), err))?;
+    }
+
+    Ok(0)
+}
+
+/// `distil theme-color`: prints the color covering the most of an image and
+/// writes it into the `theme-color` meta tags of HTML pages and the
+/// `theme_color` of web app manifests.
+fn theme_color(args: &[String]) -> Result<i32, CliError> {
+    let mut image = None;
+    let mut writes = Vec::new();
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--write" => writes.push(option_value(arg, args.next())?),
+            _ if image.is_none() => image = Some(arg),
+            _ => return Err(CliError::from(format!("Unexpected argument `{}`\n\n{}", arg, USAGE))),
+        }
+    }
+
+    let image = image.ok_or(USAGE)?;
+    let distilled = Distil::from_path(Path::new(image))?;
+    let color = distilled.entries()
+        .into_iter()
+        .max_by(|x, y| x.weight.total_cmp(&y.weight))
+        .map(|entry| entry.color)
+        .ok_or(DistilError::Uninteresting)?;
+
+    for path in writes {
+        let contents = fs::read_to_string(path).map_err(|err| DistilError::Io(path.to_owned(), err.to_string()))?;
+        let is_manifest = path.ends_with(".json") || path.ends_with(".webmanifest");
+
+        let patched = if is_manifest {
+            theme::patch_manifest(&contents, color)
+        } else {
+            theme::patch_html(&contents, color)
+        };
+
+        let patched = patched.map_err(|err| format!("{}: {}", path, err))?;
+        fs::write(path, patched).map_err(|err| DistilError::Output(path.to_owned(), err))?;
+    }
+
+    println!("{}", color);
+
+    Ok(0)
+}
+
+/// `distil a11y`: prints the contrast ratio between every pair of palette
+/// colors of an image and whether each pair passes WCAG AA and AAA.
+fn accessibility(args: &[String]) -> Result<i32, CliError> {
+    let image = match args {
+        [image] => image,
+        _ => return Err(USAGE.into()),
+    };
+
+    let distilled = Distil::from_path(Path::new(image))?;
+
+    println!("{}\n", a11y::matrix(&distilled.colors));
+    println!("{}", a11y::pairs(&distilled.colors));
+
+    Ok(0)
+}
+
+/// `distil explain`: prints how many pixels of an image were sampled and
+/// filtered, what the quantizer produced, which colors were merged and the
+/// resulting palette.
+fn explain(args: &[String]) -> Result<i32, CliError> {
+    let image = match args {
+        [image] => image,
+        _ => return Err(USAGE.into()),
+    };
+
+    let img = Image::open(Path::new(image))?;
+    let artifacts = Distil::builder().debug(&img)?;
+
+    print!("{}", explain::explain(img.dimensions(), &artifacts));
+
+    Ok(0)
+}
+
+/// `distil preview`: prints an image recolored with its `--size` most
+/// frequent palette colors as Unicode half blocks, `--width` characters
+/// wide at most.
+fn preview(args: &[String]) -> Result<i32, CliError> {
+    let mut image = None;
+    let mut width = PREVIEW_WIDTH;
+    let mut size = usize::MAX;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--width" => width = parse_number(arg, option_value(arg, args.next())?)?.max(1.0) as u32,
+            "--size" => size = parse_number(arg, option_value(arg, args.next())?)?.max(1.0) as usize,
+            _ if image.is_none() => image = Some(arg),
+            _ => return Err(CliError::from(format!("Unexpected argument `{}`\n\n{}", arg, USAGE))),
+        }
+    }
+
+    let image = image.ok_or(USAGE)?;
+    let img = Image::open(Path::new(image))?;
+    let distilled = truncate(Distil::from_image(&img)?, size);
+
+    print!("{}", half_blocks(&remap_to_palette(&img, &distilled), width));
+
+    Ok(0)
+}
+
+/// `distil ticker`: streams frames through a `StreamDistiller` and prints
+/// the palette of each one as a line of JSON, flushing after every frame so
+/// that overlays can follow along. Frames that fail are reported on stderr
+/// and the exit code is that of the first failure.
+fn ticker(args: &[String]) -> Result<i32, CliError> {
+    let mut inputs = Vec::new();
+    let mut fps = TICKER_FPS;
+    let mut size = usize::MAX;
+    let mut stream = StreamDistiller::default();
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fps" => fps = parse_number(arg, option_value(arg, args.next())?)?,
+            "--decay" => stream = stream.decay(parse_number(arg, option_value(arg, args.next())?)?),
+            "--size" => size = parse_number(arg, option_value(arg, args.next())?)?.max(1.0) as usize,
+            _ => inputs.push(arg.as_str()),
+        }
+    }
+
+    if inputs.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    if !fps.is_finite() || fps <= 0.0 {
+        return Err("`--fps` must be greater than 0".into());
+    }
+
+    let mut frames: Box<dyn Iterator<Item = Result<PathBuf, DistilError>>> = Box::new(std::iter::empty());
+
+    for input in inputs {
+        frames = match input {
+            "-" => Box::new(frames.chain(io::stdin().lock().lines().map(|line| {
+                line.map(PathBuf::from).map_err(|err| DistilError::Io("stdin".to_owned(), err.to_string()))
+            }))),
+            input if Path::new(input).is_dir() => Box::new(frames.chain(frame_paths(Path::new(input))?.into_iter().map(Ok))),
+            input => Box::new(frames.chain(std::iter::once(Ok(PathBuf::from(input))))),
+        };
+    }
+
+    let mut stdout = io::stdout().lock();
+    let mut code = 0;
+
+    for (frame, path) in frames.enumerate() {
+        let palette = path.and_then(|path| Image::open(&path)).and_then(|img| stream.push(&img));
+
+        let palette = match palette {
+            Ok(palette) => truncate(palette, size),
+            Err(err) => {
+                eprintln!("frame {}: {}", frame, err);
+                code = if code == 0 { CliError::from(err).code } else { code };
+                continue;
+            }
+        };
+
+        writeln!(stdout, "{}", ticker::line(frame, ticker::timestamp(frame, fps), &palette))
+            .and_then(|_| stdout.flush())
+            .map_err(|err| DistilError::Output("stdout".to_owned(), err))?;
+    }
+
+    Ok(code)
+}
+
+/// Returns the paths of the images in `dir`, sorted by name so that frames
+/// numbered by e.g. ffmpeg come out in order.
+fn frame_paths(dir: &Path) -> Result<Vec<PathBuf>, DistilError> {
+    let io_err = |err: io::Error| DistilError::Io(dir.display().to_string(), err.to_string());
+    let mut paths = Vec::new();
+
+    for entry in fs::read_dir(dir).map_err(io_err)? {
+        let path = entry.map_err(io_err)?.path();
+        let extension = path.extension().and_then(|extension| extension.to_str()).map(str::to_lowercase);
+
+        if path.is_file() && extension.is_some_and(|extension| FRAME_EXTENSIONS.contains(&extension.as_str())) {
+            paths.push(path);
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Keeps the `size` most frequent colors of `distil`.
+fn truncate(mut distil: Distil, size: usize) -> Distil {
+    distil.colors.truncate(size);
+    distil.counts.truncate(size);
+    distil.lab_colors.truncate(size);
+    distil.spreads.truncate(size);
+    distil.weights.truncate(size);
+    distil
+}
+
+/// Quotes `value` as a JSON string.
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+
+    json + "\""
+}
+
+fn option_value<'a>(option: &str, value: Option<&'a String>) -> Result<&'a str, String> {
+    value.map(String::as_str).ok_or_else(|| format!("`{}` requires a value", option))
+}
+
+fn parse_colors(value: &str) -> Result<Vec<Color>, String> {
+    split_colors(value).iter()
+        .map(|color| color.parse::<Color>().map_err(|err| err.to_string()))
+        .collect()
+}
+
+fn parse_number(option: &str, value: &str) -> Result<f32, String> {
+    value.parse().map_err(|_| format!("`{}` expects a number, got `{}`", option, value))
+}
+
+/// Splits a comma separated list of colors, keeping the commas inside of
+/// `rgb()` and `hsl()` intact.
+fn split_colors(value: &str) -> Vec<&str> {
+    let mut colors = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                colors.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    colors.push(value[start..].trim());
+    colors.retain(|color| !color.is_empty());
+    colors
+}
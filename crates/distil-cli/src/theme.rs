@@ -0,0 +1,127 @@
+//! Patching the theme color of HTML pages and web app manifests.
+
+use distil_core::Color;
+
+/// Sets the `content` of every `<meta name="theme-color">` tag in `html` to
+/// `color`, or adds one at the end of `<head>` if there are none.
+pub fn patch_html(html: &str, color: Color) -> Result<String, String> {
+    // Lowercasing ASCII keeps every byte where it is, so offsets found in
+    // `lower` can be used to slice `html`.
+    let lower = html.to_ascii_lowercase();
+    let mut patched = String::with_capacity(html.len());
+    let mut last = 0;
+    let mut search = 0;
+
+    while let Some(start) = lower[search..].find("<meta").map(|i| i + search) {
+        let end = match lower[start..].find('>') {
+            Some(i) => start + i,
+            None => break,
+        };
+
+        let tag = &lower[start..end];
+        let is_theme_color = attribute_value(tag, "name").is_some_and(|(s, e)| &tag[s..e] == "theme-color");
+
+        if let (true, Some((value_start, value_end))) = (is_theme_color, attribute_value(tag, "content")) {
+            patched.push_str(&html[last..start + value_start]);
+            patched.push_str(&color.to_string());
+            last = start + value_end;
+        }
+
+        search = end;
+    }
+
+    if last > 0 {
+        patched.push_str(&html[last..]);
+        return Ok(patched);
+    }
+
+    let head_end = lower.find("</head>").ok_or_else(|| "The page has no `<head>` to add a theme color to".to_owned())?;
+    let indent = html[..head_end].rsplit('\n').next().unwrap_or("");
+    let indent = if indent.trim().is_empty() { indent } else { "" };
+
+    Ok(format!("{}  <meta name=\"theme-color\" content=\"{}\">\n{}{}",
+               &html[..head_end],
+               color,
+               indent,
+               &html[head_end..]))
+}
+
+/// Sets the `theme_color` of the web app manifest `json` to `color`, adding
+/// it as the first member if it's missing.
+pub fn patch_manifest(json: &str, color: Color) -> Result<String, String> {
+    if let Some(key) = json.find("\"theme_color\"") {
+        let after_key = key + "\"theme_color\"".len();
+        let colon = after_key + json[after_key..].find(':').ok_or_else(invalid_manifest)?;
+        let value_start = colon + 1 + json[colon + 1..].find('"').ok_or_else(invalid_manifest)? + 1;
+        let value_end = value_start + json[value_start..].find('"').ok_or_else(invalid_manifest)?;
+
+        return Ok(format!("{}{}{}", &json[..value_start], color, &json[value_end..]));
+    }
+
+    let open = json.find('{').ok_or_else(invalid_manifest)? + 1;
+    let separator = if json[open..].trim_start().starts_with('}') { "" } else { "," };
+
+    Ok(format!("{}\n  \"theme_color\": \"{}\"{}{}", &json[..open], color, separator, &json[open..]))
+}
+
+/// Returns the byte range of the value of the `name` attribute in `tag`,
+/// without its quotes.
+fn attribute_value(tag: &str, name: &str) -> Option<(usize, usize)> {
+    let mut search = 0;
+
+    while let Some(start) = tag[search..].find(name).map(|i| i + search) {
+        search = start + name.len();
+
+        let preceded_by_space = tag[..start].ends_with(|c: char| c.is_ascii_whitespace());
+        let rest = tag[search..].trim_start();
+
+        if !preceded_by_space || !rest.starts_with('=') {
+            continue;
+        }
+
+        let rest = rest[1..].trim_start();
+        let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'')?;
+        let value_start = tag.len() - rest.len() + 1;
+        let value_end = value_start + tag[value_start..].find(quote)?;
+
+        return Some((value_start, value_end));
+    }
+
+    None
+}
+
+fn invalid_manifest() -> String {
+    "The manifest isn't a JSON object".to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::Color;
+
+    use super::{patch_html, patch_manifest};
+
+    #[test]
+    fn html() {
+        let teal = Color::new(0, 128, 128);
+        let page = "<html>\n  <head>\n    <meta name=\"theme-color\" content=\"#ffffff\">\n  </head>\n</html>";
+        let bare = "<html>\n  <head>\n    <title>Distil</title>\n  </head>\n</html>";
+
+        assert_eq!(patch_html(page, teal).unwrap(),
+                   "<html>\n  <head>\n    <meta name=\"theme-color\" content=\"#008080\">\n  </head>\n</html>");
+        assert_eq!(patch_html(bare, teal).unwrap(),
+                   "<html>\n  <head>\n    <title>Distil</title>\n    <meta name=\"theme-color\" \
+                    content=\"#008080\">\n  </head>\n</html>");
+        assert!(patch_html("<p>Distil</p>", teal).is_err());
+    }
+
+    #[test]
+    fn manifest() {
+        let teal = Color::new(0, 128, 128);
+
+        assert_eq!(patch_manifest("{\n  \"name\": \"Distil\",\n  \"theme_color\": \"#fff\"\n}", teal).unwrap(),
+                   "{\n  \"name\": \"Distil\",\n  \"theme_color\": \"#008080\"\n}");
+        assert_eq!(patch_manifest("{\n  \"name\": \"Distil\"\n}", teal).unwrap(),
+                   "{\n  \"theme_color\": \"#008080\",\n  \"name\": \"Distil\"\n}");
+        assert_eq!(patch_manifest("{}", teal).unwrap(), "{\n  \"theme_color\": \"#008080\"}");
+    }
+}
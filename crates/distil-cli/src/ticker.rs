@@ -0,0 +1,61 @@
+//! The JSON lines printed by `distil ticker`.
+
+use std::time::Duration;
+
+use distil_core::palette::PaletteEntry;
+use distil_core::Distil;
+
+/// Describes the palette of the frame at index `frame`, shown at
+/// `timestamp`, as a single line of JSON with the color covering the most
+/// of the frame as `dominant`. Colors that cover none of it are left out.
+pub fn line(frame: usize, timestamp: Duration, palette: &Distil) -> String {
+    let entries: Vec<PaletteEntry> = palette.entries().into_iter().filter(|entry| entry.weight > 0.0).collect();
+
+    let dominant = entries.iter()
+        .max_by(|x, y| x.weight.total_cmp(&y.weight))
+        .map_or("null".to_owned(), |entry| format!("\"{}\"", entry.color));
+
+    let colors: Vec<String> = entries.iter()
+        .map(|entry| format!(r#"{{"hex":"{}","weight":{:.4}}}"#, entry.color, entry.weight))
+        .collect();
+
+    format!("{{\"frame\":{},\"timestamp\":{:.3},\"dominant\":{},\"palette\":[{}]}}",
+            frame,
+            timestamp.as_secs_f64(),
+            dominant,
+            colors.join(","))
+}
+
+/// Returns the timestamp of the frame at index `frame` of frames shown at
+/// `fps` frames per second.
+pub fn timestamp(frame: usize, fps: f32) -> Duration {
+    Duration::from_secs_f64(frame as f64 / fps as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use distil_core::{gen, Color, Distil, IntoLab};
+
+    use super::{line, timestamp};
+
+    #[test]
+    fn lines() {
+        let (grey, teal) = (Color::new(226, 226, 226), Color::new(0, 128, 128));
+        let palette = Distil {
+            colors: vec![grey, teal],
+            counts: vec![200, 1],
+            lab_colors: vec![grey.into_lab(), teal.into_lab()],
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.0, 1.0],
+        };
+
+        assert_eq!(timestamp(3, 25.0), Duration::from_millis(120));
+        assert_eq!(line(3, timestamp(3, 25.0), &palette),
+                   "{\"frame\":3,\"timestamp\":0.120,\"dominant\":\"#008080\",\"palette\":[{\"hex\":\"#008080\",\"weight\":1.0000}]}");
+
+        let solid = Distil::from_image(&gen::solid(16, 16, teal)).unwrap();
+        assert!(line(0, Duration::ZERO, &solid).contains(&format!("\"dominant\":\"{}\"", solid.colors[0])));
+    }
+}
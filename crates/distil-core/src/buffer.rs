@@ -0,0 +1,219 @@
+use std::path::Path;
+
+use crate::imaging;
+use crate::DistilError;
+
+/// An 8-bit RGBA image, stored row by row.
+///
+/// `Image` is how images are passed to and returned from Distil. Decoding,
+/// encoding and resizing are delegated to the `image` crate internally, but
+/// none of its types are exposed unless the `image-interop` feature is
+/// enabled, in which case `Image` converts to and from `image::DynamicImage`.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::Image;
+///
+/// let mut img = Image::new(2, 1);
+/// img.put_pixel(1, 0, [255, 0, 0, 255]);
+///
+/// assert_eq!(img.as_raw(), &[0, 0, 0, 0, 255, 0, 0, 255][..]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a fully transparent image.
+    pub fn new(width: u32, height: u32) -> Image {
+        Image {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Creates an image from RGBA channels, row by row. Fails with
+    /// `DistilError::InvalidBuffer` if `data` isn't `width * height * 4`
+    /// bytes long.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Result<Image, DistilError> {
+        if data.len() != width as usize * height as usize * 4 {
+            return Err(DistilError::InvalidBuffer);
+        }
+
+        Ok(Image {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Creates an opaque image from RGB channels, row by row. Fails with
+    /// `DistilError::InvalidBuffer` if `data` isn't `width * height * 3`
+    /// bytes long.
+    pub fn from_rgb(width: u32, height: u32, data: &[u8]) -> Result<Image, DistilError> {
+        if data.len() != width as usize * height as usize * 3 {
+            return Err(DistilError::InvalidBuffer);
+        }
+
+        let data = data.chunks(3)
+            .flat_map(|rgb| vec![rgb[0], rgb[1], rgb[2], 255])
+            .collect();
+
+        Ok(Image {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Decodes the JPEG, PNG, WebP, GIF, BMP or TIFF at `path`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::Image;
+    ///
+    /// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+    ///     let (width, height) = img.dimensions();
+    /// }
+    /// ```
+    pub fn open(path: &Path) -> Result<Image, DistilError> {
+        imaging::open(path)
+    }
+
+    /// Encodes the image to `path`, in the format given by the extension of
+    /// `path`.
+    pub fn save(&self, path: &Path) -> Result<(), DistilError> {
+        imaging::save(self, path)
+    }
+
+    /// Encodes the image as a PNG in memory, e.g. to send it over HTTP.
+    pub fn to_png(&self) -> Result<Vec<u8>, DistilError> {
+        imaging::encode_png(self)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the `(width, height)` of the image.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the RGBA channels of the pixel at `(x, y)`.
+    ///
+    /// Panics if `(x, y)` is outside of the image.
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.index(x, y);
+        [self.data[i], self.data[i + 1], self.data[i + 2], self.data[i + 3]]
+    }
+
+    /// Sets the RGBA channels of the pixel at `(x, y)`.
+    ///
+    /// Panics if `(x, y)` is outside of the image.
+    pub fn put_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let i = self.index(x, y);
+        self.data[i..i + 4].copy_from_slice(&rgba);
+    }
+
+    /// Iterates over the RGBA channels of every pixel, row by row.
+    pub fn pixels(&self) -> impl Iterator<Item = [u8; 4]> + '_ {
+        self.data.chunks(4).map(|px| [px[0], px[1], px[2], px[3]])
+    }
+
+    /// Iterates over mutable slices of the RGBA channels of every pixel, row
+    /// by row.
+    pub fn pixels_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        self.data.chunks_mut(4)
+    }
+
+    /// Returns a copy of the `width` × `height` region whose top left corner
+    /// is at `(x, y)`, clamped to the image.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Image {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+
+        if width == 0 || height == 0 {
+            return Image::new(width, height);
+        }
+
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+
+        for row in y..y + height {
+            let start = self.index(x, row);
+            data.extend_from_slice(&self.data[start..start + width as usize * 4]);
+        }
+
+        Image {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Returns the RGBA channels of the image, row by row.
+    pub fn as_raw(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consumes the image, returning its RGBA channels row by row.
+    pub fn into_raw(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height,
+                "pixel ({}, {}) is outside of a {}x{} image",
+                x,
+                y,
+                self.width,
+                self.height);
+
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Image;
+
+    #[test]
+    fn buffers() {
+        assert!(Image::from_rgba(2, 2, vec![0; 15]).is_err());
+
+        let img = Image::from_rgb(2, 1, &[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(img.get_pixel(1, 0), [4, 5, 6, 255]);
+        assert_eq!(img.pixels().count(), 2);
+    }
+
+    #[test]
+    fn crop() {
+        let mut img = Image::new(3, 3);
+        img.put_pixel(1, 1, [9, 9, 9, 255]);
+
+        let cropped = img.crop(1, 1, 5, 5);
+        assert_eq!(cropped.dimensions(), (2, 2));
+        assert_eq!(cropped.get_pixel(0, 0), [9, 9, 9, 255]);
+
+        let outside = img.crop(5, 0, 5, 5);
+        assert_eq!(outside.dimensions(), (0, 3));
+        assert!(outside.as_raw().is_empty());
+
+        let empty = Image::new(0, 4).crop(0, 1, 5, 5);
+        assert_eq!(empty.dimensions(), (0, 3));
+        assert!(empty.as_raw().is_empty());
+    }
+}
@@ -0,0 +1,591 @@
+use std::fmt;
+use std::str::FromStr;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use crate::DistilError;
+
+/// An sRGB color.
+///
+/// `Color` can be parsed from the usual CSS notations — `#rgb`, `#rrggbb`,
+/// `rgb(r, g, b)` and `hsl(h, s%, l%)` — and is displayed as a lowercase
+/// hex string.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::Color;
+///
+/// let color: Color = "hsl(180, 100%, 25%)".parse().unwrap();
+///
+/// assert_eq!(color.rgb(), [0, 128, 128]);
+/// assert_eq!(color.to_string(), "#008080");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Color {
+    rgb: [u8; 3],
+}
+
+impl Color {
+    /// Creates a `Color` from its red, green and blue channels.
+    pub const fn new(r: u8, g: u8, b: u8) -> Color {
+        Color { rgb: [r, g, b] }
+    }
+
+    /// Returns the red, green and blue channels of the color.
+    pub fn rgb(&self) -> [u8; 3] {
+        self.rgb
+    }
+
+    /// Returns the Lab value of the color.
+    pub fn lab(&self) -> Lab {
+        Lab::from_rgb(&self.rgb)
+    }
+
+    /// Returns the relative luminance of the color as defined by WCAG,
+    /// between `0.0` for black and `1.0` for white.
+    pub fn luminance(&self) -> f32 {
+        relative_luminance(self.lab())
+    }
+
+    /// Returns the WCAG contrast ratio between the color and `other`, from
+    /// `1.0` (no contrast) to `21.0` (black on white). WCAG AA asks for at
+    /// least `4.5` for body text and `3.0` for large text.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Color;
+    ///
+    /// let is_readable = Color::new(255, 255, 255).contrast_ratio(Color::new(0, 102, 204)) >= 4.5;
+    /// ```
+    pub fn contrast_ratio<C: IntoLab>(&self, other: C) -> f32 {
+        contrast_ratio(*self, other)
+    }
+
+    /// Returns the color as a lowercase `#rrggbb` hex string.
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.rgb[0], self.rgb[1], self.rgb[2])
+    }
+
+    /// Returns the hue (in degrees), saturation and lightness (both between
+    /// `0.0` and `1.0`) of the color.
+    pub fn hsl(&self) -> (f32, f32, f32) {
+        rgb_to_hsl(self.rgb)
+    }
+
+    /// Formats the color as a CSS `rgb(r, g, b)` string.
+    pub fn to_rgb_string(&self) -> String {
+        format!("rgb({}, {}, {})", self.rgb[0], self.rgb[1], self.rgb[2])
+    }
+
+    /// Formats the color as a CSS `hsl(h, s%, l%)` string.
+    pub fn to_hsl_string(&self) -> String {
+        let (h, s, l) = self.hsl();
+        format!("hsl({}, {}%, {}%)", h.round(), (s * 100.0).round(), (l * 100.0).round())
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.hex())
+    }
+}
+
+impl FromStr for Color {
+    type Err = DistilError;
+
+    fn from_str(s: &str) -> Result<Color, DistilError> {
+        let input = s.trim().to_lowercase();
+        let invalid = || DistilError::InvalidColor(s.to_owned());
+
+        if let Some(args) = function_args(&input, "rgb") {
+            if args.len() != 3 {
+                return Err(invalid());
+            }
+
+            let mut rgb = [0; 3];
+
+            for (channel, arg) in rgb.iter_mut().zip(args.iter()) {
+                *channel = arg.parse().map_err(|_| invalid())?;
+            }
+
+            return Ok(Color { rgb });
+        }
+
+        if let Some(args) = function_args(&input, "hsl") {
+            if args.len() != 3 {
+                return Err(invalid());
+            }
+
+            let h: f32 = args[0].trim_end_matches("deg").parse().map_err(|_| invalid())?;
+            let s = parse_percentage(args[1]).ok_or_else(invalid)?;
+            let l = parse_percentage(args[2]).ok_or_else(invalid)?;
+
+            return Ok(Color { rgb: hsl_to_rgb(h, s, l) });
+        }
+
+        parse_hex(&input).map(|rgb| Color { rgb }).ok_or_else(invalid)
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from(rgb: [u8; 3]) -> Color {
+        Color { rgb }
+    }
+}
+
+impl From<Color> for [u8; 3] {
+    fn from(color: Color) -> [u8; 3] {
+        color.rgb
+    }
+}
+
+impl From<Lab> for Color {
+    fn from(lab: Lab) -> Color {
+        Color { rgb: lab_to_rgb(lab) }
+    }
+}
+
+impl IntoLab for Color {
+    fn into_lab(self) -> Lab {
+        self.lab()
+    }
+}
+
+/// Converts a color into Lab so that it can be passed to the color helpers.
+///
+/// It's implemented for `Lab`, `Color` and `[u8; 3]`, so the helpers accept
+/// the colors in `Distil::colors`, the Lab values in `Distil::lab_colors` or
+/// plain RGB channels.
+pub trait IntoLab {
+    fn into_lab(self) -> Lab;
+}
+
+impl IntoLab for Lab {
+    fn into_lab(self) -> Lab {
+        self
+    }
+}
+
+impl IntoLab for [u8; 3] {
+    fn into_lab(self) -> Lab {
+        Lab::from_rgb(&self)
+    }
+}
+
+/// Returns the CIEDE2000 distance between two colors. This is the same
+/// distance Distil uses to decide whether two colors are similar.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::{delta_e, Lab};
+///
+/// let red = Lab::from_rgb(&[255, 0, 0]);
+///
+/// assert_eq!(delta_e(red, [255, 0, 0]), 0.0);
+/// ```
+pub fn delta_e<A: IntoLab, B: IntoLab>(a: A, b: B) -> f32 {
+    DE2000::new(a.into_lab(), b.into_lab())
+}
+
+/// Returns the WCAG contrast ratio between two colors, from `1.0` (no
+/// contrast) to `21.0` (black on white).
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::contrast_ratio;
+///
+/// let ratio = contrast_ratio([0, 0, 0], [255, 255, 255]);
+///
+/// assert!((ratio - 21.0).abs() < 0.01);
+/// ```
+pub fn contrast_ratio<A: IntoLab, B: IntoLab>(a: A, b: B) -> f32 {
+    let luminance_a = relative_luminance(a.into_lab());
+    let luminance_b = relative_luminance(b.into_lab());
+
+    let (lighter, darker) = if luminance_a > luminance_b {
+        (luminance_a, luminance_b)
+    } else {
+        (luminance_b, luminance_a)
+    };
+
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Returns the color opposite the passed one on the hue wheel, keeping its
+/// lightness and chroma.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::complementary;
+///
+/// let complement = complementary([200, 60, 40]);
+/// ```
+pub fn complementary<C: IntoLab>(color: C) -> Lab {
+    rotate_hue(color.into_lab(), 180.0)
+}
+
+/// Returns the two colors either side of the passed one on the hue wheel,
+/// `angle` degrees away.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::analogous;
+///
+/// let [left, right] = analogous([200, 60, 40], 30.0);
+/// ```
+pub fn analogous<C: IntoLab>(color: C, angle: f32) -> [Lab; 2] {
+    let lab = color.into_lab();
+    [rotate_hue(lab, -angle), rotate_hue(lab, angle)]
+}
+
+/// Returns the two colors that complete a triad with the passed one, evenly
+/// spaced around the hue wheel.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::triadic;
+///
+/// let [second, third] = triadic([200, 60, 40]);
+/// ```
+pub fn triadic<C: IntoLab>(color: C) -> [Lab; 2] {
+    let lab = color.into_lab();
+    [rotate_hue(lab, 120.0), rotate_hue(lab, 240.0)]
+}
+
+/// Rotates the hue of the passed color by `degrees` in the a*b* plane.
+fn rotate_hue(lab: Lab, degrees: f32) -> Lab {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+
+    Lab {
+        l: lab.l,
+        a: lab.a * cos - lab.b * sin,
+        b: lab.a * sin + lab.b * cos,
+    }
+}
+
+/// Returns the relative luminance (CIE Y, between `0.0` and `1.0`) of the
+/// passed color by inverting the L* transfer function.
+fn relative_luminance(lab: Lab) -> f32 {
+    let fy = (lab.l + 16.0) / 116.0;
+
+    if lab.l > 8.0 {
+        fy * fy * fy
+    } else {
+        lab.l / 903.3
+    }
+}
+
+/// Converts sRGB channels between `0.0` and `1.0` into Lab without rounding
+/// them to `u8` first.
+pub fn srgb_to_lab(rgb: [f32; 3]) -> Lab {
+    let linear = |c: f32| if c > 0.04045 { ((c + 0.055) / 1.055).powf(2.4) } else { c / 12.92 };
+    let (r, g, b) = (linear(rgb[0]), linear(rgb[1]), linear(rgb[2]));
+
+    let x = (r * 0.4124 + g * 0.3576 + b * 0.1805) / 0.95047;
+    let y = r * 0.2126 + g * 0.7152 + b * 0.0722;
+    let z = (r * 0.0193 + g * 0.1192 + b * 0.9505) / 1.08883;
+
+    let f = |c: f32| if c > 0.008856 { c.cbrt() } else { c * 7.787 + 16.0 / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+
+    Lab {
+        l: 116.0 * fy - 16.0,
+        a: 500.0 * (fx - fy),
+        b: 200.0 * (fy - fz),
+    }
+}
+
+/// Converts a Lab color into sRGB channels between `0.0` and `1.0` without
+/// rounding them to `u8`. Out of gamut colors are clamped.
+pub fn lab_to_srgb(lab: Lab) -> [f32; 3] {
+    lab_to_unclamped_srgb(lab).map(|c| clamp_channel(c) as f32)
+}
+
+/// Converts a Lab color into RGB, and is what every Lab to RGB conversion in
+/// Distil goes through.
+///
+/// The rounding policy is:
+///
+/// - The conversion is done in `f64`, so that an sRGB color converted into
+///   Lab with `Lab::from_rgb` converts back to exactly the same channels.
+/// - Out of gamut channels are clamped to `0.0..=1.0` before being scaled,
+///   and channels that aren't a number become `0`.
+/// - Channels are then scaled to `0.0..=255.0` and rounded half away from
+///   zero.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::{lab_to_rgb, Lab};
+///
+/// assert_eq!(lab_to_rgb(Lab::from_rgb(&[0, 128, 128])), [0, 128, 128]);
+/// assert_eq!(lab_to_rgb(Lab { l: 150.0, a: 0.0, b: 0.0 }), [255, 255, 255]);
+/// ```
+pub fn lab_to_rgb(lab: Lab) -> [u8; 3] {
+    lab_to_unclamped_srgb(lab).map(|c| (clamp_channel(c) * 255.0).round() as u8)
+}
+
+/// Converts a Lab color into linear-light RGB channels between `0.0` and
+/// `1.0`, for renderers that blend in linear light. Out of gamut colors are
+/// clamped.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::{lab_to_linear_rgb, Lab};
+///
+/// let linear = lab_to_linear_rgb(Lab::from_rgb(&[128, 128, 128]));
+/// assert!(linear.iter().all(|channel| (channel - 0.2159).abs() < 0.001));
+/// ```
+pub fn lab_to_linear_rgb(lab: Lab) -> [f32; 3] {
+    lab_to_unclamped_linear_rgb(lab).map(|c| clamp_channel(c) as f32)
+}
+
+fn lab_to_unclamped_srgb(lab: Lab) -> [f64; 3] {
+    let gamma = |c: f64| if c > 0.0031308 { 1.055 * c.powf(1.0 / 2.4) - 0.055 } else { 12.92 * c };
+    lab_to_unclamped_linear_rgb(lab).map(gamma)
+}
+
+fn lab_to_unclamped_linear_rgb(lab: Lab) -> [f64; 3] {
+    let (l, a, b) = (lab.l as f64, lab.a as f64, lab.b as f64);
+
+    let fy = (l + 16.0) / 116.0;
+    let fx = a / 500.0 + fy;
+    let fz = fy - b / 200.0;
+
+    let f_inv = |c: f64| if c.powi(3) > 0.008856 { c.powi(3) } else { (c * 116.0 - 16.0) / 903.3 };
+    let x = f_inv(fx) * 0.95047;
+    let y = if l > 0.008856 * 903.3 { fy.powi(3) } else { l / 903.3 };
+    let z = f_inv(fz) * 1.08883;
+
+    [
+        x * 3.2406 + y * -1.5372 + z * -0.4986,
+        x * -0.9689 + y * 1.8758 + z * 0.0415,
+        x * 0.0557 + y * -0.2040 + z * 1.0570,
+    ]
+}
+
+fn clamp_channel(c: f64) -> f64 {
+    if c.is_nan() {
+        return 0.0;
+    }
+
+    c.clamp(0.0, 1.0)
+}
+
+/// Returns the comma separated arguments of `input` if it's a call to the CSS
+/// function `name`, e.g. `rgb(1, 2, 3)`.
+fn function_args<'a>(input: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    if !input.starts_with(name) {
+        return None;
+    }
+
+    let rest = input[name.len()..].trim_start();
+
+    if !rest.starts_with('(') || !rest.ends_with(')') {
+        return None;
+    }
+
+    Some(rest[1..rest.len() - 1].split(',').map(|arg| arg.trim()).collect())
+}
+
+fn parse_percentage(arg: &str) -> Option<f32> {
+    if !arg.ends_with('%') {
+        return None;
+    }
+
+    match arg[..arg.len() - 1].parse::<f32>() {
+        Ok(value) if (0.0..=100.0).contains(&value) => Some(value / 100.0),
+        _ => None,
+    }
+}
+
+fn parse_hex(input: &str) -> Option<[u8; 3]> {
+    let hex = input.trim_start_matches('#');
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    match hex.len() {
+        3 => {
+            let mut rgb = [0; 3];
+
+            for (channel, digit) in rgb.iter_mut().zip(hex.chars()) {
+                let value = digit.to_digit(16)? as u8;
+                *channel = value * 16 + value;
+            }
+
+            Some(rgb)
+        }
+        6 => {
+            let mut rgb = [0; 3];
+
+            for (i, channel) in rgb.iter_mut().enumerate() {
+                *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+            }
+
+            Some(rgb)
+        }
+        _ => None,
+    }
+}
+
+fn rgb_to_hsl(rgb: [u8; 3]) -> (f32, f32, f32) {
+    let r = rgb[0] as f32 / 255.0;
+    let g = rgb[1] as f32 / 255.0;
+    let b = rgb[2] as f32 / 255.0;
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, l);
+    }
+
+    let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+
+    let h = if max == r {
+        60.0 * (((g - b) / delta) % 6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (if h < 0.0 { h + 360.0 } else { h }, s, l)
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> [u8; 3] {
+    let h = ((h % 360.0) + 360.0) % 360.0;
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+
+    [
+        ((r + m) * 255.0).round() as u8,
+        ((g + m) * 255.0).round() as u8,
+        ((b + m) * 255.0).round() as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use lab::Lab;
+
+    use super::{complementary, contrast_ratio, delta_e, lab_to_linear_rgb, lab_to_rgb, Color};
+
+    fn assert_round_trips(step: usize) {
+        for r in (0..=255).step_by(step) {
+            for g in (0..=255).step_by(step) {
+                for b in (0..=255).step_by(step) {
+                    let rgb = [r as u8, g as u8, b as u8];
+                    assert_eq!(lab_to_rgb(Lab::from_rgb(&rgb)), rgb);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rounding() {
+        assert_round_trips(5);
+
+        for &(lab, rgb) in &[(Lab { l: 0.0, a: 0.0, b: 0.0 }, [0, 0, 0]),
+                             (Lab { l: 100.0, a: 0.0, b: 0.0 }, [255, 255, 255]),
+                             (Lab { l: 150.0, a: 0.0, b: 0.0 }, [255, 255, 255]),
+                             (Lab { l: -20.0, a: 0.0, b: 0.0 }, [0, 0, 0]),
+                             (Lab { l: 50.0, a: 200.0, b: 0.0 }, [255, 0, 130]),
+                             (Lab { l: f32::NAN, a: 0.0, b: 0.0 }, [0, 0, 0])] {
+            assert_eq!(lab_to_rgb(lab), rgb);
+        }
+    }
+
+    #[test]
+    fn linear() {
+        let decode = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c > 0.04045 { ((c + 0.055) / 1.055).powf(2.4) } else { c / 12.92 }
+        };
+
+        for rgb in [[0, 0, 0], [255, 255, 255], [10, 128, 240], [200, 30, 90]] {
+            let linear = lab_to_linear_rgb(Lab::from_rgb(&rgb));
+
+            for (channel, expected) in linear.iter().zip(rgb.map(decode)) {
+                assert!((channel - expected).abs() < 0.001);
+            }
+        }
+    }
+
+    /// Checks every one of the 16.7M sRGB colors. Run it with
+    /// `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn rounding_exhaustive() {
+        assert_round_trips(1);
+    }
+
+    #[test]
+    fn parse() {
+        let teal = Color::new(0, 128, 128);
+
+        assert_eq!("#008080".parse::<Color>().unwrap(), teal);
+        assert_eq!("008080".parse::<Color>().unwrap(), teal);
+        assert_eq!("#FFF".parse::<Color>().unwrap(), Color::new(255, 255, 255));
+        assert_eq!("rgb(0, 128, 128)".parse::<Color>().unwrap(), teal);
+        assert_eq!("hsl(180, 100%, 25%)".parse::<Color>().unwrap(), teal);
+
+        assert!("#00808".parse::<Color>().is_err());
+        assert!("rgb(0, 128)".parse::<Color>().is_err());
+        assert!("rgb(0, 128, 256)".parse::<Color>().is_err());
+        assert!("hsl(180, 100, 25)".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn format() {
+        let coral = Color::new(255, 127, 80);
+
+        assert_eq!(coral.to_string(), "#ff7f50");
+        assert_eq!(coral.to_rgb_string(), "rgb(255, 127, 80)");
+        assert_eq!(coral.to_hsl_string(), "hsl(16, 100%, 66%)");
+    }
+
+    #[test]
+    fn accessors() {
+        let coral = Color::new(255, 127, 80);
+
+        assert_eq!(lab_to_rgb(coral.lab()), coral.rgb());
+        assert!((Color::new(255, 255, 255).luminance() - 1.0).abs() < 1e-3);
+        assert!(Color::new(0, 0, 0).luminance().abs() < 1e-3);
+        assert!((coral.luminance() - 0.3702).abs() < 1e-3);
+        assert!((Color::new(0, 0, 0).contrast_ratio(Color::new(255, 255, 255)) - 21.0).abs() < 0.01);
+        assert_eq!(coral.contrast_ratio([0, 0, 0]), contrast_ratio([0, 0, 0], coral));
+    }
+
+    #[test]
+    fn color_helpers() {
+        let teal = [0, 128, 128];
+
+        assert!(delta_e(teal, complementary(teal)) > 10.0);
+        assert!(delta_e(teal, complementary(complementary(teal))) < 0.01);
+        assert!((contrast_ratio([255, 255, 255], [255, 255, 255]) - 1.0).abs() < 0.01);
+    }
+}
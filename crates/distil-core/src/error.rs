@@ -0,0 +1,61 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// The errors Distil can produce.
+#[derive(Debug)]
+pub enum DistilError {
+    /// Produced when Distil fails to parse the passed path.
+    Io(String, String),
+
+    /// Produced when Distil fails to write to the passed path.
+    Output(String, io::Error),
+
+    /// Produced when the image passed isn't a JPEG, a PNG, a WebP, a GIF, a
+    /// BMP or a TIFF.
+    UnsupportedFormat,
+
+    /// Produced when a buffer's length doesn't match the dimensions of the
+    /// `Image` it's meant to become.
+    InvalidBuffer,
+
+    /// Produced when a string can't be parsed into a `Color`.
+    InvalidColor(String),
+
+    /// Produced when Distil can't find any "interesting" colours in a passed image. Colours
+    /// are deemed "interesting" if they fall between RGB(8, 8, 8) and RGB(247, 247, 247).
+    Uninteresting,
+
+    /// Produced when a fixed number of colors is asked of a palette that has
+    /// fewer. Holds the number asked for and the number available.
+    TooFewColors(usize, usize),
+}
+
+impl fmt::Display for DistilError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DistilError::Io(_, ref err) => write!(f, "Distil failed to parse the passed image: {}", err),
+            DistilError::Output(ref path, ref err) => write!(f, "Distil failed to write to {}: {}", path, err),
+            DistilError::UnsupportedFormat => {
+                write!(f, "The passed image isn't a JPEG, a PNG, a WebP, a GIF, a BMP or a TIFF")
+            }
+            DistilError::InvalidBuffer => write!(f, "The buffer's length doesn't match the image's dimensions"),
+            DistilError::InvalidColor(ref input) => {
+                write!(f, "`{}` isn't a valid hex, rgb() or hsl() color", input)
+            }
+            DistilError::Uninteresting => write!(f, "The passed image does not contain any interesting colours"),
+            DistilError::TooFewColors(wanted, found) => {
+                write!(f, "{} colors were asked for but the palette only has {}", wanted, found)
+            }
+        }
+    }
+}
+
+impl Error for DistilError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DistilError::Output(_, ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::{extract_colors, get_pixels, pixel_members, DistilBuilder, GreedyMerge};
+use crate::palette::{by_weight, distil_palette, with_members_by};
+use crate::{Color, Distil, DistilError, Image, IntoLab};
+
+/// A palette seeded with anchor colors, as returned by
+/// `Distil::from_image_with_anchors`.
+#[derive(Debug, Clone)]
+pub struct AnchoredDistil {
+    /// The palette, starting with the anchors in the order they were passed
+    /// followed by the image's other colors from most to least frequent.
+    pub distil: Distil,
+
+    /// The share of the image's interesting pixels, between `0.0` and `1.0`,
+    /// that are close enough to each anchor to be considered that color, at
+    /// the same index as the anchors. An anchor the image doesn't use has a
+    /// coverage of `0.0`.
+    pub coverage: Vec<f32>,
+}
+
+impl Distil {
+    /// `from_image_with_anchors` `Distil`s an image around `anchors`, e.g. a
+    /// brand's colors, which always appear at the start of the palette
+    /// exactly as passed.
+    ///
+    /// The quantized colors of the image that are similar to an anchor are
+    /// counted towards it rather than kept, so the rest of the palette is
+    /// made of the most frequent colors that don't conflict with any anchor.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Color, Distil, Image};
+    ///
+    /// let brand = Color::new(203, 75, 22);
+    ///
+    /// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+    ///     let anchored = Distil::from_image_with_anchors(&img, &[brand]).unwrap();
+    ///     let brand_coverage = anchored.coverage[0];
+    /// }
+    /// ```
+    pub fn from_image_with_anchors(img: &Image, anchors: &[Color]) -> Result<AnchoredDistil, DistilError> {
+        let config = DistilBuilder::default();
+        let anchor_labs: Vec<Lab> = anchors.iter().map(|anchor| anchor.into_lab()).collect();
+        let nearest_anchor = |lab: Lab| {
+            anchor_labs.iter()
+                .map(|&anchor| DE2000::new(lab, anchor))
+                .enumerate()
+                .filter(|&(_, delta)| delta < config.min_distance)
+                .min_by(|&(_, a), &(_, b)| a.total_cmp(&b))
+                .map(|(i, _)| i)
+        };
+
+        let scaled_img = config.sampled(img);
+        let mut anchor_counts = vec![0; anchors.len()];
+        let mut others = Vec::new();
+
+        for (lab, count) in extract_colors(&scaled_img, &config)? {
+            match nearest_anchor(lab) {
+                Some(i) => anchor_counts[i] += count,
+                None => others.push((lab, count)),
+            }
+        }
+
+        let mut pixel_counts = vec![0; anchors.len()];
+        let pixels = get_pixels(&scaled_img, &config)?;
+
+        for rgba in pixels.chunks(4) {
+            if let Some(i) = nearest_anchor(Lab::from_rgb(&[rgba[0], rgba[1], rgba[2]])) {
+                pixel_counts[i] += 1;
+            }
+        }
+
+        let total = (pixels.len() / 4) as f32;
+        let palette = anchor_labs.into_iter()
+            .zip(anchor_counts)
+            .chain(config.merged(others, &mut Vec::new()))
+            .collect();
+
+        Ok(AnchoredDistil {
+            distil: by_weight(with_members_by(distil_palette(palette), &pixel_members(&scaled_img, &config), &GreedyMerge),
+                              anchors.len()),
+            coverage: pixel_counts.iter().map(|&count| count as f32 / total).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use delta_e::DE2000;
+
+    use crate::{gen, Color, Distil, IntoLab};
+
+    #[test]
+    fn anchors() {
+        let red = Color::new(200, 40, 40);
+        let blue = Color::new(40, 60, 200);
+        let img = gen::blocks(40, 20, &[(Color::new(205, 45, 40), 1), (blue, 3)]);
+
+        let anchored = Distil::from_image_with_anchors(&img, &[red, Color::new(20, 200, 60)]).unwrap();
+
+        assert_eq!(anchored.distil.colors[0], red);
+        assert_eq!(anchored.distil.colors[1], Color::new(20, 200, 60));
+        assert_eq!(anchored.coverage, vec![0.25, 0.0]);
+
+        assert!(anchored.distil.lab_colors[2..].iter().all(|&lab| DE2000::new(lab, red.into_lab()) >= 10.0));
+        assert!(anchored.distil.lab_colors[2..].iter().any(|&lab| DE2000::new(lab, blue.into_lab()) < 2.0));
+    }
+}
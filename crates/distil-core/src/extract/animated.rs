@@ -0,0 +1,108 @@
+use std::path::Path;
+use std::time::Duration;
+
+use super::histogram::Histogram;
+use super::{distil_colors, scale_img, MAX_SAMPLE_COUNT};
+use crate::{imaging, Distil, DistilError};
+
+/// Frames shown for less than this are shown for `DEFAULT_FRAME_DELAY`
+/// instead, as browsers do.
+static MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// How long browsers show frames with little or no delay for.
+static DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// The palette of one frame of an animated GIF, as returned by
+/// `Distil::from_gif_frames`.
+#[derive(Debug, Clone)]
+pub struct GifFrame {
+    /// The palette of the frame, composited onto the frames before it, or
+    /// `None` if it has no interesting colors.
+    pub distil: Option<Distil>,
+
+    /// How long the frame is shown for.
+    pub delay: Duration,
+}
+
+impl Distil {
+    /// `from_gif` distils every frame of the GIF at `path` into a single
+    /// palette, weighting each frame by how long it's shown for. Frames are
+    /// decoded one at a time and counted into a histogram, so long
+    /// animations don't need to fit in memory.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_gif(Path::new("./tests/animated.gif")) {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn from_gif(path: &Path) -> Result<Distil, DistilError> {
+        let mut histogram = Histogram::default();
+
+        for frame in imaging::gif_frames(path)? {
+            let (img, delay) = frame?;
+            histogram.add_image_weighted(&scale_img(&img, MAX_SAMPLE_COUNT), displayed_for(delay).as_millis() as u64);
+        }
+
+        Ok(distil_colors(histogram.colors()?))
+    }
+
+    /// `from_gif_frames` distils each frame of the GIF at `path` into its
+    /// own palette, along with the frame's delay.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(frames) = Distil::from_gif_frames(Path::new("./tests/animated.gif")) {
+    ///     for frame in frames {
+    ///         let dominant = frame.distil.map(|distilled| distilled.colors[0]);
+    ///     }
+    /// }
+    /// ```
+    pub fn from_gif_frames(path: &Path) -> Result<Vec<GifFrame>, DistilError> {
+        imaging::gif_frames(path)?
+            .map(|frame| {
+                let (img, delay) = frame?;
+
+                Ok(GifFrame {
+                    distil: Distil::from_image(&img).ok(),
+                    delay,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns how long a frame with `delay` is actually shown for.
+fn displayed_for(delay: Duration) -> Duration {
+    if delay < MIN_FRAME_DELAY {
+        DEFAULT_FRAME_DELAY
+    } else {
+        delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::Distil;
+
+    #[test]
+    fn gif() {
+        let path = Path::new("./tests/animated.gif");
+        let frames = Distil::from_gif_frames(path).unwrap();
+        let distilled = Distil::from_gif(path).unwrap();
+
+        assert!(frames.len() > 1);
+        assert!(frames.iter().all(|frame| frame.distil.is_some()));
+        assert!(!distilled.colors.is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+use delta_e::DE2000;
+use super::kmeans::histogram;
+use super::{distil_colors, has_transparency, DistilBuilder, Region, MIN_ALPHA};
+use crate::{Color, Distil, DistilError, Image, IntoLab};
+
+/// The share of the width and height of an image, on each side, that makes
+/// up its border.
+static BORDER: f32 = 0.05;
+
+/// The Lab chroma a color needs to be considered an accent.
+static MIN_ACCENT_CHROMA: f32 = 30.0;
+
+impl Distil {
+    /// Returns the background color of `img`: the most frequent color of
+    /// the pixels along its edges, 5% of its width and height deep. Unlike
+    /// the palette, near-black and near-white pixels aren't filtered out, as
+    /// they're the most common backgrounds of all.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Distil, Image};
+    ///
+    /// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+    ///     let background = Distil::background(&img).unwrap();
+    /// }
+    /// ```
+    pub fn background(img: &Image) -> Result<Color, DistilError> {
+        let sampled = DistilBuilder::default().sampled(img);
+        let (width, height) = sampled.dimensions();
+        let (border_x, border_y) = border(width, height);
+
+        let mut pixels = Vec::new();
+
+        for y in 0..height {
+            for x in 0..width {
+                let rgba = sampled.get_pixel(x, y);
+                let on_border = x < border_x || y < border_y || x >= width - border_x || y >= height - border_y;
+
+                if on_border && !has_transparency(&rgba, MIN_ALPHA) {
+                    pixels.extend_from_slice(&rgba);
+                }
+            }
+        }
+
+        let (buckets, _) = histogram(&pixels);
+
+        if buckets.is_empty() {
+            return Err(DistilError::Uninteresting);
+        }
+
+        let colors = buckets.into_iter().map(|(lab, count)| (lab, count as usize)).collect();
+
+        Ok(distil_colors(colors).colors[0])
+    }
+
+    /// Returns the accent colors of `img`, from most to least frequent: the
+    /// saturated colors of its interior, within the border `background`
+    /// samples, that are distinct from its background.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Distil, Image};
+    ///
+    /// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+    ///     if let Some(accent) = Distil::accents(&img).unwrap().first() {
+    ///         println!("Accent color: {}", accent);
+    ///     }
+    /// }
+    /// ```
+    pub fn accents(img: &Image) -> Result<Vec<Color>, DistilError> {
+        let (width, height) = img.dimensions();
+        let (border_x, border_y) = border(width, height);
+
+        let builder = DistilBuilder::default().regions(&[
+            Region::exclude(0, 0, width, border_y),
+            Region::exclude(0, height - border_y, width, border_y),
+            Region::exclude(0, 0, border_x, height),
+            Region::exclude(width - border_x, 0, border_x, height),
+        ]);
+
+        let background = Distil::background(img)?.into_lab();
+        let interior = builder.from_image(img)?;
+
+        Ok(interior.colors.iter()
+            .zip(&interior.lab_colors)
+            .filter(|&(_, &lab)| (lab.a * lab.a + lab.b * lab.b).sqrt() >= MIN_ACCENT_CHROMA)
+            .filter(|&(_, &lab)| DE2000::new(lab, background) >= builder.min_distance)
+            .map(|(&color, _)| color)
+            .collect())
+    }
+}
+
+/// Returns how many pixels deep the border of an image of `width` by
+/// `height` is, horizontally and vertically: at least one pixel, unless the
+/// image is too small to have an interior.
+fn border(width: u32, height: u32) -> (u32, u32) {
+    let depth = |side: u32| ((side as f32 * BORDER).ceil() as u32).max(1).min(side / 2);
+
+    (depth(width), depth(height))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{delta_e, Color, Distil, Image};
+
+    #[test]
+    fn background_and_accents() {
+        let white = Color::new(250, 250, 250);
+        let red = Color::new(210, 40, 50);
+        let slate = Color::new(110, 120, 130);
+        let mut img = Image::new(60, 60);
+
+        for y in 0..60 {
+            for x in 0..60 {
+                let color = match (x, y) {
+                    (6..=53, 6..=53) if x < 30 => red,
+                    (6..=53, 6..=53) => slate,
+                    _ => white,
+                };
+
+                let [r, g, b]: [u8; 3] = color.into();
+                img.put_pixel(x, y, [r, g, b, 255]);
+            }
+        }
+
+        assert!(delta_e(Distil::background(&img).unwrap(), white) < 2.0);
+
+        let accents = Distil::accents(&img).unwrap();
+        assert!(delta_e(accents[0], red) < 5.0);
+        assert!(accents.iter().all(|&accent| delta_e(accent, slate) > 10.0 && delta_e(accent, white) > 10.0));
+    }
+}
@@ -0,0 +1,342 @@
+use std::io::Read;
+use std::path::Path;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::debug::Filtered;
+use super::filter::PixelFilter;
+use super::key::ChromaKey;
+use super::lock::Lock;
+use super::merge::MergeStrategy;
+use super::plugin::Plugin;
+use super::quantizer::Quantizer;
+use super::regions::Region;
+use super::sampling::SamplingStrategy;
+#[cfg(feature = "neuquant")]
+use super::{NQ_PALETTE_SIZE, NQ_SAMPLE_FACTION};
+use super::{has_transparency, is_black, is_white, MAX_SAMPLE_COUNT, MIN_ALPHA, MIN_BLACK, MIN_DISTANCE_FOR_UNIQUENESS, MAX_WHITE};
+use crate::{imaging, Color, Distil, DistilError, Format, Image, IntoLab};
+
+/// Configures how an image is distilled, for when the defaults used by
+/// `Distil::from_path` and friends don't suit the images at hand. Created
+/// with `Distil::builder`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::Distil;
+///
+/// let path = Path::new("./images/img-1.jpg");
+///
+/// if let Ok(distilled) = Distil::builder().sample_count(4000).min_distance(15.0).from_path(&path) {
+///     // Do something with the returned `Distil` struct…
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistilBuilder {
+    pub(super) sample_count: u32,
+    pub(super) min_alpha: u8,
+    pub(super) alpha_weighted: bool,
+    pub(super) min_black: u8,
+    pub(super) max_white: u8,
+    pub(super) min_distance: f32,
+    pub(super) excluded: Vec<(Lab, f32)>,
+    pub(super) key: Option<(ChromaKey, f32)>,
+    pub(super) regions: Vec<Region>,
+    pub(super) sampling: SamplingStrategy,
+    pub(super) pixel_filter: Plugin<dyn PixelFilter>,
+    pub(super) merge_strategy: Plugin<dyn MergeStrategy>,
+    pub(super) quantizer: Plugin<dyn Quantizer>,
+    pub(super) deterministic: bool,
+    pub(super) prior: Vec<Lab>,
+    pub(super) locked: Vec<Lock>,
+    pub(super) formats: Option<Vec<Format>>,
+    #[cfg(feature = "neuquant")]
+    pub(super) sample_faction: i32,
+    #[cfg(feature = "neuquant")]
+    pub(super) palette_size: usize,
+}
+
+impl Default for DistilBuilder {
+    fn default() -> DistilBuilder {
+        DistilBuilder {
+            sample_count: MAX_SAMPLE_COUNT,
+            min_alpha: MIN_ALPHA,
+            alpha_weighted: false,
+            min_black: MIN_BLACK,
+            max_white: MAX_WHITE,
+            min_distance: MIN_DISTANCE_FOR_UNIQUENESS,
+            excluded: Vec::new(),
+            key: None,
+            regions: Vec::new(),
+            sampling: SamplingStrategy::Uniform,
+            pixel_filter: Plugin::default(),
+            merge_strategy: Plugin::default(),
+            quantizer: Plugin::default(),
+            deterministic: false,
+            prior: Vec::new(),
+            locked: Vec::new(),
+            formats: None,
+            #[cfg(feature = "neuquant")]
+            sample_faction: NQ_SAMPLE_FACTION,
+            #[cfg(feature = "neuquant")]
+            palette_size: NQ_PALETTE_SIZE,
+        }
+    }
+}
+
+impl Distil {
+    /// Returns a `DistilBuilder` with the default configuration.
+    pub fn builder() -> DistilBuilder {
+        DistilBuilder::default()
+    }
+}
+
+impl DistilBuilder {
+    /// Sets the maximum number of pixels the image is scaled down to before
+    /// being quantized. Defaults to 1000.
+    pub fn sample_count(mut self, sample_count: u32) -> DistilBuilder {
+        self.sample_count = sample_count.max(1);
+        self
+    }
+
+    /// Sets the alpha a pixel needs for it not to be ignored as transparent,
+    /// e.g. `128` to keep the anti-aliased edges of a logo. `0` keeps every
+    /// pixel. Defaults to 255, only keeping opaque pixels.
+    pub fn min_alpha(mut self, min_alpha: u8) -> DistilBuilder {
+        self.min_alpha = min_alpha;
+        self
+    }
+
+    /// Counts each pixel that isn't ignored as transparent in proportion to
+    /// its alpha, so a pixel at half opacity counts half as much as an
+    /// opaque one. Usually combined with a lower `min_alpha`. Off by default.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let builder = Distil::builder().min_alpha(1).alpha_weighted(true);
+    ///
+    /// if let Ok(distilled) = builder.from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn alpha_weighted(mut self, alpha_weighted: bool) -> DistilBuilder {
+        self.alpha_weighted = alpha_weighted;
+        self
+    }
+
+    /// Returns how much a pixel with `alpha` counts while sampling.
+    pub(super) fn alpha_weight(&self, alpha: u8) -> f32 {
+        if self.alpha_weighted {
+            alpha as f32 / 255.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Sets the value all three channels of a pixel have to be under for it
+    /// to be ignored as black. Defaults to 8.
+    pub fn min_black(mut self, min_black: u8) -> DistilBuilder {
+        self.min_black = min_black;
+        self
+    }
+
+    /// Sets the value all three channels of a pixel have to be over for it
+    /// to be ignored as white. Defaults to 247.
+    pub fn max_white(mut self, max_white: u8) -> DistilBuilder {
+        self.max_white = max_white;
+        self
+    }
+
+    /// Sets the CIEDE2000 distance under which two colors are merged into
+    /// one. Defaults to 10.0.
+    pub fn min_distance(mut self, min_distance: f32) -> DistilBuilder {
+        self.min_distance = min_distance.max(0.0);
+        self
+    }
+
+    /// Ignores pixels within a CIEDE2000 distance of `tolerance` of any of
+    /// `colors` while sampling, e.g. a watermark, a chroma-key green or a
+    /// scanner's backing, so they never make it into the palette. Replaces
+    /// any colors excluded before.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{Color, Distil};
+    ///
+    /// let chroma_key = Color::new(0, 177, 64);
+    /// let builder = Distil::builder().exclude_colors(&[chroma_key], 12.0);
+    ///
+    /// if let Ok(distilled) = builder.from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn exclude_colors(mut self, colors: &[Color], tolerance: f32) -> DistilBuilder {
+        self.excluded = colors.iter().map(|color| (color.into_lab(), tolerance.max(0.0))).collect();
+        self
+    }
+
+    /// Returns `true` if `lab` is close to one of the excluded colors.
+    pub(super) fn is_excluded(&self, lab: Lab) -> bool {
+        self.excluded.iter().any(|&(excluded, tolerance)| DE2000::new(lab, excluded) < tolerance)
+    }
+
+    /// Returns why `rgba` is left out while sampling, or `None` if it isn't.
+    pub(super) fn filtered(&self, rgba: &[u8; 4]) -> Option<Filtered> {
+        if has_transparency(rgba, self.min_alpha) {
+            Some(Filtered::Transparent)
+        } else if is_black(rgba, self.min_black) {
+            Some(Filtered::Black)
+        } else if is_white(rgba, self.max_white) {
+            Some(Filtered::White)
+        } else if !self.excluded.is_empty() && self.is_excluded(Lab::from_rgb(&[rgba[0], rgba[1], rgba[2]])) {
+            Some(Filtered::Excluded)
+        } else if !self.is_kept(rgba) {
+            Some(Filtered::Custom)
+        } else {
+            None
+        }
+    }
+
+    /// Sets how many pixels NeuQuant skips while learning, between 1 (every
+    /// pixel, slowest) and 30. Defaults to 10. Only used when no other
+    /// `quantizer` is set.
+    #[cfg(feature = "neuquant")]
+    pub fn sample_faction(mut self, sample_faction: i32) -> DistilBuilder {
+        self.sample_faction = sample_faction.clamp(1, 30);
+        self
+    }
+
+    /// Sets the number of colors NeuQuant reduces the image to before
+    /// similar ones are merged, between 64 and 256. Defaults to 256. Only
+    /// used when no other `quantizer` is set.
+    #[cfg(feature = "neuquant")]
+    pub fn palette_size(mut self, palette_size: usize) -> DistilBuilder {
+        self.palette_size = palette_size.clamp(64, 256);
+        self
+    }
+
+    /// Only decodes images in one of `formats` in `from_path`, `from_bytes`
+    /// and `from_reader`, failing with `DistilError::UnsupportedFormat`
+    /// otherwise, e.g. to stick to the formats a service promises to
+    /// support. The format is guessed from the contents of the image.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{Distil, Format};
+    ///
+    /// let builder = Distil::builder().allow_formats(&[Format::Jpeg, Format::Png]);
+    ///
+    /// if let Ok(distilled) = builder.from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn allow_formats(mut self, formats: &[Format]) -> DistilBuilder {
+        self.formats = Some(formats.to_vec());
+        self
+    }
+
+    /// Decodes images in any format Distil can decode, for services that
+    /// already validate uploads elsewhere. Undoes `allow_formats`, and is
+    /// the default.
+    pub fn allow_any_decodable(mut self) -> DistilBuilder {
+        self.formats = None;
+        self
+    }
+
+    /// Like `Distil::from_path_str`, with this configuration.
+    pub fn from_path_str(&self, path_str: &str) -> Result<Distil, DistilError> {
+        self.from_path(Path::new(path_str))
+    }
+
+    /// Like `Distil::from_path`, with this configuration.
+    pub fn from_path(&self, path: &Path) -> Result<Distil, DistilError> {
+        let img = imaging::open_as(path, self.formats.as_deref())?;
+        self.from_image(&img)
+    }
+
+    /// Like `Distil::from_bytes`, with this configuration.
+    pub fn from_bytes(&self, bytes: &[u8]) -> Result<Distil, DistilError> {
+        let img = imaging::decode_as(bytes, "bytes", self.formats.as_deref())?;
+        self.from_image(&img)
+    }
+
+    /// Like `Distil::from_reader`, with this configuration.
+    pub fn from_reader<R: Read>(&self, mut reader: R) -> Result<Distil, DistilError> {
+        let mut bytes = Vec::new();
+
+        reader.read_to_end(&mut bytes)
+            .map_err(|err| DistilError::Io("reader".to_owned(), err.to_string()))?;
+
+        let img = imaging::decode_as(&bytes, "reader", self.formats.as_deref())?;
+        self.from_image(&img)
+    }
+
+    /// Like `Distil::from_image`, with this configuration.
+    pub fn from_image(&self, img: &Image) -> Result<Distil, DistilError> {
+        Distil::new_with(img, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::get_pixels;
+    use crate::{gen, Color, Distil, DistilError, Format};
+
+    #[test]
+    fn builder() {
+        let img = gen::blocks(60, 20, &[(Color::new(200, 60, 60), 1), (Color::new(4, 4, 4), 1), (Color::new(252, 252, 252), 1)]);
+
+        let default = Distil::from_image(&img).unwrap();
+        assert_eq!(Distil::builder().from_image(&img).unwrap().colors, default.colors);
+
+        assert_eq!(get_pixels(&img, &Distil::builder()).unwrap().len(), 400 * 4);
+        assert_eq!(get_pixels(&img, &Distil::builder().min_black(0)).unwrap().len(), 800 * 4);
+        assert_eq!(get_pixels(&img, &Distil::builder().min_black(0).max_white(255)).unwrap().len(), 1200 * 4);
+
+        let magenta = Distil::builder().min_black(0).max_white(255).exclude_colors(&[Color::new(200, 60, 60)], 5.0);
+        assert_eq!(get_pixels(&img, &magenta).unwrap().len(), 800 * 4);
+
+        let loose = Distil::builder().min_distance(1000.0).from_image(&img).unwrap();
+        assert_eq!(loose.colors.len(), 1);
+    }
+
+    #[test]
+    fn alpha() {
+        let mut img = gen::blocks(40, 20, &[(Color::new(200, 60, 60), 1), (Color::new(40, 90, 200), 1)]);
+
+        // The blue band is 40% opaque.
+        for (i, pixel) in img.pixels_mut().enumerate() {
+            if i % 40 >= 20 {
+                pixel[3] = 102;
+            }
+        }
+
+        assert_eq!(get_pixels(&img, &Distil::builder()).unwrap().len(), 400 * 4);
+        assert_eq!(get_pixels(&img, &Distil::builder().min_alpha(100)).unwrap().len(), 800 * 4);
+        assert!(get_pixels(&img, &Distil::builder().min_alpha(100)).unwrap().chunks(4).all(|rgba| rgba[3] == 255));
+
+        let weighted = Distil::builder().min_alpha(100).alpha_weighted(true).deterministic(true).from_image(&img).unwrap();
+        assert_eq!(weighted.weights.len(), 2);
+        assert!((weighted.weights[1] - 0.4 / 1.4).abs() < 0.01);
+    }
+
+    #[test]
+    fn formats() {
+        let png = gen::solid(8, 8, Color::new(0, 128, 128)).to_png().unwrap();
+        let jpeg_only = Distil::builder().allow_formats(&[Format::Jpeg]);
+
+        assert!(Distil::builder().allow_formats(&[Format::Jpeg, Format::Png]).from_bytes(&png).is_ok());
+        assert!(matches!(jpeg_only.from_bytes(&png), Err(DistilError::UnsupportedFormat)));
+        assert!(matches!(jpeg_only.from_reader(&png[..]), Err(DistilError::UnsupportedFormat)));
+        assert!(jpeg_only.from_path_str("./images/img-1.jpg").is_ok());
+        assert!(jpeg_only.allow_any_decodable().from_bytes(&png).is_ok());
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use lab::Lab;
+
+use super::{extract_colors, get_pixels, remove_similar_colors, scale_img, DistilBuilder};
+use crate::palette::{distil_palette, with_members};
+use crate::{Distil, DistilError, Image};
+
+/// The range of the a* and b* channels covered by `ChannelHistograms`.
+/// Values outside of it are counted in the first or last bin.
+static AB_RANGE: (f32, f32) = (-128.0, 128.0);
+
+/// Per-channel histograms of the pixels a palette was distilled from, as
+/// returned by `Distil::from_image_with_histograms`.
+///
+/// Each histogram has `bins` equally wide bins covering the full range of
+/// its channel: `0..=255` for R, G and B, `0.0..=100.0` for L* and
+/// `-128.0..128.0` for a* and b*.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelHistograms {
+    /// The number of bins of each histogram.
+    pub bins: usize,
+
+    /// The histogram of the red channel.
+    pub red: Vec<usize>,
+
+    /// The histogram of the green channel.
+    pub green: Vec<usize>,
+
+    /// The histogram of the blue channel.
+    pub blue: Vec<usize>,
+
+    /// The histogram of the L* channel.
+    pub lightness: Vec<usize>,
+
+    /// The histogram of the a* channel.
+    pub a: Vec<usize>,
+
+    /// The histogram of the b* channel.
+    pub b: Vec<usize>,
+}
+
+impl ChannelHistograms {
+    fn new(bins: usize) -> ChannelHistograms {
+        let bins = bins.max(1);
+
+        ChannelHistograms {
+            bins,
+            red: vec![0; bins],
+            green: vec![0; bins],
+            blue: vec![0; bins],
+            lightness: vec![0; bins],
+            a: vec![0; bins],
+            b: vec![0; bins],
+        }
+    }
+
+    fn add(&mut self, rgb: [u8; 3], lab: Lab) {
+        let bins = self.bins;
+
+        self.red[bin(rgb[0] as f32, (0.0, 256.0), bins)] += 1;
+        self.green[bin(rgb[1] as f32, (0.0, 256.0), bins)] += 1;
+        self.blue[bin(rgb[2] as f32, (0.0, 256.0), bins)] += 1;
+        self.lightness[bin(lab.l, (0.0, 100.0), bins)] += 1;
+        self.a[bin(lab.a, AB_RANGE, bins)] += 1;
+        self.b[bin(lab.b, AB_RANGE, bins)] += 1;
+    }
+}
+
+/// Returns the index of the bin `value` falls in when `range` is split into
+/// `bins` equally wide bins, clamping values outside of `range`.
+fn bin(value: f32, range: (f32, f32), bins: usize) -> usize {
+    let position = (value - range.0) / (range.1 - range.0) * bins as f32;
+
+    (position.max(0.0) as usize).min(bins - 1)
+}
+
+impl Distil {
+    /// `from_path_with_histograms` `Distil`s the image at `path` and, in the
+    /// same pass over its pixels, builds histograms of their R, G, B, L*, a*
+    /// and b* channels with `bins` bins each.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::Distil;
+    ///
+    /// let path = Path::new("./images/img-1.jpg");
+    ///
+    /// if let Ok((distilled, histograms)) = Distil::from_path_with_histograms(&path, 16) {
+    ///     // Do something with the palette and the histograms…
+    /// }
+    /// ```
+    pub fn from_path_with_histograms(path: &Path, bins: usize) -> Result<(Distil, ChannelHistograms), DistilError> {
+        let img = Image::open(path)?;
+        Distil::from_image_with_histograms(&img, bins)
+    }
+
+    /// `from_image_with_histograms` `Distil`s an already decoded image and
+    /// builds histograms of its channels like `from_path_with_histograms`.
+    ///
+    /// The histograms count the same pixels the palette is distilled from:
+    /// those of the image scaled down for sampling, leaving out the
+    /// transparent, near-black and near-white ones.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let img = gen::solid(10, 10, Color::new(0, 128, 128));
+    /// let (distilled, histograms) = Distil::from_image_with_histograms(&img, 4).unwrap();
+    ///
+    /// assert_eq!(histograms.red, vec![100, 0, 0, 0]);
+    /// assert_eq!(histograms.green, vec![0, 0, 100, 0]);
+    /// ```
+    pub fn from_image_with_histograms(img: &Image, bins: usize) -> Result<(Distil, ChannelHistograms), DistilError> {
+        let config = DistilBuilder::default();
+        let scaled_img = scale_img(img, config.sample_count);
+        let palette = remove_similar_colors(extract_colors(&scaled_img, &config)?, config.min_distance);
+
+        let mut histograms = ChannelHistograms::new(bins);
+        let mut members = Vec::new();
+
+        for rgba in get_pixels(&scaled_img, &config)?.chunks(4) {
+            let rgb = [rgba[0], rgba[1], rgba[2]];
+            let lab = Lab::from_rgb(&rgb);
+
+            histograms.add(rgb, lab);
+            members.push((lab, 1.0, 0.0));
+        }
+
+        Ok((with_members(distil_palette(palette), &members), histograms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::Distil;
+
+    #[test]
+    fn histograms() {
+        let path = Path::new("./images/img-1.jpg");
+        let (distilled, histograms) = Distil::from_path_with_histograms(path, 8).unwrap();
+        let total: usize = histograms.red.iter().sum();
+
+        assert_eq!(distilled.colors, Distil::from_path(path).unwrap().colors);
+        assert!(total > 0);
+
+        for histogram in &[&histograms.green, &histograms.blue, &histograms.lightness, &histograms.a, &histograms.b] {
+            assert_eq!(histogram.len(), 8);
+            assert_eq!(histogram.iter().sum::<usize>(), total);
+        }
+    }
+}
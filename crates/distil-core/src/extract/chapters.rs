@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use super::histogram::Histogram;
+use super::{distil_colors, scale_img, MAX_SAMPLE_COUNT};
+use crate::{Distil, DistilError, Image};
+
+/// The palettes of the chapters of a video, as returned by
+/// `Distil::from_chapters`.
+#[derive(Debug, Clone)]
+pub struct ChapterPalettes {
+    /// The palette of each chapter, in the order of the chapter starts, or
+    /// `None` if no frame of the chapter has interesting colors.
+    pub chapters: Vec<Option<Distil>>,
+
+    /// The palette of every frame of the video.
+    pub whole: Distil,
+}
+
+impl Distil {
+    /// `from_chapters` distils the `frames` of a video, each paired with its
+    /// timestamp, into one palette per chapter as well as one for the whole
+    /// video, e.g. to theme the chapter markers of a player.
+    ///
+    /// Chapter `i` runs from `chapter_starts[i]` up to the next start, and
+    /// frames before the first start count towards the first chapter. The
+    /// frames, e.g. decoded with ffmpeg at a fixed interval, are counted
+    /// into histograms one at a time so long videos don't need to fit in
+    /// memory, each frame counting as much as any other.
+    ///
+    /// Returns `DistilError::Uninteresting` if no frame has interesting
+    /// colors.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let frames = (0..20).map(|second| {
+    ///     let color = if second < 10 { Color::new(0, 128, 128) } else { Color::new(255, 127, 80) };
+    ///     (Duration::from_secs(second), gen::solid(32, 18, color))
+    /// });
+    ///
+    /// let palettes = Distil::from_chapters(frames, &[Duration::ZERO, Duration::from_secs(10)]).unwrap();
+    /// let marker_colors: Vec<_> = palettes.chapters.iter().map(|chapter| chapter.as_ref().map(|distilled| distilled.colors[0])).collect();
+    /// ```
+    pub fn from_chapters<I>(frames: I, chapter_starts: &[Duration]) -> Result<ChapterPalettes, DistilError>
+        where I: IntoIterator<Item = (Duration, Image)>
+    {
+        let mut chapters = vec![Histogram::default(); chapter_starts.len().max(1)];
+        let mut whole = Histogram::default();
+
+        for (timestamp, frame) in frames {
+            let scaled = scale_img(&frame, MAX_SAMPLE_COUNT);
+            let chapter = chapter_starts.iter().rposition(|&start| start <= timestamp).unwrap_or(0);
+
+            chapters[chapter].add_image(&scaled);
+            whole.add_image(&scaled);
+        }
+
+        Ok(ChapterPalettes {
+            whole: distil_colors(whole.colors()?),
+            chapters: chapters.into_iter()
+                .take(chapter_starts.len())
+                .map(|histogram| histogram.colors().ok().map(distil_colors))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{delta_e, gen, Color, Distil, DistilError};
+
+    #[test]
+    fn chapters() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let frames = || (0..6u64).map(move |i| (Duration::from_secs(i * 5), gen::solid(16, 9, if i < 3 { teal } else { coral })));
+        let starts = [Duration::from_secs(5), Duration::from_secs(15), Duration::from_secs(60)];
+
+        let palettes = Distil::from_chapters(frames(), &starts).unwrap();
+
+        assert_eq!(palettes.chapters.len(), 3);
+        assert!(delta_e(palettes.chapters[0].as_ref().unwrap().colors[0], teal) < 2.0);
+        assert_eq!(palettes.chapters[1].as_ref().unwrap().colors.len(), 1);
+        assert!(delta_e(palettes.chapters[1].as_ref().unwrap().colors[0], coral) < 2.0);
+        assert!(palettes.chapters[2].is_none());
+        assert_eq!(palettes.whole.colors.len(), 2);
+
+        assert!(matches!(Distil::from_chapters(Vec::new(), &starts), Err(DistilError::Uninteresting)));
+    }
+}
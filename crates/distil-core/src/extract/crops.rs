@@ -0,0 +1,324 @@
+use std::cmp::Ordering;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::scale_img;
+use crate::{Color, Distil, DistilError, Image, IntoLab};
+
+/// The number of pixels the image is scaled down to when searching for a
+/// suggested crop.
+static CROP_SEARCH_SAMPLE_COUNT: u32 = 10000;
+
+/// A standard crop of an image, as used by automated thumbnail cropping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crop {
+    /// The whole image.
+    Full,
+
+    /// The centre of the image, half its width and half its height.
+    Center,
+
+    /// The first third of the image along its longest side, i.e. the left
+    /// third of a landscape image or the top third of a portrait one.
+    LeadingThird,
+
+    /// The middle third of the image along its longest side.
+    MiddleThird,
+
+    /// The last third of the image along its longest side, i.e. the right
+    /// third of a landscape image or the bottom third of a portrait one.
+    TrailingThird,
+}
+
+impl Crop {
+    /// All of the standard crops.
+    pub fn all() -> [Crop; 5] {
+        [Crop::Full, Crop::Center, Crop::LeadingThird, Crop::MiddleThird, Crop::TrailingThird]
+    }
+
+    /// Returns the `(x, y, width, height)` rectangle the crop covers in an
+    /// image of the passed dimensions.
+    pub fn rect(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let third = |i: u32| {
+            if width >= height {
+                (width * i / 3, 0, (width / 3).max(1), height)
+            } else {
+                (0, height * i / 3, width, (height / 3).max(1))
+            }
+        };
+
+        match *self {
+            Crop::Full => (0, 0, width, height),
+            Crop::Center => (width / 4, height / 4, (width / 2).max(1), (height / 2).max(1)),
+            Crop::LeadingThird => third(0),
+            Crop::MiddleThird => third(1),
+            Crop::TrailingThird => third(2),
+        }
+    }
+}
+
+/// The dominant color of one crop, as reported by `crop_stability`.
+#[derive(Debug, Clone)]
+pub struct CropDominant {
+    pub crop: Crop,
+
+    /// The most frequent color of the crop, or `None` if the crop doesn't
+    /// contain any interesting colors.
+    pub dominant: Option<Color>,
+
+    /// The CIEDE2000 distance between `dominant` and the dominant color of
+    /// the full image, or infinity if `dominant` is `None`.
+    pub distance: f32,
+}
+
+/// How much the dominant color of an image changes across the standard
+/// crops.
+#[derive(Debug, Clone)]
+pub struct CropStability {
+    /// The dominant color of the full image.
+    pub dominant: Color,
+
+    /// The dominant color of each crop, in the order of `Crop::all`.
+    pub crops: Vec<CropDominant>,
+}
+
+impl CropStability {
+    /// Returns the largest distance between the dominant color of a crop and
+    /// that of the full image.
+    pub fn max_distance(&self) -> f32 {
+        self.crops.iter().fold(0.0, |max, crop| max.max(crop.distance))
+    }
+
+    /// Returns `true` if no crop changes the dominant color by more than
+    /// `tolerance`.
+    pub fn is_stable(&self, tolerance: f32) -> bool {
+        self.max_distance() <= tolerance
+    }
+
+    /// Returns the crops that keep the dominant color within `tolerance` of
+    /// the full image's.
+    pub fn stable_crops(&self, tolerance: f32) -> Vec<Crop> {
+        self.crops.iter()
+            .filter(|crop| crop.distance <= tolerance)
+            .map(|crop| crop.crop)
+            .collect()
+    }
+}
+
+/// Distils each of the standard crops of `img` and reports how far their
+/// dominant colors are from the dominant color of the full image, so crops
+/// that would change the perceived theme color can be avoided.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::extract::crop_stability;
+/// use distil_core::Image;
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     if let Ok(stability) = crop_stability(&img) {
+///         let safe_crops = stability.stable_crops(10.0);
+///     }
+/// }
+/// ```
+pub fn crop_stability(img: &Image) -> Result<CropStability, DistilError> {
+    let (width, height) = img.dimensions();
+    let full = dominant_lab(img)?;
+
+    let crops = Crop::all()
+        .iter()
+        .map(|&crop| {
+            let (x, y, crop_width, crop_height) = crop.rect(width, height);
+            let dominant = dominant_lab(&img.crop(x, y, crop_width, crop_height)).ok();
+
+            CropDominant {
+                crop,
+                dominant: dominant.map(Color::from),
+                distance: dominant.map_or(f32::INFINITY, |lab| DE2000::new(full, lab)),
+            }
+        })
+        .collect();
+
+    Ok(CropStability {
+        dominant: Color::from(full),
+        crops,
+    })
+}
+
+/// The color `suggest_crop` tries to fit as much of as possible into the
+/// crop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CropTarget {
+    /// A specific color.
+    Color(Color),
+
+    /// The image's accent color, i.e. the most saturated color of its
+    /// palette.
+    Accent,
+}
+
+/// A crop rectangle suggested by `suggest_crop`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuggestedCrop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+
+    /// The share of the crop, between `0.0` and `1.0`, covered by pixels
+    /// within tolerance of the target color.
+    pub coverage: f32,
+}
+
+/// Suggests a `width` × `height` crop of `img` containing as many pixels as
+/// possible within `tolerance` (a CIEDE2000 distance) of `target`, e.g. to
+/// generate hero crops emphasising the subject's color.
+///
+/// The crop is clamped to the dimensions of the image, and an empty image is
+/// `DistilError::Uninteresting`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::extract::{suggest_crop, CropTarget};
+/// use distil_core::Image;
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     if let Ok(crop) = suggest_crop(&img, CropTarget::Accent, 400, 400, 10.0) {
+///         let hero = img.crop(crop.x, crop.y, crop.width, crop.height);
+///     }
+/// }
+/// ```
+pub fn suggest_crop(img: &Image,
+                    target: CropTarget,
+                    width: u32,
+                    height: u32,
+                    tolerance: f32)
+                    -> Result<SuggestedCrop, DistilError> {
+    let (img_width, img_height) = img.dimensions();
+
+    if img_width == 0 || img_height == 0 {
+        return Err(DistilError::Uninteresting);
+    }
+
+    let (width, height) = (width.clamp(1, img_width), height.clamp(1, img_height));
+
+    let target = match target {
+        CropTarget::Color(color) => color.into_lab(),
+        CropTarget::Accent => accent_lab(img)?,
+    };
+
+    let scaled = scale_img(img, CROP_SEARCH_SAMPLE_COUNT);
+    let (scaled_width, scaled_height) = scaled.dimensions();
+    let scale = scaled_width as f32 / img_width as f32;
+
+    // A summed-area table of the pixels matching the target, so the number of
+    // matches inside any window can be read in constant time.
+    let stride = scaled_width as usize + 1;
+    let mut table = vec![0u32; stride * (scaled_height as usize + 1)];
+
+    for y in 0..scaled_height as usize {
+        for x in 0..scaled_width as usize {
+            let px = scaled.get_pixel(x as u32, y as u32);
+            let matches = px[3] != 0 && DE2000::new(Lab::from_rgb(&[px[0], px[1], px[2]]), target) < tolerance;
+
+            table[(y + 1) * stride + x + 1] = matches as u32 + table[y * stride + x + 1] +
+                                              table[(y + 1) * stride + x] -
+                                              table[y * stride + x];
+        }
+    }
+
+    let window_width = ((width as f32 * scale).round() as usize).clamp(1, scaled_width as usize);
+    let window_height = ((height as f32 * scale).round() as usize).clamp(1, scaled_height as usize);
+
+    let mut best = (0, 0, 0);
+
+    for y in 0..scaled_height as usize - window_height + 1 {
+        for x in 0..scaled_width as usize - window_width + 1 {
+            let (x2, y2) = (x + window_width, y + window_height);
+            let matches = table[y2 * stride + x2] + table[y * stride + x] -
+                          table[y * stride + x2] - table[y2 * stride + x];
+
+            if matches > best.2 {
+                best = (x, y, matches);
+            }
+        }
+    }
+
+    let x = ((best.0 as f32 / scale).round() as u32).min(img_width - width);
+    let y = ((best.1 as f32 / scale).round() as u32).min(img_height - height);
+
+    Ok(SuggestedCrop {
+        x,
+        y,
+        width,
+        height,
+        coverage: best.2 as f32 / (window_width * window_height) as f32,
+    })
+}
+
+/// Returns the most saturated color of the image's palette.
+fn accent_lab(img: &Image) -> Result<Lab, DistilError> {
+    let distilled = Distil::new(img)?;
+    let chroma = |lab: &Lab| (lab.a * lab.a + lab.b * lab.b).sqrt();
+
+    Ok(*distilled.lab_colors
+        .iter()
+        .max_by(|a, b| chroma(a).partial_cmp(&chroma(b)).unwrap_or(Ordering::Equal))
+        .unwrap_or(&distilled.lab_colors[0]))
+}
+
+/// Returns the color of the image's palette that covers the most of it.
+fn dominant_lab(img: &Image) -> Result<Lab, DistilError> {
+    let distilled = Distil::new(img)?;
+
+    distilled.entries()
+        .into_iter()
+        .max_by(|x, y| x.weight.total_cmp(&y.weight))
+        .map(|entry| entry.lab)
+        .ok_or(DistilError::Uninteresting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crop_stability, suggest_crop, Crop, CropTarget};
+    use crate::{delta_e, gen, Color};
+
+    #[test]
+    fn rects() {
+        assert_eq!(Crop::LeadingThird.rect(300, 100), (0, 0, 100, 100));
+        assert_eq!(Crop::TrailingThird.rect(100, 300), (0, 200, 100, 100));
+        assert_eq!(Crop::Center.rect(100, 100), (25, 25, 50, 50));
+    }
+
+    #[test]
+    fn stability() {
+        let blue = Color::new(40, 40, 220);
+        let red = Color::new(220, 40, 40);
+        let stability = crop_stability(&gen::blocks(300, 100, &[(blue, 2), (red, 1)])).unwrap();
+
+        assert_eq!(stability.crops.len(), Crop::all().len());
+        assert!(delta_e(stability.dominant, blue) < 2.0);
+        assert_eq!(stability.stable_crops(5.0), vec![Crop::Full, Crop::Center, Crop::LeadingThird, Crop::MiddleThird]);
+        assert!(!stability.is_stable(5.0));
+
+        let trailing = &stability.crops[4];
+        assert_eq!(trailing.crop, Crop::TrailingThird);
+        assert!(delta_e(trailing.dominant.unwrap(), red) < 2.0);
+        assert!(trailing.distance > 30.0);
+    }
+
+    #[test]
+    fn suggested_crop() {
+        let img = gen::blocks(150, 50, &[(Color::new(40, 40, 220), 2), (Color::new(220, 40, 40), 1)]);
+
+        let red = CropTarget::Color(Color::new(220, 40, 40));
+        let crop = suggest_crop(&img, red, 50, 80, 10.0).unwrap();
+
+        assert_eq!((crop.x, crop.y, crop.width, crop.height), (100, 0, 50, 50));
+        assert!(crop.coverage > 0.99);
+    }
+}
@@ -0,0 +1,185 @@
+use lab::Lab;
+
+use super::{count_colors_as_lab, pixel_members, quantize, DistilBuilder};
+use crate::palette::distil_palette;
+use crate::{Color, Distil, DistilError, Image};
+
+/// Why a pixel was left out while sampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Filtered {
+    /// The pixel is more transparent than the minimum alpha.
+    Transparent,
+
+    /// The pixel is too dark to be interesting.
+    Black,
+
+    /// The pixel is too light to be interesting.
+    White,
+
+    /// The pixel is close to an excluded or keyed color.
+    Excluded,
+
+    /// The pixel is in a region with a weight of `0.0`.
+    Region,
+
+    /// The pixel filter left the pixel out.
+    Custom,
+}
+
+/// A color merged into a similar one while distilling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Merge {
+    /// The merged color and its count.
+    pub color: Lab,
+    pub count: usize,
+
+    /// The color it was merged into, as it was before the merge.
+    pub into: Lab,
+
+    /// The CIEDE2000 distance between `color` and `into`.
+    pub distance: f32,
+}
+
+/// The intermediate results of distilling an image, as returned by
+/// `DistilBuilder::debug`, for finding out why a color is or isn't in a
+/// palette.
+#[derive(Debug, Clone)]
+pub struct Artifacts {
+    /// The palette, exactly as `from_image` returns it.
+    pub distil: Distil,
+
+    /// The image after being scaled down to the sample count, which is what
+    /// every later step works on.
+    pub scaled: Image,
+
+    /// Whether each pixel of `scaled`, row by row, was sampled. Pixels that
+    /// are transparent, too dark or light, excluded, keyed or in a region
+    /// with a weight of `0.0` aren't.
+    pub mask: Vec<bool>,
+
+    /// How many pixels of `scaled` were left out for each reason, for the
+    /// reasons that left out any.
+    pub filtered: Vec<(Filtered, usize)>,
+
+    /// The name of the quantizer used, e.g. `"NeuQuant"`.
+    pub quantizer: &'static str,
+
+    /// The raw output of the quantizer: NeuQuant's palette or, for the
+    /// other quantizers, the color each sampled pixel was quantized to.
+    pub quantized: Vec<Color>,
+
+    /// The unique colors of `quantized` in Lab along with how many times
+    /// each appears in it, from most to least frequent, before similar
+    /// colors are merged into the final palette.
+    pub histogram: Vec<(Lab, usize)>,
+
+    /// Every merge of a color of `histogram`, or of a color merged from it,
+    /// into a similar one, in the order they happened.
+    pub merges: Vec<Merge>,
+}
+
+impl Artifacts {
+    /// Renders `mask` as an image the size of `scaled`, with the sampled
+    /// pixels in white and the rest in black.
+    pub fn mask_image(&self) -> Image {
+        let mut mask = Image::new(self.scaled.width(), self.scaled.height());
+
+        for (px, &sampled) in mask.pixels_mut().zip(&self.mask) {
+            let value = if sampled { 255 } else { 0 };
+            px.copy_from_slice(&[value, value, value, 255]);
+        }
+
+        mask
+    }
+}
+
+impl DistilBuilder {
+    /// Like `from_image`, but also returns the intermediate results of each
+    /// step of the pipeline.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Distil, Image};
+    ///
+    /// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+    ///     let artifacts = Distil::builder().debug(&img).unwrap();
+    ///     let sampled = artifacts.mask.iter().filter(|&&sampled| sampled).count();
+    ///
+    ///     println!("{} of {} pixels sampled", sampled, artifacts.mask.len());
+    /// }
+    /// ```
+    pub fn debug(&self, img: &Image) -> Result<Artifacts, DistilError> {
+        let scaled_img = self.sampled(img).into_owned();
+        let config = self.keyed(&scaled_img);
+        let config = config.scaled(img, &scaled_img);
+
+        let reasons = config.filter_reasons(&scaled_img);
+
+        let mut filtered: Vec<(Filtered, usize)> = Vec::new();
+
+        for &reason in reasons.iter().flatten() {
+            match filtered.iter_mut().find(|(other, _)| *other == reason) {
+                Some((_, count)) => *count += 1,
+                None => filtered.push((reason, 1)),
+            }
+        }
+
+        filtered.sort();
+
+        let quantized = quantize(&scaled_img, &config)?;
+        let histogram = count_colors_as_lab(quantized.clone());
+        let mut merges = Vec::new();
+        let palette = config.merged_around_locks(histogram.clone(), &mut merges);
+        let distil = config.placed_locks(config.with_members(distil_palette(palette), &pixel_members(&scaled_img, &config)));
+
+        Ok(Artifacts {
+            distil,
+            mask: reasons.iter().map(Option::is_none).collect(),
+            filtered,
+            quantizer: config.quantizer_name(),
+            quantized: quantized.into_iter().map(|[r, g, b]| Color::new(r, g, b)).collect(),
+            histogram,
+            merges,
+            scaled: scaled_img,
+        })
+    }
+
+    /// Returns why each pixel of `scaled_img`, row by row, is left out
+    /// while sampling, or `None` for the pixels that are sampled.
+    pub(super) fn filter_reasons(&self, scaled_img: &Image) -> Vec<Option<Filtered>> {
+        let width = scaled_img.width().max(1);
+
+        scaled_img.pixels()
+            .enumerate()
+            .map(|(i, rgba)| {
+                self.filtered(&rgba)
+                    .or_else(|| (self.weight_at(i as u32 % width, i as u32 / width) <= 0.0).then_some(Filtered::Region))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Filtered;
+    use crate::{gen, Color, Distil};
+
+    #[test]
+    fn debug() {
+        let img = gen::blocks(60, 20, &[(Color::new(200, 60, 60), 1), (Color::new(4, 4, 4), 1), (Color::new(60, 60, 200), 1)]);
+        let artifacts = Distil::builder().debug(&img).unwrap();
+
+        assert_eq!(artifacts.distil.colors, Distil::from_image(&img).unwrap().colors);
+        assert_eq!(artifacts.scaled.dimensions(), (54, 18));
+        assert_eq!(artifacts.mask.len(), 54 * 18);
+        assert!((16 * 18..=18 * 18).contains(&artifacts.mask.iter().filter(|&&sampled| !sampled).count()));
+        assert_eq!(artifacts.filtered, vec![(Filtered::Black, artifacts.mask.iter().filter(|&&sampled| !sampled).count())]);
+        assert_eq!(artifacts.mask_image().get_pixel(27, 9), [0, 0, 0, 255]);
+        assert!(!artifacts.quantized.is_empty());
+        assert_eq!(artifacts.histogram.iter().map(|&(_, count)| count).sum::<usize>(), artifacts.quantized.len());
+        assert_eq!(artifacts.histogram.len() - artifacts.merges.len(), artifacts.distil.colors.len());
+        assert!(artifacts.merges.iter().all(|merge| merge.distance < 10.0));
+    }
+}
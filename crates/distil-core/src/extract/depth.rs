@@ -0,0 +1,83 @@
+use super::histogram::Histogram;
+use super::{distil_colors, scale_img, MAX_SAMPLE_COUNT};
+use crate::{Distil, DistilError, Image};
+
+/// How many times more the nearest pixels count than the farthest ones when
+/// distilling with a depth map.
+static MAX_DEPTH_WEIGHT: u64 = 8;
+
+impl Distil {
+    /// `from_image_with_depth` `Distil`s an image, counting each pixel
+    /// according to how near it is in `depth`, so that the palette emphasizes
+    /// the subject of a photo over its background.
+    ///
+    /// `depth` is a grayscale depth map of the same scene, as shipped with
+    /// portrait photos, where white is nearest and black farthest. It's
+    /// stretched over the image when their sizes differ. The nearest pixels
+    /// count eight times as much as the farthest ones, which is reflected in
+    /// both `counts` and `weights`. Without a depth map it's the
+    /// same as `from_image`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Distil, Image};
+    ///
+    /// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+    ///     let depth = Image::open(Path::new("./images/img-1-depth.png")).ok();
+    ///     let distilled = Distil::from_image_with_depth(&img, depth.as_ref());
+    /// }
+    /// ```
+    pub fn from_image_with_depth(img: &Image, depth: Option<&Image>) -> Result<Distil, DistilError> {
+        let depth = match depth {
+            Some(depth) if depth.width() > 0 && depth.height() > 0 => depth,
+            _ => return Distil::from_image(img),
+        };
+
+        let scaled_img = scale_img(img, MAX_SAMPLE_COUNT);
+        let (width, height) = scaled_img.dimensions();
+        let mut histogram = Histogram::default();
+
+        for y in 0..height {
+            for x in 0..width {
+                let nearness = depth.get_pixel(
+                    (x as u64 * depth.width() as u64 / width as u64) as u32,
+                    (y as u64 * depth.height() as u64 / height as u64) as u32,
+                )[0] as u64;
+
+                histogram.add_weighted(scaled_img.get_pixel(x, y), 1 + nearness * (MAX_DEPTH_WEIGHT - 1) / 255);
+            }
+        }
+
+        Ok(distil_colors(histogram.colors()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn depth() {
+        let coral = Color::new(240, 110, 90);
+        let teal = Color::new(0, 128, 128);
+        let img = gen::blocks(120, 40, &[(coral, 1), (teal, 3)]);
+        let depth = gen::blocks(12, 4, &[(Color::new(255, 255, 255), 1), (Color::new(0, 0, 0), 3)]);
+
+        let weight = |distilled: &Distil, expected| {
+            distilled.colors.iter()
+                .zip(&distilled.weights)
+                .filter(|&(&color, _)| delta_e(color, expected) < 5.0)
+                .map(|(_, &weight)| weight)
+                .sum::<f32>()
+        };
+
+        let flat = Distil::from_image_with_depth(&img, None).unwrap();
+        let weighted = Distil::from_image_with_depth(&img, Some(&depth)).unwrap();
+
+        assert!(weight(&flat, coral) < 0.3);
+        assert!(weight(&weighted, coral) > 0.6);
+        assert!(delta_e(weighted.colors[0], coral) < 5.0);
+    }
+}
@@ -0,0 +1,94 @@
+use std::borrow::Cow;
+
+use super::{scale_img, DistilBuilder};
+use crate::Image;
+
+impl DistilBuilder {
+    /// Guarantees that the same image always distils into the same palette,
+    /// e.g. for snapshot tests and cache keys. The image is sampled at a
+    /// fixed stride with integer arithmetic rather than resized with a
+    /// Gaussian filter, and quantized with `WuQuantizer` unless another
+    /// quantizer is set, rather than NeuQuant, whose palette depends on the
+    /// order it learns the sampled pixels in. Off by default.
+    ///
+    /// Colors are still converted to Lab and compared with floating point
+    /// math, so a platform whose `powf` or trigonometry rounds differently
+    /// can, very rarely, merge two borderline colors differently.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let builder = Distil::builder().deterministic(true);
+    ///
+    /// if let (Ok(a), Ok(b)) = (builder.from_path_str("./images/img-1.jpg"), builder.from_path_str("./images/img-1.jpg")) {
+    ///     assert_eq!(a.colors, b.colors);
+    /// }
+    /// ```
+    pub fn deterministic(mut self, deterministic: bool) -> DistilBuilder {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Scales `img` down to at most `sample_count` pixels, the way the
+    /// configuration samples images.
+    pub(super) fn sampled<'a>(&self, img: &'a Image) -> Cow<'a, Image> {
+        if self.deterministic {
+            sample_img(img, self.sample_count)
+        } else {
+            Cow::Owned(scale_img(img, self.sample_count))
+        }
+    }
+}
+
+/// Like `scale_img`, but picks the pixel at the center of each cell of a
+/// grid instead of filtering, so that only integer arithmetic is involved.
+fn sample_img(img: &Image, sample_count: u32) -> Cow<'_, Image> {
+    let (width, height) = img.dimensions();
+
+    if width as u64 * height as u64 <= sample_count as u64 {
+        return Cow::Borrowed(img);
+    }
+
+    // `sqrt` is correctly rounded on every platform, unlike `powf`.
+    let ratio = width as f64 / height as f64;
+    let sampled_width = ((ratio * sample_count as f64).sqrt() as u32).clamp(1, width);
+    let sampled_height = ((sample_count / sampled_width) as u64).min(height as u64 * sampled_width as u64 / width as u64).max(1) as u32;
+
+    let mut sampled = Image::new(sampled_width, sampled_height);
+
+    for y in 0..sampled_height {
+        let source_y = ((2 * y as u64 + 1) * height as u64 / (2 * sampled_height as u64)) as u32;
+
+        for x in 0..sampled_width {
+            let source_x = ((2 * x as u64 + 1) * width as u64 / (2 * sampled_width as u64)) as u32;
+            sampled.put_pixel(x, y, img.get_pixel(source_x, source_y));
+        }
+    }
+
+    Cow::Owned(sampled)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gen, Color, Distil};
+
+    #[test]
+    fn deterministic() {
+        let img = gen::jitter(&gen::gradient(120, 90, Color::new(0, 128, 128), Color::new(255, 127, 80)), 40, 7);
+        let builder = Distil::builder().deterministic(true);
+        let sampled = builder.sampled(&img);
+
+        assert!(sampled.width() * sampled.height() <= 1000);
+        assert_eq!(sampled.width() / 4, sampled.height() / 3);
+
+        let distilled = builder.from_image(&img).unwrap();
+
+        for _ in 0..3 {
+            let again = builder.from_image(&img).unwrap();
+            assert_eq!(again.colors, distilled.colors);
+            assert_eq!(again.weights, distilled.weights);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::extract::DistilBuilder;
+use crate::{imaging, Distil, DistilError};
+
+/// Each image path with the result of distilling it.
+type Distils = Vec<(PathBuf, Result<Distil, DistilError>)>;
+
+/// Configures `Distil::from_dir`.
+#[derive(Debug, Clone, Default)]
+pub struct DirConfig {
+    /// Whether to also distil the images in subdirectories.
+    pub recursive: bool,
+
+    /// How each image is distilled.
+    pub builder: DistilBuilder,
+
+    /// The number of threads images are distilled on with the `parallel`
+    /// feature. `None` or `Some(0)` uses rayon's global pool, one thread
+    /// per core, and `Some(n)` a pool of its own with `n` threads.
+    ///
+    /// `Some(1)` distils every image on the calling thread without spawning
+    /// any, e.g. for plugins whose host forbids it. So does any value when
+    /// a pool can't be created, and without the `parallel` feature.
+    pub threads: Option<usize>,
+}
+
+impl Distil {
+    /// `from_dir` distils every image in a directory, in parallel when the
+    /// `parallel` feature is enabled and `config.threads` allows it. Files
+    /// without the extension of a supported format are skipped, and
+    /// symlinked directories aren't followed. Each path is returned with its
+    /// own result, sorted by path, so that one broken image doesn't fail the
+    /// whole batch.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{DirConfig, Distil};
+    ///
+    /// let config = DirConfig { recursive: true, ..DirConfig::default() };
+    ///
+    /// if let Ok(distils) = Distil::from_dir(Path::new("./images"), &config) {
+    ///     for (path, distilled) in distils {
+    ///         // Do something with each `Distil` struct…
+    ///     }
+    /// }
+    /// ```
+    pub fn from_dir(path: &Path, config: &DirConfig) -> Result<Distils, DistilError> {
+        let mut paths = Vec::new();
+        let mut unreadable = Vec::new();
+
+        image_paths(path, config.recursive, &mut paths, &mut unreadable)?;
+        paths.sort();
+
+        let distil = |path: PathBuf| {
+            let distilled = config.builder.from_path(&path);
+            (path, distilled)
+        };
+
+        #[cfg(feature = "parallel")]
+        let pool = match config.threads {
+            Some(threads) if threads > 1 => Some(rayon::ThreadPoolBuilder::new().num_threads(threads).build()),
+            _ => None,
+        };
+
+        #[cfg(feature = "parallel")]
+        let mut distils: Vec<_> = match (config.threads, pool) {
+            (Some(1), _) | (_, Some(Err(_))) => paths.into_iter().map(distil).collect(),
+            (_, Some(Ok(pool))) => pool.install(|| paths.into_par_iter().map(distil).collect()),
+            (_, None) => paths.into_par_iter().map(distil).collect(),
+        };
+        #[cfg(not(feature = "parallel"))]
+        let mut distils: Vec<_> = paths.into_iter().map(distil).collect();
+
+        distils.extend(unreadable);
+        distils.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(distils)
+    }
+}
+
+/// Collects the image paths in `dir`. Subdirectories that can't be read are
+/// reported in `unreadable` rather than failing the walk.
+fn image_paths(
+    dir: &Path,
+    recursive: bool,
+    paths: &mut Vec<PathBuf>,
+    unreadable: &mut Distils,
+) -> Result<(), DistilError> {
+    let io_err = |err: std::io::Error| DistilError::Io(dir.display().to_string(), err.to_string());
+
+    for entry in fs::read_dir(dir).map_err(io_err)? {
+        let entry = entry.map_err(io_err)?;
+        let path = entry.path();
+        let file_type = entry.file_type().map_err(io_err)?;
+
+        if file_type.is_dir() {
+            if recursive {
+                if let Err(err) = image_paths(&path, recursive, paths, unreadable) {
+                    unreadable.push((path, Err(err)));
+                }
+            }
+        } else if imaging::has_image_extension(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_dir() {
+        let dir = std::env::temp_dir().join(format!("distil-from-dir-{}", std::process::id()));
+        let nested = dir.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::copy("./images/img-1.jpg", dir.join("b.jpg")).unwrap();
+        fs::copy("./images/img-3.jpg", nested.join("a.jpg")).unwrap();
+        fs::write(dir.join("a.png"), b"not a png").unwrap();
+        fs::write(dir.join("notes.txt"), b"skipped").unwrap();
+
+        let flat = Distil::from_dir(&dir, &DirConfig::default()).unwrap();
+        let recursive = Distil::from_dir(&dir, &DirConfig { recursive: true, ..DirConfig::default() }).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<_> = flat.iter().map(|(path, _)| path.strip_prefix(&dir).unwrap().to_owned()).collect();
+        assert_eq!(names, vec![PathBuf::from("a.png"), PathBuf::from("b.jpg")]);
+        assert!(flat[0].1.is_err());
+        assert!(flat[1].1.is_ok());
+
+        assert_eq!(recursive.len(), 3);
+        assert_eq!(recursive[2].0, nested.join("a.jpg"));
+        assert!(recursive[2].1.is_ok());
+    }
+
+    #[test]
+    fn single_threaded() {
+        use std::collections::HashSet;
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+
+        let dir = std::env::temp_dir().join(format!("distil-single-threaded-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for name in ["a.jpg", "b.jpg", "c.jpg"] {
+            fs::copy("./images/img-1.jpg", dir.join(name)).unwrap();
+        }
+
+        let threads = Arc::new(Mutex::new(HashSet::new()));
+        let recorded = Arc::clone(&threads);
+
+        let record_thread = move |_: &[u8; 4]| {
+            recorded.lock().unwrap().insert(thread::current().id());
+            true
+        };
+
+        let config = DirConfig {
+            builder: Distil::builder().pixel_filter(record_thread),
+            threads: Some(1),
+            ..DirConfig::default()
+        };
+
+        let distils = Distil::from_dir(&dir, &config).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(distils.len(), 3);
+        assert_eq!(*threads.lock().unwrap(), HashSet::from([thread::current().id()]));
+    }
+
+    #[test]
+    fn missing_dir() {
+        assert!(Distil::from_dir(Path::new("./images/missing"), &DirConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::signature::distil_distance;
+use crate::Distil;
+
+/// A palette that moved far enough away from the last one a subscriber was
+/// told about, passed to the callbacks of `StreamDistiller::on_change` and
+/// `IncrementalDistil::on_change`.
+#[derive(Debug, Clone, Copy)]
+pub struct PaletteChange<'a> {
+    /// The new palette.
+    pub palette: &'a Distil,
+
+    /// The palette the subscriber was last called with, or `None` the first
+    /// time it's called.
+    pub previous: Option<&'a Distil>,
+
+    /// How far the palette moved since `previous`: the average CIEDE2000
+    /// distance from each color to the nearest color of the other palette,
+    /// weighted by share, both ways. Infinite the first time.
+    pub distance: f32,
+}
+
+type Callback = Arc<dyn Fn(&PaletteChange) + Send + Sync>;
+
+#[derive(Clone)]
+struct Subscriber {
+    threshold: f32,
+    callback: Callback,
+    last: Option<Distil>,
+}
+
+/// The callbacks subscribed to the palette of a stream or an accumulator,
+/// each with the palette it was last called with.
+#[derive(Clone, Default)]
+pub(super) struct Subscribers(Vec<Subscriber>);
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Subscribers({})", self.0.len())
+    }
+}
+
+impl Subscribers {
+    /// Subscribes `callback` to palettes at least `threshold` away from the
+    /// last one it was called with.
+    pub(super) fn add<F: Fn(&PaletteChange) + Send + Sync + 'static>(&mut self, threshold: f32, callback: F) {
+        self.0.push(Subscriber {
+            threshold: threshold.max(0.0),
+            callback: Arc::new(callback),
+            last: None,
+        });
+    }
+
+    /// Calls the subscribers `palette` moved far enough away for.
+    pub(super) fn notify(&mut self, palette: &Distil) {
+        for subscriber in &mut self.0 {
+            let distance = subscriber.last.as_ref().map_or(f32::INFINITY, |last| distil_distance(last, palette));
+
+            // Palettes that didn't move at all are skipped even with a
+            // threshold of 0.
+            if distance < subscriber.threshold || distance == 0.0 {
+                continue;
+            }
+
+            (subscriber.callback)(&PaletteChange {
+                palette,
+                previous: subscriber.last.as_ref(),
+                distance,
+            });
+
+            subscriber.last = Some(palette.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::Subscribers;
+    use crate::{gen, Color, Distil};
+
+    #[test]
+    fn threshold() {
+        let distances = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&distances);
+
+        let mut subscribers = Subscribers::default();
+        subscribers.add(5.0, move |change| recorded.lock().unwrap().push(change.distance));
+
+        for color in [Color::new(0, 128, 128), Color::new(0, 129, 128), Color::new(0, 130, 129), Color::new(255, 127, 80)] {
+            subscribers.notify(&Distil::from_image(&gen::solid(8, 8, color)).unwrap());
+        }
+
+        let distances = distances.lock().unwrap();
+        assert_eq!(distances.len(), 2);
+        assert!(distances[0].is_infinite());
+        assert!(distances[1] > 5.0);
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::plugin::Plugin;
+use super::DistilBuilder;
+
+/// Decides which pixels are sampled, on top of the built-in transparent,
+/// black and white filters and excluded colors. Implemented for closures
+/// taking the RGBA channels of a pixel.
+pub trait PixelFilter: Send + Sync {
+    /// Returns `true` if `rgba` should be sampled.
+    fn keep(&self, rgba: &[u8; 4]) -> bool;
+}
+
+impl<F: Fn(&[u8; 4]) -> bool + Send + Sync> PixelFilter for F {
+    fn keep(&self, rgba: &[u8; 4]) -> bool {
+        self(rgba)
+    }
+}
+
+impl fmt::Debug for Plugin<dyn PixelFilter> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(PixelFilter)"),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+impl DistilBuilder {
+    /// Only samples the pixels `filter` keeps, e.g. to leave out near-grey
+    /// pixels. It composes with the built-in filters, which run first; to
+    /// replace them instead, turn them off with `min_alpha(0)`,
+    /// `min_black(0)` and `max_white(255)`. Replaces any filter set before.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let is_grey = |rgba: &[u8; 4]| rgba[..3].iter().max().unwrap() - rgba[..3].iter().min().unwrap() < 16;
+    /// let builder = Distil::builder().pixel_filter(move |rgba: &[u8; 4]| !is_grey(rgba));
+    ///
+    /// if let Ok(distilled) = builder.from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn pixel_filter<F: PixelFilter + 'static>(mut self, filter: F) -> DistilBuilder {
+        self.pixel_filter = Plugin(Some(Arc::new(filter)));
+        self
+    }
+
+    /// Returns `false` if the pixel filter leaves `rgba` out.
+    pub(super) fn is_kept(&self, rgba: &[u8; 4]) -> bool {
+        self.pixel_filter.0.as_ref().map_or(true, |filter| filter.keep(rgba))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::get_pixels;
+    use super::super::Filtered;
+    use crate::{gen, Color, Distil};
+
+    #[test]
+    fn pixel_filter() {
+        let brand_green = Color::new(0, 177, 64);
+        let img = gen::blocks(30, 10, &[(Color::new(200, 60, 60), 1), (Color::new(120, 122, 125), 1), (brand_green, 1)]);
+
+        let builder = Distil::builder().pixel_filter(|rgba: &[u8; 4]| {
+            let (max, min) = (rgba[..3].iter().max().unwrap(), rgba[..3].iter().min().unwrap());
+            max - min >= 16 && rgba[..3] != [0, 177, 64]
+        });
+
+        assert_eq!(get_pixels(&img, &builder).unwrap().len(), 100 * 4);
+        assert_eq!(builder.filtered(&[120, 122, 125, 255]), Some(Filtered::Custom));
+        assert_eq!(builder.clone(), builder);
+        assert_ne!(Distil::builder().pixel_filter(|_: &[u8; 4]| true), builder);
+    }
+}
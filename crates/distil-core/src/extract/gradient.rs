@@ -0,0 +1,248 @@
+use super::{has_transparency, DistilBuilder};
+use crate::{delta_e, Color, Distil, DistilError, Image};
+
+/// The largest root mean square distance, in sRGB units, between the pixels
+/// of an image and the gradient fitted to them for it to count as one.
+static MAX_GRADIENT_RESIDUAL: f32 = 10.0;
+
+/// The CIEDE2000 distance the endpoints of a gradient need to be apart, so
+/// that flat images aren't reported as gradients.
+static MIN_GRADIENT_CONTRAST: f32 = 10.0;
+
+/// The number of opaque pixels needed to fit a gradient.
+static MIN_GRADIENT_PIXELS: usize = 16;
+
+/// A linear gradient an image is essentially made of, e.g. a sky or a
+/// studio sweep, as returned by `Distil::gradient`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gradient {
+    /// The color the gradient starts from, the darker of its endpoints.
+    pub start: Color,
+
+    /// The color the gradient ends at.
+    pub end: Color,
+
+    /// The direction of the gradient in degrees, as in CSS: `0.0` runs from
+    /// the bottom of the image to the top, `90.0` from left to right and
+    /// `180.0` from top to bottom.
+    pub angle: f32,
+
+    /// The root mean square distance, in sRGB units, between the pixels of
+    /// the image and the gradient. The lower, the better it fits.
+    pub residual: f32,
+}
+
+impl Gradient {
+    /// Returns the gradient as a CSS `linear-gradient`, e.g. to reproduce
+    /// the image as a background while it loads.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let sky = gen::gradient(60, 20, Color::new(40, 90, 180), Color::new(200, 220, 240));
+    ///
+    /// if let Some(gradient) = Distil::gradient(&sky) {
+    ///     println!("background: {};", gradient.css());
+    /// }
+    /// ```
+    pub fn css(&self) -> String {
+        format!("linear-gradient({}deg, {}, {})", self.angle.round(), self.start.hex(), self.end.hex())
+    }
+}
+
+impl Distil {
+    /// `gradient` returns the linear gradient `img` is essentially made of,
+    /// with its endpoint colors and direction, or `None` if it isn't one.
+    /// A flat palette misrepresents a sky or a studio sweep, which a
+    /// gradient reproduces.
+    ///
+    /// A gradient is fitted to the opaque pixels of the scaled down image
+    /// along the direction their colors change the most, interpolating in
+    /// sRGB like CSS does. The image counts as a gradient when its pixels
+    /// are within a root mean square distance of 10 of it and its endpoints
+    /// are distinct.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let sky = gen::gradient(60, 20, Color::new(40, 90, 180), Color::new(200, 220, 240));
+    /// let gradient = Distil::gradient(&sky).unwrap();
+    ///
+    /// assert_eq!(gradient.angle.round(), 90.0);
+    /// ```
+    pub fn gradient(img: &Image) -> Option<Gradient> {
+        DistilBuilder::default().gradient(img)
+    }
+
+    /// `from_image_with_gradient` `Distil`s `img` like `from_image`, and
+    /// also returns the gradient it's made of, if it's one.
+    pub fn from_image_with_gradient(img: &Image) -> Result<(Distil, Option<Gradient>), DistilError> {
+        DistilBuilder::default().from_image_with_gradient(img)
+    }
+}
+
+impl DistilBuilder {
+    /// Like `Distil::gradient`, with this configuration's sample count and
+    /// minimum alpha.
+    pub fn gradient(&self, img: &Image) -> Option<Gradient> {
+        fit_gradient(&self.sampled(img), self.min_alpha)
+    }
+
+    /// Like `Distil::from_image_with_gradient`, with this configuration.
+    pub fn from_image_with_gradient(&self, img: &Image) -> Result<(Distil, Option<Gradient>), DistilError> {
+        Ok((self.from_image(img)?, self.gradient(img)))
+    }
+}
+
+/// Fits a linear gradient to the pixels of `img` at least `min_alpha`
+/// opaque, returning it if it fits well enough.
+fn fit_gradient(img: &Image, min_alpha: u8) -> Option<Gradient> {
+    let (width, height) = img.dimensions();
+
+    let pixels: Vec<([f32; 2], [f32; 3])> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| (x, y, img.get_pixel(x, y)))
+        .filter(|(_, _, rgba)| !has_transparency(rgba, min_alpha))
+        .map(|(x, y, rgba)| ([x as f32 + 0.5, y as f32 + 0.5], [rgba[0] as f32, rgba[1] as f32, rgba[2] as f32]))
+        .collect();
+
+    if pixels.len() < MIN_GRADIENT_PIXELS {
+        return None;
+    }
+
+    let n = pixels.len() as f32;
+    let mut mean_position = [0.0f32; 2];
+    let mut mean_color = [0.0f32; 3];
+
+    for (position, color) in &pixels {
+        for (mean, &value) in mean_position.iter_mut().zip(position) {
+            *mean += value / n;
+        }
+
+        for (mean, &value) in mean_color.iter_mut().zip(color) {
+            *mean += value / n;
+        }
+    }
+
+    // Least squares fit of each channel to a plane over the pixels'
+    // positions.
+    let (mut sxx, mut sxy, mut syy) = (0.0f32, 0.0f32, 0.0f32);
+    let mut sxc = [0.0f32; 3];
+    let mut syc = [0.0f32; 3];
+
+    for (position, color) in &pixels {
+        let dx = position[0] - mean_position[0];
+        let dy = position[1] - mean_position[1];
+
+        sxx += dx * dx;
+        sxy += dx * dy;
+        syy += dy * dy;
+
+        for (((sxc, syc), &value), &mean) in sxc.iter_mut().zip(&mut syc).zip(color).zip(&mean_color) {
+            *sxc += dx * (value - mean);
+            *syc += dy * (value - mean);
+        }
+    }
+
+    let det = sxx * syy - sxy * sxy;
+
+    if det <= f32::EPSILON {
+        return None;
+    }
+
+    let slopes: Vec<[f32; 2]> = sxc.iter()
+        .zip(&syc)
+        .map(|(&sxc, &syc)| [(syy * sxc - sxy * syc) / det, (sxx * syc - sxy * sxc) / det])
+        .collect();
+
+    // The direction the channels change the most along, pointing towards
+    // the lighter end.
+    let a: f32 = slopes.iter().map(|slope| slope[0] * slope[0]).sum();
+    let b: f32 = slopes.iter().map(|slope| slope[0] * slope[1]).sum();
+    let c: f32 = slopes.iter().map(|slope| slope[1] * slope[1]).sum();
+    let theta = 0.5 * (2.0 * b).atan2(a - c);
+    let mut direction = [theta.cos(), theta.sin()];
+    let mut along: Vec<f32> = slopes.iter().map(|slope| slope[0] * direction[0] + slope[1] * direction[1]).collect();
+
+    if along.iter().sum::<f32>() < 0.0 {
+        direction = [-direction[0], -direction[1]];
+        along.iter_mut().for_each(|slope| *slope = -*slope);
+    }
+
+    let project = |x: f32, y: f32| (x - mean_position[0]) * direction[0] + (y - mean_position[1]) * direction[1];
+    let at = |t: f32| [0, 1, 2].map(|c| mean_color[c] + t * along[c]);
+
+    let squared_error: f32 = pixels.iter()
+        .map(|(position, color)| {
+            let fitted = at(project(position[0], position[1]));
+            color.iter().zip(&fitted).map(|(value, fitted)| (value - fitted).powi(2)).sum::<f32>()
+        })
+        .sum();
+
+    let residual = (squared_error / n).sqrt();
+
+    if residual > MAX_GRADIENT_RESIDUAL {
+        return None;
+    }
+
+    // Like CSS, the gradient runs between the lines through the corners of
+    // the image perpendicular to it.
+    let center = project(width as f32 / 2.0, height as f32 / 2.0);
+    let half_length = (width as f32 * direction[0].abs() + height as f32 * direction[1].abs()) / 2.0;
+    let to_color = |channels: [f32; 3]| {
+        let [r, g, b] = channels.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+        Color::new(r, g, b)
+    };
+
+    let start = to_color(at(center - half_length));
+    let end = to_color(at(center + half_length));
+
+    if delta_e(start, end) < MIN_GRADIENT_CONTRAST {
+        return None;
+    }
+
+    Some(Gradient {
+        start,
+        end,
+        angle: direction[0].atan2(-direction[1]).to_degrees().rem_euclid(360.0),
+        residual,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extract::ImageTransform;
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn gradient() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let img = gen::gradient(60, 20, teal, coral);
+
+        let gradient = Distil::gradient(&img).unwrap();
+        assert!((gradient.angle - 90.0).abs() < 1.0);
+        assert!(delta_e(gradient.start, teal) < 3.0);
+        assert!(delta_e(gradient.end, coral) < 3.0);
+        assert!(gradient.residual < 2.0);
+        assert!(gradient.css().starts_with("linear-gradient(90deg, "));
+
+        let down = Distil::gradient(&ImageTransform::Rotate90.apply(&img)).unwrap();
+        assert!((down.angle - 180.0).abs() < 1.0);
+        assert!(delta_e(down.start, teal) < 3.0);
+
+        let up = Distil::gradient(&ImageTransform::Rotate270.apply(&img)).unwrap();
+        assert!(up.angle < 1.0 || up.angle > 359.0);
+
+        assert_eq!(Distil::gradient(&gen::solid(60, 20, teal)), None);
+        assert_eq!(Distil::gradient(&gen::blocks(60, 20, &[(teal, 1), (coral, 1)])), None);
+
+        let (distilled, detected) = Distil::from_image_with_gradient(&img).unwrap();
+        assert!(!distilled.colors.is_empty());
+        assert_eq!(detected, Some(gradient));
+    }
+}
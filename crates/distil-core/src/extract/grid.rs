@@ -0,0 +1,66 @@
+use lab::Lab;
+
+use crate::{Color, Image};
+
+/// Splits `img` into a `columns` × `rows` grid and returns the average color
+/// of each cell, row by row. Cells are averaged in Lab space and fully
+/// transparent pixels are ignored.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::Image;
+/// use distil_core::extract::color_grid;
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let grid = color_grid(&img, 4, 4);
+///     assert_eq!(grid.len(), 16);
+/// }
+/// ```
+pub fn color_grid(img: &Image, columns: u32, rows: u32) -> Vec<Color> {
+    lab_grid(img, columns, rows)
+        .into_iter()
+        .map(|lab| lab.map_or(Color::new(0, 0, 0), Color::from))
+        .collect()
+}
+
+/// Like `color_grid`, but returns the Lab averages before they're rounded
+/// into colors, or `None` for cells with no opaque pixels.
+pub(crate) fn lab_grid(img: &Image, columns: u32, rows: u32) -> Vec<Option<Lab>> {
+    let (columns, rows) = (columns.max(1), rows.max(1));
+    let (width, height) = img.dimensions();
+
+    let mut sums = vec![([0.0f32; 3], 0.0f32); columns as usize * rows as usize];
+
+    for (i, px) in img.pixels().enumerate() {
+        if px[3] == 0 {
+            continue;
+        }
+
+        let (x, y) = ((i % width as usize) as u32, (i / width as usize) as u32);
+        let column = (x as u64 * columns as u64 / width as u64) as u32;
+        let row = (y as u64 * rows as u64 / height as u64) as u32;
+        let lab = Lab::from_rgb(&[px[0], px[1], px[2]]);
+        let cell = &mut sums[row as usize * columns as usize + column as usize];
+
+        cell.0[0] += lab.l;
+        cell.0[1] += lab.a;
+        cell.0[2] += lab.b;
+        cell.1 += 1.0;
+    }
+
+    sums.iter()
+        .map(|&(sum, count)| {
+            if count == 0.0 {
+                return None;
+            }
+
+            Some(Lab {
+                l: sum[0] / count,
+                a: sum[1] / count,
+                b: sum[2] / count,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use lab::Lab;
+
+use super::{has_transparency, is_black, is_white, MAX_WHITE, MIN_ALPHA, MIN_BLACK};
+use crate::{DistilError, Image};
+
+/// The number of bits of each channel used to bucket pixels.
+static HISTOGRAM_BITS: u8 = 5;
+
+/// The number of most-populated buckets that make it into a palette, in
+/// line with the size of the palette NeuQuant produces.
+static HISTOGRAM_PALETTE_SIZE: usize = 256;
+
+/// Counts the interesting pixels of an image by bucketing them on the top
+/// `HISTOGRAM_BITS` bits of their channels, keeping the sum of each bucket's
+/// pixels so that it can be averaged back into a color.
+///
+/// Unlike NeuQuant, pixels can be added and removed one at a time, which is
+/// what makes incremental and tiled extraction possible.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Histogram {
+    buckets: BTreeMap<usize, ([u64; 3], u64)>,
+}
+
+impl Histogram {
+    /// Adds the interesting pixels of `img` to the histogram.
+    pub(crate) fn add_image(&mut self, img: &Image) {
+        for rgba in img.pixels() {
+            self.add(rgba);
+        }
+    }
+
+    /// Removes the interesting pixels of `img` from the histogram. They're
+    /// expected to have been added before.
+    pub(crate) fn remove_image(&mut self, img: &Image) {
+        for rgba in img.pixels() {
+            self.remove(rgba);
+        }
+    }
+
+    /// Adds `rgba` to the histogram if it's interesting.
+    pub(crate) fn add(&mut self, rgba: [u8; 4]) {
+        self.add_weighted(rgba, 1);
+    }
+
+    /// Adds the interesting pixels of `img` to the histogram, each counting
+    /// as `weight` pixels.
+    pub(crate) fn add_image_weighted(&mut self, img: &Image, weight: u64) {
+        for rgba in img.pixels() {
+            self.add_weighted(rgba, weight);
+        }
+    }
+
+    /// Adds `rgba` to the histogram as `weight` pixels if it's interesting.
+    pub(crate) fn add_weighted(&mut self, rgba: [u8; 4], weight: u64) {
+        if is_interesting(&rgba) {
+            self.insert(rgba, weight);
+        }
+    }
+
+    /// Adds `rgba` to the histogram whether it's interesting or not, for
+    /// pixels the configuration has already filtered.
+    pub(crate) fn add_unfiltered(&mut self, rgba: [u8; 4]) {
+        self.insert(rgba, 1);
+    }
+
+    fn insert(&mut self, rgba: [u8; 4], weight: u64) {
+        let (sum, count) = self.buckets.entry(bucket(&rgba)).or_insert(([0; 3], 0));
+
+        for (channel, &value) in sum.iter_mut().zip(&rgba) {
+            *channel += value as u64 * weight;
+        }
+
+        *count += weight;
+    }
+
+    /// Removes `rgba` from the histogram if it's interesting.
+    pub(crate) fn remove(&mut self, rgba: [u8; 4]) {
+        if !is_interesting(&rgba) {
+            return;
+        }
+
+        let key = bucket(&rgba);
+
+        if let Some((sum, count)) = self.buckets.get_mut(&key) {
+            for (channel, &value) in sum.iter_mut().zip(&rgba) {
+                *channel = channel.saturating_sub(value as u64);
+            }
+
+            *count = count.saturating_sub(1);
+
+            if *count == 0 {
+                self.buckets.remove(&key);
+            }
+        }
+    }
+
+    /// Returns the average color of the bucket `rgba` falls into, or `None`
+    /// if that bucket is empty.
+    pub(crate) fn average(&self, rgba: &[u8]) -> Option<[u8; 3]> {
+        self.buckets.get(&bucket(rgba)).map(|&(sum, count)| sum.map(|channel| (channel / count) as u8))
+    }
+
+    /// Returns the average Lab color and count of the
+    /// `HISTOGRAM_PALETTE_SIZE` most populated buckets, in the same shape as
+    /// the quantizers produce.
+    pub(crate) fn colors(&self) -> Result<Vec<(Lab, usize)>, DistilError> {
+        if self.buckets.is_empty() {
+            return Err(DistilError::Uninteresting);
+        }
+
+        let mut buckets: Vec<&([u64; 3], u64)> = self.buckets.values().collect();
+        buckets.sort_by_key(|&&(_, count)| std::cmp::Reverse(count));
+
+        Ok(buckets.into_iter()
+            .take(HISTOGRAM_PALETTE_SIZE)
+            .map(|&(sum, count)| (Lab::from_rgb(&sum.map(|channel| (channel / count) as u8)), count as usize))
+            .collect())
+    }
+}
+
+fn is_interesting(rgba: &[u8; 4]) -> bool {
+    !(has_transparency(rgba, MIN_ALPHA) || is_black(rgba, MIN_BLACK) || is_white(rgba, MAX_WHITE))
+}
+
+fn bucket(rgba: &[u8]) -> usize {
+    let shift = 8 - HISTOGRAM_BITS;
+
+    ((rgba[0] >> shift) as usize) << (2 * HISTOGRAM_BITS) | ((rgba[1] >> shift) as usize) << HISTOGRAM_BITS |
+    (rgba[2] >> shift) as usize
+}
@@ -0,0 +1,138 @@
+use super::distil_colors;
+use super::events::{PaletteChange, Subscribers};
+use super::histogram::Histogram;
+use crate::{Distil, DistilError, Image};
+
+/// Keeps the color histogram of an image around so that its palette can be
+/// updated as the image is edited, without distilling the whole image again.
+///
+/// Palettes are built from a histogram of every pixel rather than with
+/// NeuQuant, so they can differ slightly from those of `Distil::from_image`.
+#[derive(Debug, Clone)]
+pub struct IncrementalDistil {
+    img: Image,
+    histogram: Histogram,
+    subscribers: Subscribers,
+}
+
+impl IncrementalDistil {
+    /// Counts the colors of `img`. This is the only step that goes through
+    /// every pixel of the image.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::IncrementalDistil;
+    /// use distil_core::{gen, Color};
+    ///
+    /// let canvas = gen::solid(64, 64, Color::new(0, 128, 128));
+    /// let mut incremental = IncrementalDistil::new(canvas);
+    /// let before = incremental.distil().unwrap();
+    ///
+    /// let stroke = gen::solid(8, 8, Color::new(230, 57, 70));
+    /// let after = incremental.update(10, 10, &stroke).unwrap();
+    /// assert_eq!(after.colors.len(), before.colors.len() + 1);
+    /// ```
+    pub fn new(img: Image) -> IncrementalDistil {
+        let mut histogram = Histogram::default();
+        histogram.add_image(&img);
+
+        IncrementalDistil {
+            img,
+            histogram,
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// Returns the image as it currently is, with every update applied.
+    pub fn image(&self) -> &Image {
+        &self.img
+    }
+
+    /// Returns the palette of the image as it currently is.
+    pub fn distil(&self) -> Result<Distil, DistilError> {
+        Ok(distil_colors(self.histogram.colors()?))
+    }
+
+    /// Calls `callback` with the palette of each update that's at least
+    /// `threshold` away, on average in CIEDE2000, from the palette it was
+    /// last called with, e.g. to refresh a swatch panel only when a stroke
+    /// changes the palette noticeably. It's called with the first updated
+    /// palette too.
+    pub fn on_change<F: Fn(&PaletteChange) + Send + Sync + 'static>(&mut self, threshold: f32, callback: F) {
+        self.subscribers.add(threshold, callback);
+    }
+
+    /// Replaces the region of the image whose top-left corner is at `x`, `y`
+    /// with `pixels` and returns the updated palette. Only the pixels of the
+    /// region are counted again. Parts of `pixels` that fall outside of the
+    /// image are ignored. The `on_change` callbacks the palette is far
+    /// enough away for are called.
+    pub fn update(&mut self, x: u32, y: u32, pixels: &Image) -> Result<Distil, DistilError> {
+        let (width, height) = self.img.dimensions();
+
+        if x >= width || y >= height {
+            return self.distil();
+        }
+
+        let previous = self.img.crop(x, y, pixels.width(), pixels.height());
+        self.histogram.remove_image(&previous);
+
+        for py in 0..previous.height() {
+            for px in 0..previous.width() {
+                let rgba = pixels.get_pixel(px, py);
+
+                if x + px < width && y + py < height {
+                    self.img.put_pixel(x + px, y + py, rgba);
+                    self.histogram.add(rgba);
+                }
+            }
+        }
+
+        let palette = self.distil()?;
+        self.subscribers.notify(&palette);
+
+        Ok(palette)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IncrementalDistil;
+    use crate::{gen, Color};
+
+    #[test]
+    fn update() {
+        let mut img = gen::solid(20, 20, Color::new(0, 128, 128));
+        let mut incremental = IncrementalDistil::new(img.clone());
+
+        let stroke = gen::solid(6, 6, Color::new(230, 57, 70));
+        let updated = incremental.update(16, 16, &stroke).unwrap();
+
+        for x in 16..20 {
+            for y in 16..20 {
+                img.put_pixel(x, y, [230, 57, 70, 255]);
+            }
+        }
+
+        let recomputed = IncrementalDistil::new(img.clone()).distil().unwrap();
+
+        assert_eq!(incremental.image(), &img);
+        assert_eq!(updated.colors, recomputed.colors);
+        assert_eq!(updated.counts, recomputed.counts);
+        assert_eq!(updated.counts[1], 16);
+    }
+
+    #[test]
+    fn update_outside() {
+        let img = gen::solid(10, 10, Color::new(0, 128, 128));
+        let mut incremental = IncrementalDistil::new(img.clone());
+        let before = incremental.distil().unwrap();
+
+        let stroke = gen::solid(4, 4, Color::new(230, 57, 70));
+        let after = incremental.update(12, 0, &stroke).unwrap();
+
+        assert_eq!(incremental.image(), &img);
+        assert_eq!(after.colors, before.colors);
+    }
+}
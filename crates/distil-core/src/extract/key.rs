@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::DistilBuilder;
+use crate::{Color, Image, IntoLab};
+
+/// Key colors with a Lab chroma below this aren't considered keys when
+/// detecting them, since a grey or white border is most likely part of the
+/// picture.
+static MIN_KEY_CHROMA: f32 = 20.0;
+
+/// The share of an image's edge a color has to cover, once detected, to be
+/// considered a key. A keyed background surrounds its subject, so most of
+/// the edge is expected to be the key.
+static MIN_KEY_EDGE_SHARE: f32 = 0.5;
+
+/// The background color of green- or blue-screen footage, removed before
+/// extraction with `DistilBuilder::chroma_key`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChromaKey {
+    /// The usual chroma-key green, RGB(0, 177, 64).
+    Green,
+
+    /// The usual chroma-key blue, RGB(0, 71, 187).
+    Blue,
+
+    /// A specific key color.
+    Color(Color),
+
+    /// The most common color along the edges of each image, as long as it
+    /// covers most of them and is saturated enough to be a key. Images
+    /// without one are left untouched.
+    Detect,
+}
+
+impl DistilBuilder {
+    /// Removes the keyed background of green- or blue-screen footage before
+    /// extraction: every pixel within a CIEDE2000 distance of `tolerance` of
+    /// the key color is ignored, on top of any colors excluded with
+    /// `exclude_colors`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::ChromaKey;
+    /// use distil_core::{delta_e, gen, Color, Distil};
+    ///
+    /// let green = Color::new(0, 177, 64);
+    /// let frame = gen::blocks(60, 20, &[(green, 2), (Color::new(200, 150, 120), 1)]);
+    /// let distilled = Distil::builder().chroma_key(ChromaKey::Detect, 15.0).from_image(&frame).unwrap();
+    ///
+    /// assert!(distilled.colors.iter().all(|&color| delta_e(color, green) > 15.0));
+    /// ```
+    pub fn chroma_key(mut self, key: ChromaKey, tolerance: f32) -> DistilBuilder {
+        self.key = Some((key, tolerance.max(0.0)));
+        self
+    }
+
+    /// Returns the configuration to distil `img` with, excluding the key
+    /// color if there is one.
+    pub(super) fn keyed(&self, img: &Image) -> Cow<'_, DistilBuilder> {
+        let (key, tolerance) = match self.key {
+            Some(key) => key,
+            None => return Cow::Borrowed(self),
+        };
+
+        let lab = match key {
+            ChromaKey::Green => Some(Color::new(0, 177, 64).into_lab()),
+            ChromaKey::Blue => Some(Color::new(0, 71, 187).into_lab()),
+            ChromaKey::Color(color) => Some(color.into_lab()),
+            ChromaKey::Detect => detect_key(img, tolerance),
+        };
+
+        match lab {
+            Some(lab) => {
+                let mut keyed = self.clone();
+                keyed.excluded.push((lab, tolerance));
+                Cow::Owned(keyed)
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
+}
+
+/// Returns the opaque edge pixel of `img` with the most other edge pixels
+/// within `tolerance` of it, if those cover most of the edge and it's
+/// saturated enough to be a key color.
+fn detect_key(img: &Image, tolerance: f32) -> Option<Lab> {
+    let (width, height) = img.dimensions();
+
+    let edge: Vec<Lab> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| x == 0 || y == 0 || x + 1 == width || y + 1 == height)
+        .map(|(x, y)| img.get_pixel(x, y))
+        .filter(|px| px[3] == 255)
+        .map(|px| Lab::from_rgb(&[px[0], px[1], px[2]]))
+        .collect();
+
+    edge.iter()
+        .map(|&lab| (lab, edge.iter().filter(|&&other| DE2000::new(lab, other) < tolerance).count()))
+        .max_by_key(|&(_, count)| count)
+        .filter(|&(lab, count)| {
+            count as f32 > MIN_KEY_EDGE_SHARE * edge.len() as f32 &&
+            (lab.a * lab.a + lab.b * lab.b).sqrt() >= MIN_KEY_CHROMA
+        })
+        .map(|(lab, _)| lab)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChromaKey;
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn chroma_key() {
+        let (blue, orange) = (Color::new(0, 71, 187), Color::new(210, 160, 40));
+        let blue_screen = gen::jitter(&gen::blocks(60, 20, &[(blue, 2), (orange, 1)]), 6, 1);
+        let grey = gen::blocks(60, 20, &[(Color::new(128, 128, 128), 1), (orange, 1)]);
+
+        for key in &[ChromaKey::Blue, ChromaKey::Detect] {
+            let keyed = Distil::builder().chroma_key(*key, 15.0).from_image(&blue_screen).unwrap();
+
+            assert!(delta_e(keyed.colors[0], orange) < 2.0);
+            assert!(keyed.colors.iter().all(|&color| delta_e(color, blue) > 15.0));
+        }
+
+        let untouched = Distil::builder().chroma_key(ChromaKey::Detect, 15.0).from_image(&grey).unwrap();
+        assert_eq!(untouched.colors, Distil::from_image(&grey).unwrap().colors);
+    }
+}
@@ -0,0 +1,234 @@
+use lab::Lab;
+
+use super::quantizer::{Quantizer, QUANTIZED_COLORS};
+use crate::gen::Rng;
+use crate::lab_to_rgb;
+
+static KMEANS_ITERATIONS: usize = 10;
+static KMEANS_TOLERANCE: f32 = 0.5;
+static KMEANS_SEED: u64 = 1;
+
+/// The number of bits kept of each channel when pixels are gathered into
+/// the histogram k-means runs on.
+static KMEANS_HISTOGRAM_BITS: u32 = 5;
+
+/// K-means clustering in Lab space. It runs on a histogram of the sampled
+/// pixels, each bucket weighted by the pixels in it, rather than on the
+/// pixels themselves, which makes it fast enough for real-time use. Seeded
+/// with k-means++ from a fixed seed, so the same image always gives the
+/// same palette, unlike NeuQuant on noisy images.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KMeansQuantizer {
+    /// The number of clusters. Defaults to 64.
+    pub colors: usize,
+
+    /// The maximum number of times the clusters are moved. Defaults to 10.
+    pub iterations: usize,
+
+    /// The Lab distance every cluster has to move less than for k-means to
+    /// have converged and stop before `iterations`. Defaults to `0.5`.
+    pub tolerance: f32,
+
+    /// The seed of the k-means++ seeding. Defaults to 1.
+    pub seed: u64,
+}
+
+impl Default for KMeansQuantizer {
+    fn default() -> KMeansQuantizer {
+        KMeansQuantizer {
+            colors: QUANTIZED_COLORS,
+            iterations: KMEANS_ITERATIONS,
+            tolerance: KMEANS_TOLERANCE,
+            seed: KMEANS_SEED,
+        }
+    }
+}
+
+impl Quantizer for KMeansQuantizer {
+    fn name(&self) -> &'static str {
+        "k-means"
+    }
+
+    fn quantize(&self, pixels: &[u8]) -> Vec<[u8; 3]> {
+        self.quantize_from(pixels, &[])
+    }
+}
+
+impl KMeansQuantizer {
+    /// Like `quantize`, starting the clusters at `initial`, e.g. the colors
+    /// of a prior palette, before seeding the rest with k-means++. Clusters
+    /// that start close to where they end up converge in an iteration or
+    /// two.
+    pub(super) fn quantize_from(&self, pixels: &[u8], initial: &[Lab]) -> Vec<[u8; 3]> {
+        let (buckets, pixel_buckets) = histogram(pixels);
+
+        if buckets.is_empty() {
+            return Vec::new();
+        }
+
+        let colors = self.colors.max(1);
+        let initial = initial[..initial.len().min(colors)].to_vec();
+        let mut centroids = seeds_after(&buckets, initial, colors, self.seed);
+        let mut assignments = vec![0; buckets.len()];
+
+        for _ in 0..self.iterations.max(1) {
+            for (assignment, &(lab, _)) in assignments.iter_mut().zip(&buckets) {
+                *assignment = nearest(&centroids, lab);
+            }
+
+            let mut sums = vec![([0.0; 3], 0.0); centroids.len()];
+
+            for (&i, &(lab, weight)) in assignments.iter().zip(&buckets) {
+                let (sum, total) = &mut sums[i];
+                *sum = [sum[0] + weight * lab.l, sum[1] + weight * lab.a, sum[2] + weight * lab.b];
+                *total += weight;
+            }
+
+            let mut shift: f32 = 0.0;
+
+            for (centroid, (sum, total)) in centroids.iter_mut().zip(sums) {
+                if total > 0.0 {
+                    let moved = Lab { l: sum[0] / total, a: sum[1] / total, b: sum[2] / total };
+                    shift = shift.max(distance(*centroid, moved).sqrt());
+                    *centroid = moved;
+                }
+            }
+
+            if shift < self.tolerance {
+                break;
+            }
+        }
+
+        for (assignment, &(lab, _)) in assignments.iter_mut().zip(&buckets) {
+            *assignment = nearest(&centroids, lab);
+        }
+
+        let rgbs: Vec<[u8; 3]> = centroids.iter().map(|&centroid| lab_to_rgb(centroid)).collect();
+
+        pixel_buckets.iter().map(|&bucket| rgbs[assignments[bucket]]).collect()
+    }
+}
+
+/// Gathers `pixels` into buckets of similar colors, returning the average
+/// color of each bucket in Lab with the number of pixels in it, and the
+/// bucket of each pixel.
+pub(super) fn histogram(pixels: &[u8]) -> (Vec<(Lab, f32)>, Vec<usize>) {
+    let shift = 8 - KMEANS_HISTOGRAM_BITS;
+    let mut sums = vec![([0u32; 3], 0u32); 1 << (3 * KMEANS_HISTOGRAM_BITS)];
+    let mut keys = Vec::with_capacity(pixels.len() / 4);
+
+    for rgba in pixels.chunks_exact(4) {
+        let key = ((rgba[0] >> shift) as usize) << (2 * KMEANS_HISTOGRAM_BITS) |
+                  ((rgba[1] >> shift) as usize) << KMEANS_HISTOGRAM_BITS | (rgba[2] >> shift) as usize;
+        let (sum, count) = &mut sums[key];
+
+        *sum = [sum[0] + rgba[0] as u32, sum[1] + rgba[1] as u32, sum[2] + rgba[2] as u32];
+        *count += 1;
+        keys.push(key);
+    }
+
+    let mut indices = vec![0; sums.len()];
+    let mut buckets = Vec::new();
+
+    for (key, &(sum, count)) in sums.iter().enumerate() {
+        if count > 0 {
+            indices[key] = buckets.len();
+            buckets.push((Lab::from_rgb(&sum.map(|channel| ((channel + count / 2) / count) as u8)), count as f32));
+        }
+    }
+
+    (buckets, keys.into_iter().map(|key| indices[key]).collect())
+}
+
+/// Picks up to `k` seeds with k-means++: each bucket is picked with a
+/// probability proportional to its weight and its squared distance to the
+/// seeds before it.
+pub(super) fn seeds(buckets: &[(Lab, f32)], k: usize, seed: u64) -> Vec<Lab> {
+    seeds_after(buckets, Vec::with_capacity(k), k, seed)
+}
+
+/// Like `seeds`, keeping `seeds` and picking the rest of the `k` seeds away
+/// from them.
+fn seeds_after(buckets: &[(Lab, f32)], mut seeds: Vec<Lab>, k: usize, seed: u64) -> Vec<Lab> {
+    let mut rng = Rng::new(seed);
+    let mut distances: Vec<f32> = if seeds.is_empty() {
+        vec![1.0; buckets.len()]
+    } else {
+        buckets.iter()
+            .map(|&(bucket, _)| seeds.iter().map(|&seed| distance(bucket, seed)).fold(f32::INFINITY, f32::min))
+            .collect()
+    };
+
+    while seeds.len() < k {
+        let total: f32 = buckets.iter().zip(&distances).map(|(&(_, weight), &d)| weight * d).sum();
+
+        if total <= 0.0 {
+            break;
+        }
+
+        let mut target = (rng.next() >> 11) as f32 / (1u64 << 53) as f32 * total;
+        let mut picked = distances.iter().rposition(|&d| d > 0.0).unwrap_or(0);
+
+        for (i, (&(_, weight), &d)) in buckets.iter().zip(&distances).enumerate() {
+            target -= weight * d;
+
+            if target < 0.0 && d > 0.0 {
+                picked = i;
+                break;
+            }
+        }
+
+        let lab = buckets[picked].0;
+        seeds.push(lab);
+
+        for (d, &(bucket, _)) in distances.iter_mut().zip(buckets) {
+            *d = if seeds.len() == 1 { distance(bucket, lab) } else { d.min(distance(bucket, lab)) };
+        }
+    }
+
+    seeds
+}
+
+pub(super) fn nearest(centroids: &[Lab], lab: Lab) -> usize {
+    centroids.iter()
+        .map(|&centroid| distance(lab, centroid))
+        .enumerate()
+        .min_by(|(_, x), (_, y)| x.total_cmp(y))
+        .map_or(0, |(i, _)| i)
+}
+
+/// The squared Euclidean distance between two Lab colors.
+fn distance(x: Lab, y: Lab) -> f32 {
+    (x.l - y.l).powi(2) + (x.a - y.a).powi(2) + (x.b - y.b).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        let pixels: Vec<u8> = (0..400u32).flat_map(|i| [(i * 7 % 256) as u8, (i * 13 % 256) as u8, (i * 29 % 256) as u8, 255]).collect();
+        let quantizer = KMeansQuantizer { colors: 8, ..KMeansQuantizer::default() };
+
+        let quantized = quantizer.quantize(&pixels);
+        let mut colors = quantized.clone();
+        colors.sort();
+        colors.dedup();
+
+        assert_eq!(quantized.len(), 400);
+        assert!(colors.len() <= 8);
+        assert_eq!(quantizer.quantize(&pixels), quantized);
+    }
+
+    #[test]
+    fn seeds_distinct_colors() {
+        let teal = [0, 128, 128, 255];
+        let coral = [255, 127, 80, 255];
+        let pixels = [teal, teal, teal, coral].concat();
+
+        // Only two buckets, so asking for more clusters can't seed more.
+        assert_eq!(seeds(&histogram(&pixels).0, 8, KMEANS_SEED).len(), 2);
+        assert_eq!(KMeansQuantizer::default().quantize(&pixels), vec![[0, 128, 128], [0, 128, 128], [0, 128, 128], [255, 127, 80]]);
+    }
+}
@@ -0,0 +1,149 @@
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::debug::Merge;
+use super::DistilBuilder;
+use crate::{Color, Distil};
+
+/// An entry of a palette kept verbatim by `DistilBuilder::lock`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Lock {
+    index: usize,
+    color: Color,
+    lab: Lab,
+}
+
+impl DistilBuilder {
+    /// Locks the entries of `palette` at `indices`, e.g. the colors a
+    /// designer picked from a palette distilled before, so that distilling
+    /// again with new settings keeps them verbatim and at the same index
+    /// while the rest of the palette is recomputed.
+    ///
+    /// The quantized colors of the image that are similar to a locked
+    /// entry are counted towards it rather than kept, the way anchors are
+    /// with `Distil::from_image_with_anchors`. Indices out of range are
+    /// ignored, and entries locked before are replaced.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let img = gen::blocks(40, 20, &[(Color::new(0, 128, 128), 2), (Color::new(255, 127, 80), 1), (Color::new(240, 200, 40), 1)]);
+    /// let draft = Distil::from_image(&img).unwrap();
+    ///
+    /// let refined = Distil::builder().min_distance(25.0).lock(&draft, &[1]).from_image(&img).unwrap();
+    ///
+    /// assert_eq!(refined.colors[1], draft.colors[1]);
+    /// ```
+    pub fn lock(mut self, palette: &Distil, indices: &[usize]) -> DistilBuilder {
+        let len = palette.colors.len().min(palette.lab_colors.len());
+        let mut indices: Vec<usize> = indices.iter().copied().filter(|&index| index < len).collect();
+
+        indices.sort_unstable();
+        indices.dedup();
+
+        self.locked = indices.into_iter()
+            .map(|index| Lock { index, color: palette.colors[index], lab: palette.lab_colors[index] })
+            .collect();
+
+        self
+    }
+
+    /// Like `merged`, after counting the colors of `palette` similar to a
+    /// locked entry towards it. The locked entries come first, in the order
+    /// of their indices.
+    pub(super) fn merged_around_locks(&self, palette: Vec<(Lab, usize)>, merges: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+        if self.locked.is_empty() {
+            return self.merged(palette, merges);
+        }
+
+        let mut locked: Vec<(Lab, usize)> = self.locked.iter().map(|lock| (lock.lab, 0)).collect();
+        let mut others = Vec::new();
+
+        for (lab, count) in palette {
+            let nearest = self.locked.iter()
+                .map(|lock| DE2000::new(lab, lock.lab))
+                .enumerate()
+                .filter(|&(_, delta)| delta < self.min_distance)
+                .min_by(|&(_, a), &(_, b)| a.total_cmp(&b))
+                .map(|(i, _)| i);
+
+            match nearest {
+                Some(i) => locked[i].1 += count,
+                None => others.push((lab, count)),
+            }
+        }
+
+        locked.extend(self.merged(others, merges));
+        locked
+    }
+
+    /// Moves the locked entries at the start of `distil`, as returned by
+    /// `merged_around_locks`, back to their indices with their colors
+    /// restored verbatim. An entry whose index is past the end of the
+    /// palette goes last.
+    pub(super) fn placed_locks(&self, mut distil: Distil) -> Distil {
+        let locked = self.locked.len().min(distil.colors.len());
+
+        if locked == 0 {
+            return distil;
+        }
+
+        for (i, lock) in self.locked.iter().take(locked).enumerate() {
+            distil.colors[i] = lock.color;
+            distil.lab_colors[i] = lock.lab;
+        }
+
+        // Inserting the locks in the order of their indices leaves each one
+        // at its index, as the ones after it are only ever inserted later.
+        let mut order: Vec<usize> = (locked..distil.colors.len()).collect();
+
+        for (i, lock) in self.locked.iter().take(locked).enumerate() {
+            order.insert(lock.index.min(order.len()), i);
+        }
+
+        Distil {
+            colors: order.iter().map(|&i| distil.colors[i]).collect(),
+            counts: order.iter().map(|&i| distil.counts[i]).collect(),
+            lab_colors: order.iter().map(|&i| distil.lab_colors[i]).collect(),
+            spreads: order.iter().map(|&i| distil.spreads[i]).collect(),
+            weights: order.iter().map(|&i| distil.weights[i]).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lab::Lab;
+
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn lock() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let gold = Color::new(240, 200, 40);
+        let img = gen::blocks(40, 20, &[(teal, 2), (coral, 1), (gold, 1)]);
+
+        let draft = Distil::builder().deterministic(true).from_image(&img).unwrap();
+        assert_eq!(draft.colors.len(), 3);
+
+        // A locked entry keeps its color and index, even one that's been
+        // nudged away from the image's own color.
+        let mut nudged = draft.clone();
+        nudged.colors[2] = Color::new(250, 130, 90);
+        nudged.lab_colors[2] = Lab::from_rgb(&[250, 130, 90]);
+
+        let refined = Distil::builder().deterministic(true).min_distance(12.0).lock(&nudged, &[2, 7]).from_image(&img).unwrap();
+
+        assert_eq!(refined.colors.len(), 3);
+        assert_eq!(refined.colors[2], Color::new(250, 130, 90));
+        assert_eq!(refined.lab_colors[2], nudged.lab_colors[2]);
+        assert!(refined.colors[..2].iter().all(|&color| delta_e(color, nudged.colors[2]) >= 12.0));
+        assert!((refined.weights.iter().sum::<f32>() - 1.0).abs() < 0.001);
+
+        let unlocked = Distil::builder().deterministic(true).lock(&draft, &[]).from_image(&img).unwrap();
+        assert_eq!(unlocked.colors, draft.colors);
+    }
+}
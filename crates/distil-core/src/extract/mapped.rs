@@ -0,0 +1,103 @@
+use std::fs::File;
+use std::io::Cursor;
+use std::path::Path;
+
+use memmap2::Mmap;
+use png::{ColorType, Decoder, Transformations};
+
+use super::distil_colors;
+use super::histogram::Histogram;
+use crate::{imaging, Distil, DistilError};
+
+static PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+impl Distil {
+    /// `from_path_mapped` memory-maps the image at `path` rather than reading
+    /// it, for very large files.
+    ///
+    /// PNGs are decoded a row at a time and counted into a histogram, so the
+    /// decoded image is never held in memory in full. Only PNGs are streamed:
+    /// TIFFs, JPEGs and the other formats are decoded in full from the mapped
+    /// file, which saves reading the file but not the memory of the decoded
+    /// image. Either way the palette is built from a histogram of every pixel
+    /// rather than with NeuQuant, so it can differ slightly from that of
+    /// `from_path`.
+    ///
+    /// The file must not be modified while it's being distilled.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::Distil;
+    ///
+    /// let path = Path::new("./images/img-1.jpg");
+    ///
+    /// if let Ok(distilled) = Distil::from_path_mapped(&path) {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn from_path_mapped(path: &Path) -> Result<Distil, DistilError> {
+        let io_err = |err: String| DistilError::Io(format!("{:?}", path), err);
+
+        let file = File::open(path).map_err(|err| io_err(err.to_string()))?;
+
+        // Safety: the map is only read from, and modifying the file while
+        // it's mapped is documented as unsupported above.
+        let bytes = unsafe { Mmap::map(&file) }.map_err(|err| io_err(err.to_string()))?;
+
+        let histogram = if bytes.starts_with(&PNG_SIGNATURE) {
+            png_histogram(&bytes).map_err(|err| io_err(err.to_string()))?
+        } else {
+            let mut histogram = Histogram::default();
+            histogram.add_image(&imaging::decode(&bytes, &format!("{:?}", path))?);
+            histogram
+        };
+
+        Ok(distil_colors(histogram.colors()?))
+    }
+}
+
+/// Counts the pixels of a PNG into a histogram one row at a time.
+fn png_histogram(bytes: &[u8]) -> Result<Histogram, png::DecodingError> {
+    let mut decoder = Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info()?;
+    let (color_type, _) = reader.output_color_type();
+    let mut histogram = Histogram::default();
+
+    while let Some(row) = reader.next_row()? {
+        let data = row.data();
+
+        match color_type {
+            ColorType::Grayscale => data.iter().for_each(|&l| histogram.add([l, l, l, 255])),
+            ColorType::GrayscaleAlpha => data.chunks(2).for_each(|la| histogram.add([la[0], la[0], la[0], la[1]])),
+            ColorType::Rgb => data.chunks(3).for_each(|rgb| histogram.add([rgb[0], rgb[1], rgb[2], 255])),
+            _ => data.chunks(4).for_each(|rgba| histogram.add([rgba[0], rgba[1], rgba[2], rgba[3]])),
+        }
+    }
+
+    Ok(histogram)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{Distil, DistilError, Image};
+
+    #[test]
+    fn from_path_mapped() {
+        let img = Image::open(Path::new("./images/img-1.jpg")).unwrap();
+        let png = std::env::temp_dir().join("distil-img-1-mapped.png");
+        img.save(&png).unwrap();
+
+        let from_png = Distil::from_path_mapped(&png).unwrap();
+        let from_jpeg = Distil::from_path_mapped(Path::new("./images/img-1.jpg")).unwrap();
+
+        assert_eq!(from_png.colors, from_jpeg.colors);
+        assert!(matches!(Distil::from_path_mapped(Path::new("./tests/pure-white.png")),
+                         Err(DistilError::Uninteresting)));
+    }
+}
@@ -0,0 +1,97 @@
+use super::quantizer::{Quantizer, QUANTIZED_COLORS};
+
+/// Median-cut, as popularized by Color Thief: the box of pixels with the
+/// most pixels spread over the widest channel is split at its median,
+/// until there are `colors` boxes. Fast and deterministic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MedianCutQuantizer {
+    /// The number of boxes. Defaults to 64.
+    pub colors: usize,
+}
+
+impl Default for MedianCutQuantizer {
+    fn default() -> MedianCutQuantizer {
+        MedianCutQuantizer { colors: QUANTIZED_COLORS }
+    }
+}
+
+impl Quantizer for MedianCutQuantizer {
+    fn name(&self) -> &'static str {
+        "median-cut"
+    }
+
+    fn quantize(&self, pixels: &[u8]) -> Vec<[u8; 3]> {
+        let rgbs: Vec<[u8; 3]> = pixels.chunks_exact(4).map(|rgba| [rgba[0], rgba[1], rgba[2]]).collect();
+        let mut boxes = vec![rgbs];
+
+        while boxes.len() < self.colors.max(1) {
+            let next = boxes.iter()
+                .enumerate()
+                .map(|(i, rgbs)| (i, widest_channel(rgbs)))
+                .filter(|&(_, (_, range))| range > 0)
+                .max_by_key(|&(i, (_, range))| boxes[i].len() * range as usize);
+
+            let (i, channel) = match next {
+                Some((i, (channel, _))) => (i, channel),
+                None => break,
+            };
+
+            let mut lower = boxes.swap_remove(i);
+            lower.sort_unstable_by_key(|rgb| rgb[channel]);
+
+            // Split between two values, so that pixels of the same color
+            // never end up in different boxes.
+            let median = lower[lower.len() / 2][channel];
+            let mut at = lower.partition_point(|rgb| rgb[channel] < median);
+
+            if at == 0 {
+                at = lower.partition_point(|rgb| rgb[channel] <= median);
+            }
+
+            let upper = lower.split_off(at);
+            boxes.push(lower);
+            boxes.push(upper);
+        }
+
+        boxes.iter()
+            .flat_map(|rgbs| std::iter::repeat(average(rgbs)).take(rgbs.len()))
+            .collect()
+    }
+}
+
+/// Returns the channel the colors of `rgbs` span the most of, and how much.
+fn widest_channel(rgbs: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let min = rgbs.iter().map(|rgb| rgb[channel]).min().unwrap_or(0);
+            let max = rgbs.iter().map(|rgb| rgb[channel]).max().unwrap_or(0);
+            (channel, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn average(rgbs: &[[u8; 3]]) -> [u8; 3] {
+    let count = rgbs.len().max(1) as u64;
+    let sum = rgbs.iter().fold([0u64; 3], |sum, rgb| [sum[0] + rgb[0] as u64, sum[1] + rgb[1] as u64, sum[2] + rgb[2] as u64]);
+
+    sum.map(|channel| ((channel + count / 2) / count) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_the_median() {
+        let pixels = [[10, 0, 0, 255], [10, 0, 0, 255], [10, 0, 0, 255], [200, 0, 0, 255]].concat();
+        let quantized = MedianCutQuantizer { colors: 4 }.quantize(&pixels);
+
+        let mut colors = quantized.clone();
+        colors.sort();
+        colors.dedup();
+
+        assert_eq!(colors, vec![[10, 0, 0], [200, 0, 0]]);
+        assert_eq!(quantized.iter().filter(|&&rgb| rgb == [10, 0, 0]).count(), 3);
+    }
+}
@@ -0,0 +1,376 @@
+use std::fmt;
+use std::sync::Arc;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::plugin::Plugin;
+use super::{remove_similar_colors_with_merges, DistilBuilder, Merge};
+use crate::palette::{balance_colors, by_weight, with_members_by};
+use crate::Distil;
+
+static FUZZINESS: f32 = 2.0;
+static MIN_FUZZINESS: f32 = 1.1;
+static FUZZY_ITERATIONS: usize = 10;
+
+/// Merges the similar colors of a quantized palette into the colors of the
+/// final palette. Decoding, sampling and quantizing stay the same whichever
+/// strategy is used, so strategies can be compared on equal footing.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::extract::{Merge, MergeStrategy};
+/// use distil_core::{Distil, Lab};
+///
+/// /// Keeps the 5 most frequent colors and drops the rest.
+/// #[derive(Debug)]
+/// struct TopFive;
+///
+/// impl MergeStrategy for TopFive {
+///     fn merge(&self, mut palette: Vec<(Lab, usize)>, _: f32, _: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+///         palette.truncate(5);
+///         palette
+///     }
+/// }
+///
+/// if let Ok(distilled) = Distil::builder().merge_strategy(TopFive).from_path_str("./images/img-1.jpg") {
+///     assert!(distilled.colors.len() <= 5);
+/// }
+/// ```
+pub trait MergeStrategy: fmt::Debug + Send + Sync {
+    /// Merges `palette`, sorted from the most to the least frequent color,
+    /// and returns the merged palette sorted the same way. `min_distance` is
+    /// the CIEDE2000 distance set with `DistilBuilder::min_distance`. Each
+    /// merge should be recorded in `merges` for `DistilBuilder::debug`.
+    fn merge(&self, palette: Vec<(Lab, usize)>, min_distance: f32, merges: &mut Vec<Merge>) -> Vec<(Lab, usize)>;
+
+    /// Returns how much a pixel belongs to each color of the merged
+    /// palette, summing to `1.0`, given its squared Lab distances to them.
+    /// The weights and spreads of the palette are measured with these. By
+    /// default a pixel belongs only to the color it's closest to.
+    fn memberships(&self, distances: &[f32]) -> Vec<f32> {
+        let mut memberships = vec![0.0; distances.len()];
+        let nearest = distances.iter().enumerate().min_by(|&(_, a), &(_, b)| a.total_cmp(b));
+
+        if let Some((i, _)) = nearest {
+            memberships[i] = 1.0;
+        }
+
+        memberships
+    }
+}
+
+/// The default strategy: each color is merged into the first more frequent
+/// color it's within `min_distance` of, in passes until no two colors are
+/// that close. Fast, but the result depends on the order of the colors.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GreedyMerge;
+
+impl MergeStrategy for GreedyMerge {
+    fn merge(&self, palette: Vec<(Lab, usize)>, min_distance: f32, merges: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+        remove_similar_colors_with_merges(palette, min_distance, merges)
+    }
+}
+
+/// Agglomerative clustering: the two closest colors are merged, one pair at
+/// a time, until no two colors are within `min_distance` of each other.
+/// Independent of the order of the colors, but slower than `GreedyMerge`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AgglomerativeMerge;
+
+impl MergeStrategy for AgglomerativeMerge {
+    fn merge(&self, palette: Vec<(Lab, usize)>, min_distance: f32, merges: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+        let mut clusters: Vec<Option<(Lab, usize)>> = palette.into_iter().map(Some).collect();
+        let len = clusters.len();
+
+        // Only the upper triangle is used, with merged away clusters at an
+        // infinite distance.
+        let mut distances = vec![f32::INFINITY; len * len];
+
+        for i in 0..len {
+            for j in i + 1..len {
+                distances[i * len + j] = distance(clusters[i], clusters[j]);
+            }
+        }
+
+        loop {
+            let closest = (0..len)
+                .flat_map(|i| (i + 1..len).map(move |j| (i, j)))
+                .min_by(|&(i, j), &(k, l)| distances[i * len + j].total_cmp(&distances[k * len + l]));
+
+            let (i, j) = match closest {
+                Some((i, j)) if distances[i * len + j] < min_distance => (i, j),
+                _ => break,
+            };
+
+            let (x, y) = match (clusters[i], clusters[j]) {
+                (Some(x), Some(y)) => (x, y),
+                _ => break,
+            };
+
+            // The less frequent color is merged into the more frequent one.
+            let (from, into) = if y.1 > x.1 { (x, y) } else { (y, x) };
+
+            merges.push(Merge {
+                color: from.0,
+                count: from.1,
+                into: into.0,
+                distance: distances[i * len + j],
+            });
+
+            clusters[i] = Some(balance_colors(into, from));
+            clusters[j] = None;
+
+            for k in 0..len {
+                if k != i {
+                    let (a, b) = (i.min(k), i.max(k));
+                    distances[a * len + b] = distance(clusters[a], clusters[b]);
+                }
+
+                if k != j {
+                    let (a, b) = (j.min(k), j.max(k));
+                    distances[a * len + b] = f32::INFINITY;
+                }
+            }
+        }
+
+        let mut merged: Vec<(Lab, usize)> = clusters.into_iter().flatten().collect();
+        merged.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        merged
+    }
+}
+
+fn distance(x: Option<(Lab, usize)>, y: Option<(Lab, usize)>) -> f32 {
+    match (x, y) {
+        (Some((lab_x, _)), Some((lab_y, _))) => DE2000::new(lab_x, lab_y),
+        _ => f32::INFINITY,
+    }
+}
+
+/// Fuzzy c-means: starts from the colors of `GreedyMerge`, then moves them
+/// to where the colors of the palette belong to them fractionally, by how
+/// close they are to each. Pixels count towards every color of the palette
+/// in the same way, so the weights of gradients are shared between their
+/// colors rather than won by the nearest one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyMerge {
+    /// How fuzzy memberships are, over `1.0`. The higher it is, the more
+    /// evenly colors belong to the palette colors around them. Defaults to
+    /// `2.0`.
+    pub fuzziness: f32,
+
+    /// The number of times the colors are moved. Defaults to 10.
+    pub iterations: usize,
+}
+
+impl Default for FuzzyMerge {
+    fn default() -> FuzzyMerge {
+        FuzzyMerge {
+            fuzziness: FUZZINESS,
+            iterations: FUZZY_ITERATIONS,
+        }
+    }
+}
+
+impl MergeStrategy for FuzzyMerge {
+    fn merge(&self, palette: Vec<(Lab, usize)>, min_distance: f32, merges: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+        let mut centers: Vec<Lab> = GreedyMerge.merge(palette.clone(), min_distance, merges)
+            .into_iter()
+            .map(|(lab, _)| lab)
+            .collect();
+
+        let exponent = self.fuzziness.max(MIN_FUZZINESS);
+        let mut counts = vec![0.0; centers.len()];
+
+        for iteration in 0..=self.iterations {
+            let mut sums = vec![([0.0; 3], 0.0); centers.len()];
+            counts = vec![0.0; centers.len()];
+
+            for &(lab, count) in &palette {
+                let distances: Vec<f32> = centers.iter().map(|&center| squared_distance(lab, center)).collect();
+
+                for ((i, membership), (sum, total)) in self.memberships(&distances).into_iter().enumerate().zip(&mut sums) {
+                    let weight = count as f32 * membership.powf(exponent);
+                    *sum = [sum[0] + weight * lab.l, sum[1] + weight * lab.a, sum[2] + weight * lab.b];
+                    *total += weight;
+                    counts[i] += count as f32 * membership;
+                }
+            }
+
+            // The last pass only measures the counts of the final colors.
+            if iteration == self.iterations {
+                break;
+            }
+
+            for (center, (sum, total)) in centers.iter_mut().zip(sums) {
+                if total > 0.0 {
+                    *center = Lab { l: sum[0] / total, a: sum[1] / total, b: sum[2] / total };
+                }
+            }
+        }
+
+        let mut merged: Vec<(Lab, usize)> = centers.into_iter()
+            .zip(counts)
+            .map(|(center, count)| (center, count.round() as usize))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+
+        merged.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        merged
+    }
+
+    fn memberships(&self, distances: &[f32]) -> Vec<f32> {
+        // A color on top of a palette color belongs to it alone.
+        if distances.contains(&0.0) {
+            return distances.iter().map(|&distance| if distance == 0.0 { 1.0 } else { 0.0 }).collect();
+        }
+
+        let power = 1.0 / (self.fuzziness.max(MIN_FUZZINESS) - 1.0);
+        let memberships: Vec<f32> = distances.iter().map(|&distance| distance.powf(-power)).collect();
+        let total: f32 = memberships.iter().sum();
+
+        memberships.into_iter().map(|membership| membership / total).collect()
+    }
+}
+
+fn squared_distance(x: Lab, y: Lab) -> f32 {
+    (x.l - y.l).powi(2) + (x.a - y.a).powi(2) + (x.b - y.b).powi(2)
+}
+
+/// Keeps every quantized color as is, e.g. to see what the other strategies
+/// merge away.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NoMerge;
+
+impl MergeStrategy for NoMerge {
+    fn merge(&self, palette: Vec<(Lab, usize)>, _min_distance: f32, _merges: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+        palette
+    }
+}
+
+impl fmt::Debug for Plugin<dyn MergeStrategy> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(ref strategy) => strategy.fmt(f),
+            None => GreedyMerge.fmt(f),
+        }
+    }
+}
+
+impl DistilBuilder {
+    /// Sets how the similar colors of the quantized palette are merged.
+    /// Defaults to `GreedyMerge`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::AgglomerativeMerge;
+    /// use distil_core::Distil;
+    ///
+    /// let builder = Distil::builder().merge_strategy(AgglomerativeMerge);
+    ///
+    /// if let Ok(distilled) = builder.from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn merge_strategy<S: MergeStrategy + 'static>(mut self, strategy: S) -> DistilBuilder {
+        self.merge_strategy = Plugin(Some(Arc::new(strategy)));
+        self
+    }
+
+    /// Merges `palette` with the configured strategy, recording each merge
+    /// into `merges`.
+    pub(super) fn merged(&self, palette: Vec<(Lab, usize)>, merges: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+        match self.merge_strategy.0 {
+            Some(ref strategy) => strategy.merge(palette, self.min_distance, merges),
+            None => GreedyMerge.merge(palette, self.min_distance, merges),
+        }
+    }
+
+    /// Sets the weights and spreads of `distil` from `members`, with the
+    /// memberships of the configured strategy, and orders it by weight. The
+    /// locked entries at its start are left there for `placed_locks`.
+    pub(super) fn with_members(&self, distil: Distil, members: &[(Lab, f32, f32)]) -> Distil {
+        let distil = match self.merge_strategy.0 {
+            Some(ref strategy) => with_members_by(distil, members, strategy.as_ref()),
+            None => with_members_by(distil, members, &GreedyMerge),
+        };
+
+        by_weight(distil, self.locked.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::WuQuantizer;
+    use crate::{gen, Color};
+
+    fn palette() -> Vec<(Lab, usize)> {
+        // Red and a slightly darker red, then a red that's close to the dark
+        // one but not to the first, and blue.
+        vec![
+            (Lab { l: 50.0, a: 70.0, b: 50.0 }, 40),
+            (Lab { l: 44.0, a: 70.0, b: 50.0 }, 30),
+            (Lab { l: 37.0, a: 70.0, b: 50.0 }, 20),
+            (Lab { l: 30.0, a: 20.0, b: -60.0 }, 10),
+        ]
+    }
+
+    #[test]
+    fn strategies() {
+        let total = |palette: &[(Lab, usize)]| palette.iter().map(|&(_, count)| count).sum::<usize>();
+
+        let mut merges = Vec::new();
+        let none = NoMerge.merge(palette(), 10.0, &mut merges);
+        assert_eq!(none, palette());
+        assert!(merges.is_empty());
+
+        for strategy in [&GreedyMerge as &dyn MergeStrategy, &AgglomerativeMerge] {
+            let mut merges = Vec::new();
+            let merged = strategy.merge(palette(), 10.0, &mut merges);
+
+            assert_eq!(total(&merged), 100);
+            assert_eq!(merged.len() + merges.len(), palette().len());
+
+            for (i, &(lab_x, _)) in merged.iter().enumerate() {
+                for &(lab_y, _) in &merged[i + 1..] {
+                    assert!(DE2000::new(lab_x, lab_y) >= 10.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn builder() {
+        let greedy = Distil::builder();
+        let agglomerative = Distil::builder().merge_strategy(AgglomerativeMerge);
+
+        assert_eq!(greedy, Distil::builder());
+        assert_eq!(agglomerative.clone(), agglomerative);
+        assert_ne!(greedy, agglomerative);
+        assert_eq!(format!("{:?}", agglomerative.merge_strategy), "AgglomerativeMerge");
+    }
+
+    #[test]
+    fn fuzzy() {
+        let fuzzy = FuzzyMerge::default();
+        let memberships = fuzzy.memberships(&[1.0, 4.0]);
+
+        assert!((memberships[0] - 0.8).abs() < 1e-6);
+        assert!((memberships[1] - 0.2).abs() < 1e-6);
+        assert_eq!(fuzzy.memberships(&[0.0, 4.0]), vec![1.0, 0.0]);
+
+        // Each pixel of a gradient shares its weight between the colors
+        // around it, so no color wins as much of it.
+        let img = gen::gradient(100, 10, Color::new(0, 128, 128), Color::new(255, 127, 80));
+        let builder = Distil::builder().quantizer(WuQuantizer::default());
+        let hard = builder.from_image(&img).unwrap();
+        let soft = builder.merge_strategy(fuzzy).from_image(&img).unwrap();
+        let max = |distil: &Distil| distil.weights.iter().cloned().fold(0.0, f32::max);
+
+        assert!((soft.weights.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!(max(&soft) < max(&hard));
+    }
+}
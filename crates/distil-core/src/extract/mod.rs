@@ -0,0 +1,811 @@
+//! Distilling images into palettes.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use self::histogram::Histogram;
+use crate::palette::{balance_colors, distil_palette, with_members};
+use crate::{imaging, Distil, DistilError, Image};
+
+pub use self::anchors::AnchoredDistil;
+pub use self::animated::GifFrame;
+pub use self::builder::DistilBuilder;
+pub use self::channels::ChannelHistograms;
+pub use self::chapters::ChapterPalettes;
+pub use self::crops::{crop_stability, suggest_crop, Crop, CropDominant, CropStability, CropTarget, SuggestedCrop};
+pub use self::debug::{Artifacts, Filtered, Merge};
+pub use self::dir::DirConfig;
+pub use self::events::PaletteChange;
+pub use self::filter::PixelFilter;
+pub use self::gradient::Gradient;
+pub use self::grid::color_grid;
+pub use self::incremental::IncrementalDistil;
+pub use self::key::ChromaKey;
+pub use self::kmeans::KMeansQuantizer;
+pub use self::median_cut::MedianCutQuantizer;
+pub use self::merge::{AgglomerativeMerge, FuzzyMerge, GreedyMerge, MergeStrategy, NoMerge};
+pub use self::modes::{lightness_modes, LightnessModes};
+pub use self::orientation::{ImageTransform, OrientationReport};
+#[cfg(feature = "neuquant")]
+pub use self::quantizer::NeuQuantQuantizer;
+pub use self::quantizer::{HistogramQuantizer, Quantizer};
+pub use self::regions::Region;
+pub use self::sampling::SamplingStrategy;
+pub use self::signature::{signature, similarity, Signature};
+pub use self::stream::StreamDistiller;
+pub use self::warnings::DistilWarning;
+pub use self::wallpaper::{wallpaper_suitability, WallpaperScore};
+pub use self::wu::WuQuantizer;
+
+mod anchors;
+mod animated;
+mod background;
+mod builder;
+mod channels;
+mod chapters;
+mod crops;
+mod debug;
+mod depth;
+mod deterministic;
+mod dir;
+mod events;
+mod filter;
+mod gradient;
+mod grid;
+mod histogram;
+mod incremental;
+mod key;
+mod kmeans;
+mod lock;
+#[cfg(feature = "mmap")]
+mod mapped;
+mod median_cut;
+mod merge;
+mod orientation;
+mod plugin;
+mod quantizer;
+mod modes;
+mod regions;
+mod roi;
+mod sampling;
+mod signature;
+mod stream;
+mod wallpaper;
+mod warm;
+mod warnings;
+mod wu;
+
+static MAX_SAMPLE_COUNT: u32 = 1000;
+#[cfg(feature = "neuquant")]
+static NQ_SAMPLE_FACTION: i32 = 10;
+#[cfg(feature = "neuquant")]
+static NQ_PALETTE_SIZE: usize = 256;
+static MIN_ALPHA: u8 = 255;
+static MIN_BLACK: u8 = 8;
+static MAX_WHITE: u8 = 247;
+pub(crate) static MIN_DISTANCE_FOR_UNIQUENESS: f32 = 10.0;
+
+/// Configures `Distil::from_path_pyramid`.
+#[derive(Debug, Clone)]
+pub struct PyramidConfig {
+    /// The maximum number of pixels sampled at each level of the pyramid.
+    /// Each level is distilled independently.
+    pub sample_counts: Vec<u32>,
+
+    /// The fraction of levels, between `0.0` and `1.0`, that a color needs
+    /// to appear in to be kept in the final palette.
+    pub consensus: f32,
+
+    /// The CIEDE2000 distance under which colors from two different levels
+    /// are considered to be the same color.
+    pub tolerance: f32,
+}
+
+impl Default for PyramidConfig {
+    fn default() -> PyramidConfig {
+        PyramidConfig {
+            sample_counts: vec![MAX_SAMPLE_COUNT / 4, MAX_SAMPLE_COUNT, MAX_SAMPLE_COUNT * 4],
+            consensus: 0.66,
+            tolerance: MIN_DISTANCE_FOR_UNIQUENESS,
+        }
+    }
+}
+
+impl Distil {
+    /// `from_path_str` takes a path to an image which exists locally on the
+    /// system and `Distil`s it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let path_str = "./images/img-1.jpg";
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str(path_str) {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn from_path_str(path_str: &str) -> Result<Distil, DistilError> {
+        let path = Path::new(&path_str);
+        Distil::from_path(path)
+    }
+
+    /// `from_path` takes a `&Path` to an image which exists locally on the
+    /// system and `Distil`s it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::Distil;
+    ///
+    /// let path = Path::new("./images/img-1.jpg");
+    ///
+    /// if let Ok(distilled) = Distil::from_path(&path) {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn from_path(path: &Path) -> Result<Distil, DistilError> {
+        let img = Image::open(path)?;
+        Distil::new(&img)
+    }
+
+    /// `from_bytes` takes the encoded bytes of an image, e.g. an upload that
+    /// was never written to disk, and `Distil`s it. The format is guessed
+    /// from the bytes themselves.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::fs;
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(bytes) = fs::read("./images/img-1.jpg") {
+    ///     let distilled = Distil::from_bytes(&bytes).unwrap();
+    /// }
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Distil, DistilError> {
+        let img = imaging::decode(bytes, "bytes")?;
+        Distil::new(&img)
+    }
+
+    /// `from_reader` reads an encoded image from `reader`, e.g. the body of
+    /// an HTTP request, and `Distil`s it like `from_bytes`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(file) = File::open("./images/img-1.jpg") {
+    ///     let distilled = Distil::from_reader(file).unwrap();
+    /// }
+    /// ```
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Distil, DistilError> {
+        let mut bytes = Vec::new();
+
+        reader.read_to_end(&mut bytes)
+            .map_err(|err| DistilError::Io("reader".to_owned(), err.to_string()))?;
+
+        let img = imaging::decode(&bytes, "reader")?;
+        Distil::new(&img)
+    }
+
+    /// `from_image` `Distil`s an already decoded image. With the
+    /// `image-interop` feature enabled, an `image::DynamicImage` can be
+    /// passed with `&Image::from(dynamic_image)`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let img = gen::solid(10, 10, Color::new(0, 128, 128));
+    /// let distilled = Distil::from_image(&img).unwrap();
+    /// ```
+    pub fn from_image(img: &Image) -> Result<Distil, DistilError> {
+        Distil::new(img)
+    }
+
+    /// `from_raw_rgba` `Distil`s raw RGBA channels, row by row, e.g. a frame
+    /// read back from a GPU. Fails with `DistilError::InvalidBuffer` if
+    /// `data` isn't `width * height * 4` bytes long.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let data = [0, 128, 128, 255].repeat(100);
+    /// let distilled = Distil::from_raw_rgba(10, 10, &data).unwrap();
+    /// ```
+    pub fn from_raw_rgba(width: u32, height: u32, data: &[u8]) -> Result<Distil, DistilError> {
+        let img = Image::from_rgba(width, height, data.to_vec())?;
+        Distil::new(&img)
+    }
+
+    /// `from_tiles` distils an image that's too large to decode at once
+    /// from its tiles, e.g. those of a deep-zoom tile set, counting the
+    /// colors of each tile into a histogram before dropping it. Only one
+    /// tile is held in memory at a time.
+    ///
+    /// Every pixel of every tile is counted, so tiles should come from a
+    /// single zoom level. The first error returned by `tiles` is returned.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Distil, Image};
+    ///
+    /// let tiles = (0..4).map(|i| format!("./images/tiles/0_{}.jpg", i));
+    ///
+    /// if let Ok(distilled) = Distil::from_tiles(tiles.map(|path| Image::open(Path::new(&path)))) {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn from_tiles<I>(tiles: I) -> Result<Distil, DistilError>
+        where I: IntoIterator<Item = Result<Image, DistilError>>
+    {
+        let mut histogram = Histogram::default();
+
+        for tile in tiles {
+            histogram.add_image(&tile?);
+        }
+
+        Ok(distil_colors(histogram.colors()?))
+    }
+
+    /// `from_path_pyramid` distils the image at `path` at each of the
+    /// resolutions described by `config` and only keeps the colors that
+    /// appear consistently across those resolutions.
+    ///
+    /// Colors that only show up at a single scale tend to be produced by
+    /// noise, compression artefacts or very fine detail rather than being
+    /// representative of the image, so this trades a few extra passes for a
+    /// cleaner palette.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Distil, PyramidConfig};
+    ///
+    /// let path = Path::new("./images/img-1.jpg");
+    /// let config = PyramidConfig {
+    ///     consensus: 1.0,
+    ///     ..PyramidConfig::default()
+    /// };
+    ///
+    /// if let Ok(distilled) = Distil::from_path_pyramid(&path, &config) {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn from_path_pyramid(path: &Path, config: &PyramidConfig) -> Result<Distil, DistilError> {
+        let img = Image::open(path)?;
+        Distil::new_pyramid(&img, config)
+    }
+
+    fn new(img: &Image) -> Result<Distil, DistilError> {
+        Distil::new_with(img, &DistilBuilder::default())
+    }
+
+    fn new_with(img: &Image, config: &DistilBuilder) -> Result<Distil, DistilError> {
+        let scaled_img = config.sampled(img);
+        let config = config.keyed(&scaled_img);
+        let config = config.scaled(img, &scaled_img);
+        let palette = config.merged_around_locks(extract_colors(&scaled_img, &config)?, &mut Vec::new());
+
+        Ok(config.placed_locks(config.with_members(distil_palette(palette), &pixel_members(&scaled_img, &config))))
+    }
+
+    fn new_pyramid(img: &Image, config: &PyramidConfig) -> Result<Distil, DistilError> {
+        let default = DistilBuilder::default();
+        let mut levels = Vec::new();
+
+        for &sample_count in &config.sample_counts {
+            let scaled_img = scale_img(img, sample_count);
+            levels.push((remove_similar_colors(extract_colors(&scaled_img, &default)?, default.min_distance), scaled_img));
+        }
+
+        // The level with the most samples is the most detailed, so it's the
+        // one the other levels get to vote on.
+        let (reference, reference_img) =
+            match levels.iter().enumerate().max_by_key(|&(i, _)| config.sample_counts[i]) {
+                Some((i, _)) => levels.remove(i),
+                None => return Distil::new(img),
+            };
+
+        let members = pixel_members(&reference_img, &default);
+        let levels: Vec<Vec<(Lab, usize)>> = levels.into_iter().map(|(level, _)| level).collect();
+
+        let total_levels = (levels.len() + 1) as f32;
+        let required_levels = (config.consensus.clamp(0.0, 1.0) * total_levels).ceil() as usize;
+
+        let palette: Vec<(Lab, usize)> = reference.iter()
+            .filter(|&&(lab_x, _)| {
+                let appearances = 1 + levels.iter()
+                    .filter(|level| {
+                        level.iter().any(|&(lab_y, _)| DE2000::new(lab_x, lab_y) < config.tolerance)
+                    })
+                    .count();
+
+                appearances >= required_levels
+            })
+            .cloned()
+            .collect();
+
+        // If nothing survives there's no consensus to speak of, so the most
+        // detailed palette is the best answer available.
+        if palette.is_empty() {
+            return Ok(with_members(distil_palette(reference), &members));
+        }
+
+        Ok(with_members(distil_palette(palette), &members))
+    }
+}
+
+/// Quantizes the passed, already scaled, image and returns the Lab colors it
+/// was quantized to along with their counts.
+fn extract_colors(scaled_img: &Image, config: &DistilBuilder) -> Result<Vec<(Lab, usize)>, DistilError> {
+    let quantized_img = quantize(scaled_img, config)?;
+
+    Ok(count_colors_as_lab(quantized_img))
+}
+
+/// Merges similar colors of the passed Lab palette, e.g. the buckets of a
+/// histogram, and measures how much of the histogram and how spread out the
+/// colors merged into each of the resulting colors are.
+fn distil_colors(colors: Vec<(Lab, usize)>) -> Distil {
+    let members: Vec<(Lab, f32, f32)> = colors.iter().map(|&(lab, count)| (lab, count as f32, 0.0)).collect();
+    with_members(distil_palette(remove_similar_colors(colors, MIN_DISTANCE_FOR_UNIQUENESS)), &members)
+}
+
+/// Returns the interesting pixels of the passed image as members for
+/// `with_members`, so that weights and spreads are measured against the image
+/// itself rather than against the quantizer's palette.
+fn pixel_members(img: &Image, config: &DistilBuilder) -> Vec<(Lab, f32, f32)> {
+    get_pixels(img, config)
+        .unwrap_or_default()
+        .chunks(4)
+        .map(|rgba| (Lab::from_rgb(&[rgba[0], rgba[1], rgba[2]]), 1.0, 0.0))
+        .collect()
+}
+
+/// Proportionally scales the passed image to a size where its total number of
+/// pixels does not exceed the value of `sample_count`.
+fn scale_img(img: &Image, sample_count: u32) -> Image {
+    let (width, height) = img.dimensions();
+
+    if width as u64 * height as u64 > sample_count as u64 {
+        let (width, height) = (width as f32, height as f32);
+        let ratio = width / height;
+
+        let scaled_width = ((ratio * (sample_count as f32)).sqrt() as u32).max(1);
+
+        return imaging::resize(img, scaled_width, height as u32);
+    }
+
+    img.clone()
+}
+
+/// Reduces the interesting pixels of the passed image with the quantizer of
+/// `config`, NeuQuant by default.
+fn quantize(img: &Image, config: &DistilBuilder) -> Result<Vec<[u8; 3]>, DistilError> {
+    let pixels = get_pixels(img, config)?;
+
+    Ok(config.quantized(&pixels))
+}
+
+/// Processes each of the pixels in the passed image, filtering out any that are
+/// transparent, too light / dark to be interesting or excluded by `config`,
+/// repeating those in weighted regions or favored by the sampling strategy
+/// and, if `config` weights them by alpha, thinning out semi-transparent
+/// ones, then returns a `Vec` of the
+/// RGBA channels of "interesting" pixels which is intended to be fed into
+/// the quantizer.
+fn get_pixels(img: &Image, config: &DistilBuilder) -> Result<Vec<u8>, DistilError> {
+    let mut pixels = Vec::new();
+
+    let width = img.width().max(1) as usize;
+    let sampling_weights = config.sampling_weights(img);
+
+    // Weights of regions are carried over from pixel to pixel, so that a
+    // region with a weight of 0.5 keeps every other pixel.
+    let mut carry = 0.0;
+
+    for (i, rgba) in img.pixels().enumerate() {
+        if config.filtered(&rgba).is_some() {
+            continue;
+        }
+
+        let alpha = rgba[3];
+
+        // Pixels are passed on as opaque, so that quantizers that learn
+        // alpha don't tell semi-transparent pixels apart from opaque ones.
+        let rgba = [rgba[0], rgba[1], rgba[2], 255];
+
+        if config.regions.is_empty() && !config.alpha_weighted && sampling_weights.is_none() {
+            pixels.extend_from_slice(&rgba);
+            continue;
+        }
+
+        let sampling_weight = sampling_weights.as_ref().map_or(1.0, |weights| weights[i]);
+        carry += config.weight_at((i % width) as u32, (i / width) as u32) * config.alpha_weight(alpha) * sampling_weight;
+
+        while carry >= 1.0 {
+            pixels.extend_from_slice(&rgba);
+            carry -= 1.0;
+        }
+    }
+
+    if pixels.is_empty() {
+        return Err(DistilError::Uninteresting);
+    }
+
+    Ok(pixels)
+}
+
+/// Checks if the passed pixel is too transparent to be interesting, i.e.
+/// its alpha is under `min_alpha`.
+fn has_transparency(rgba: &[u8; 4], min_alpha: u8) -> bool {
+    let alpha_channel = rgba[3];
+
+    alpha_channel < min_alpha
+}
+
+/// Checks if the passed pixel is too dark to be interesting, i.e. all of
+/// its channels are under `min_black`.
+fn is_black(rgba: &[u8; 4], min_black: u8) -> bool {
+    rgba[0] < min_black && rgba[1] < min_black && rgba[2] < min_black
+}
+
+/// Checks if the passed pixel is too light to be interesting, i.e. all of
+/// its channels are over `max_white`.
+fn is_white(rgba: &[u8; 4], max_white: u8) -> bool {
+    rgba[0] > max_white && rgba[1] > max_white && rgba[2] > max_white
+}
+
+/// Maps each unique Lab color in the passed `Vec` of pixels to the total
+/// number of times that color appears in the `Vec`.
+fn count_colors_as_lab(pixels: Vec<[u8; 3]>) -> Vec<(Lab, usize)> {
+    let color_count_map = pixels.iter()
+        .fold(BTreeMap::new(), |mut acc, px| {
+            *acc.entry(px).or_insert(0) += 1;
+            acc
+        });
+
+    let mut color_count_vec = color_count_map.iter()
+        .fold(Vec::new(), |mut acc, (rgb, count)| {
+            acc.push((Lab::from_rgb(rgb), *count as usize));
+            acc
+        });
+
+    color_count_vec.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    color_count_vec
+}
+
+/// Merges colors that are closer than `min_distance` to each other,
+/// weighting each by its count, until no two colors are that close. The
+/// total count of the palette is preserved.
+pub(crate) fn remove_similar_colors(palette: Vec<(Lab, usize)>, min_distance: f32) -> Vec<(Lab, usize)> {
+    remove_similar_colors_with_merges(palette, min_distance, &mut Vec::new())
+}
+
+/// Like `remove_similar_colors`, recording each merge into `merges`.
+fn remove_similar_colors_with_merges(mut palette: Vec<(Lab, usize)>,
+                                     min_distance: f32,
+                                     merges: &mut Vec<Merge>)
+                                     -> Vec<(Lab, usize)> {
+    // Merging moves colors, which can bring two of the remaining ones close
+    // enough to be merged in turn.
+    loop {
+        let len = palette.len();
+        palette = merge_similar_colors(palette, min_distance, merges);
+
+        if palette.len() == len {
+            return palette;
+        }
+    }
+}
+
+/// Merges each color of `palette` into the first color before it that it's
+/// similar to.
+fn merge_similar_colors(palette: Vec<(Lab, usize)>, min_distance: f32, merges: &mut Vec<Merge>) -> Vec<(Lab, usize)> {
+    let mut similars = Vec::new();
+    let mut refined_palette: Vec<(Lab, usize)> = Vec::new();
+
+    for &(lab_x, count_x) in palette.iter() {
+        let mut is_similar = false;
+
+        for (i, &(lab_y, _)) in refined_palette.iter().enumerate() {
+            let delta = DE2000::new(lab_x, lab_y);
+
+            if delta < min_distance {
+                similars.push((i, lab_x, count_x));
+                merges.push(Merge {
+                    color: lab_x,
+                    count: count_x,
+                    into: lab_y,
+                    distance: delta,
+                });
+                is_similar = true;
+                break;
+            }
+        }
+
+        if !is_similar {
+            refined_palette.push((lab_x, count_x));
+        }
+    }
+
+    for &(i, lab_y, count) in &similars {
+        refined_palette[i] = balance_colors(refined_palette[i], (lab_y, count));
+    }
+
+    refined_palette.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    refined_palette
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::needless_borrow)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use delta_e::DE2000;
+    use lab::Lab;
+    use proptest::prelude::*;
+
+    use super::{remove_similar_colors, MIN_DISTANCE_FOR_UNIQUENESS};
+    use crate::{gen, lab_to_rgb, Color, Distil, DistilError, Image, PyramidConfig};
+
+    fn palette() -> impl Strategy<Value = Vec<(Lab, usize)>> {
+        let color = (0.0f32..100.0, -100.0f32..100.0, -100.0f32..100.0, 1usize..1000);
+        prop::collection::vec(color.prop_map(|(l, a, b, count)| (Lab { l, a, b }, count)), 0..40)
+    }
+
+    /// Small images, empty ones included, with random pixels.
+    fn image() -> impl Strategy<Value = Image> {
+        (0u32..12, 0u32..12).prop_flat_map(|(width, height)| {
+            prop::collection::vec(any::<u8>(), (width * height * 4) as usize)
+                .prop_map(move |data| Image::from_rgba(width, height, data).unwrap())
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn remove_similar_colors_invariants(palette in palette()) {
+            let refined = remove_similar_colors(palette.clone(), MIN_DISTANCE_FOR_UNIQUENESS);
+
+            let total = |palette: &[(Lab, usize)]| palette.iter().map(|&(_, count)| count).sum::<usize>();
+            prop_assert_eq!(total(&refined), total(&palette));
+
+            for (i, &(lab_x, _)) in refined.iter().enumerate() {
+                for &(lab_y, _) in &refined[i + 1..] {
+                    prop_assert!(DE2000::new(lab_x, lab_y) >= MIN_DISTANCE_FOR_UNIQUENESS);
+                }
+            }
+
+            prop_assert_eq!(remove_similar_colors(refined.clone(), MIN_DISTANCE_FOR_UNIQUENESS), refined);
+        }
+
+        #[test]
+        fn public_api_never_panics(img in image(), bytes in prop::collection::vec(any::<u8>(), 0..64), text in ".{0,24}",
+                                   (x, y, width, height) in (0u32..16, 0u32..16, 0u32..16, 0u32..16),
+                                   sample_count in 0u32..64) {
+            for builder in [Distil::builder(), Distil::builder().deterministic(true), Distil::builder().sample_count(sample_count)] {
+                if let Ok(distilled) = builder.from_image(&img) {
+                    prop_assert!(!distilled.colors.is_empty());
+                }
+            }
+
+            let _ = Distil::from_raw_rgba(width, height, img.as_raw());
+            let _ = Distil::from_bytes(&bytes);
+            let _ = text.parse::<Color>();
+            let _ = Distil::from_image_region(&img, x, y, width, height);
+            let _ = Distil::from_image_masked(&img, &img.crop(x, y, width, height));
+            let _ = super::IncrementalDistil::new(img.clone()).update(x, y, &img);
+            let _ = super::signature(&img);
+            let _ = super::color_grid(&img, width, height);
+            let _ = super::lightness_modes(&img);
+            let _ = super::wallpaper_suitability(&img);
+            let _ = super::crop_stability(&img);
+            let _ = super::suggest_crop(&img, super::CropTarget::Accent, width, height, 10.0);
+
+            let quantizers: [&dyn super::Quantizer; 4] = [&super::HistogramQuantizer, &super::KMeansQuantizer::default(),
+                                                          &super::MedianCutQuantizer::default(), &super::WuQuantizer::default()];
+
+            for quantizer in quantizers {
+                let _ = quantizer.quantize(&bytes);
+                let _ = quantizer.quantize(img.as_raw());
+            }
+
+            #[cfg(feature = "render")]
+            if let Ok(distilled) = Distil::from_image(&img) {
+                let options = crate::render::PaletteRenderOptions {
+                    colors: x as usize,
+                    swatch_width: width,
+                    swatch_height: height,
+                    spacing: y,
+                    proportional: true,
+                    ..crate::render::PaletteRenderOptions::default()
+                };
+
+                let _ = distilled.render_palette(&options).to_png();
+            }
+        }
+    }
+
+    #[test]
+    fn from_path_str() {
+        let path_str = "./images/img-1.jpg";
+
+        match Distil::from_path_str(path_str) {
+            Ok(distilled) => {
+                #[cfg(feature = "render")]
+                distilled.as_img(&std::env::temp_dir().join("distil-from-path-str.png"), 5).unwrap();
+                #[cfg(not(feature = "render"))]
+                assert!(!distilled.colors.is_empty());
+            }
+            Err(err) => {
+                println!("{}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn from_path() {
+        let path = Path::new("./images/img-1.jpg");
+
+        match Distil::from_path(path) {
+            Ok(distilled) => {
+                #[cfg(feature = "render")]
+                distilled.as_img(&std::env::temp_dir().join("distil-from-path.png"), 5).unwrap();
+                #[cfg(not(feature = "render"))]
+                assert!(!distilled.colors.is_empty());
+            }
+            Err(err) => {
+                println!("{}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn from_bytes() {
+        let bytes = fs::read("./images/img-1.jpg").unwrap();
+        let distilled = Distil::from_path(Path::new("./images/img-1.jpg")).unwrap();
+
+        assert_eq!(Distil::from_bytes(&bytes).unwrap().colors, distilled.colors);
+        assert_eq!(Distil::from_reader(&bytes[..]).unwrap().colors, distilled.colors);
+        assert!(matches!(Distil::from_bytes(b"not an image"), Err(DistilError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn from_raw_rgba() {
+        let img = gen::blocks(20, 10, &[(Color::new(200, 60, 60), 1), (Color::new(60, 60, 200), 1)]);
+
+        assert_eq!(Distil::from_raw_rgba(20, 10, img.as_raw()).unwrap().colors,
+                   Distil::from_image(&img).unwrap().colors);
+        assert!(matches!(Distil::from_raw_rgba(20, 11, img.as_raw()), Err(DistilError::InvalidBuffer)));
+    }
+
+    #[test]
+    fn from_path_pyramid() {
+        let path = Path::new("./images/img-1.jpg");
+        let loose = PyramidConfig { consensus: 0.0, ..PyramidConfig::default() };
+        let strict = PyramidConfig { consensus: 1.0, ..PyramidConfig::default() };
+
+        let all = Distil::from_path_pyramid(path, &loose).unwrap();
+        let consistent = Distil::from_path_pyramid(path, &strict).unwrap();
+
+        assert!(!consistent.colors.is_empty());
+        assert!(consistent.colors.iter().all(|color| all.colors.contains(color)));
+    }
+
+    #[test]
+    fn lab_colors() {
+        let path = Path::new("./images/img-1.jpg");
+        let distilled = Distil::from_path(path).unwrap();
+
+        assert_eq!(distilled.lab_colors.len(), distilled.colors.len());
+
+        for (lab, rgb) in distilled.lab_colors.iter().zip(distilled.colors.iter()) {
+            assert_eq!(lab_to_rgb(*lab), rgb.rgb());
+        }
+    }
+
+    #[test]
+    fn from_tiles() {
+        let img = Image::open(Path::new("./images/img-1.jpg")).unwrap();
+        let (width, height) = img.dimensions();
+
+        let tiles = (0..4).map(|i| Ok(img.crop(i % 2 * width / 2, i / 2 * height / 2, width / 2 + 1, height / 2 + 1)));
+        let distilled = Distil::from_tiles(tiles).unwrap();
+
+        assert!(!distilled.colors.is_empty());
+        assert!(matches!(Distil::from_tiles(vec![Err(DistilError::UnsupportedFormat)]),
+                         Err(DistilError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn spreads() {
+        let blue = Color::new(40, 80, 200);
+        let flat = Distil::from_image(&gen::solid(40, 40, blue)).unwrap();
+        let varied = Distil::from_image(&gen::jitter(&gen::solid(40, 40, blue), 12, 1)).unwrap();
+
+        let spread = |distilled: &Distil| {
+            let (i, _) = distilled.nearest(blue.rgb()).unwrap();
+            distilled.spreads[i]
+        };
+
+        assert_eq!(flat.spreads.len(), flat.colors.len());
+        assert!(spread(&flat) < 2.0);
+        assert!(spread(&varied) > spread(&flat) + 1.0);
+    }
+
+    #[test]
+    fn pure_white() {
+        let path = Path::new("./tests/pure-white.png");
+        let distilled_err = Distil::from_path(&path).unwrap_err();
+
+        match distilled_err {
+            DistilError::Uninteresting => assert!(true),
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn pure_black() {
+        let path = Path::new("./tests/pure-black.png");
+        let distilled_err = Distil::from_path(&path).unwrap_err();
+
+        match distilled_err {
+            DistilError::Uninteresting => assert!(true),
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn gif() {
+        let path = Path::new("./tests/animated.gif");
+
+        assert!(!Distil::from_path(path).unwrap().colors.is_empty());
+    }
+
+    #[test]
+    fn unsupported_format() {
+        let path = Path::new("./Cargo.toml");
+        let distilled_err = Distil::from_path(&path).unwrap_err();
+
+        match distilled_err {
+            DistilError::UnsupportedFormat => assert!(true),
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn remove_similar_colors_until_stable() {
+        // Merging the first two colors moves their average within
+        // `MIN_DISTANCE_FOR_UNIQUENESS` of the third.
+        let palette = vec![
+            (Lab { l: 15.519241, a: -68.10315, b: -55.245266 }, 1),
+            (Lab { l: 23.818726, a: -43.998867, b: -40.3431 }, 1),
+            (Lab { l: 29.289446, a: -41.031403, b: -47.04374 }, 1),
+        ];
+
+        let refined = remove_similar_colors(palette, MIN_DISTANCE_FOR_UNIQUENESS);
+
+        assert_eq!(refined.len(), 1);
+        assert_eq!(refined[0].1, 3);
+    }
+}
+
@@ -0,0 +1,168 @@
+use lab::Lab;
+
+use super::scale_img;
+use crate::{Distil, DistilError, Image};
+
+/// The number of pixels the image is scaled down to when looking at its
+/// lightness.
+static MODES_SAMPLE_COUNT: u32 = 4000;
+
+/// The share of the lightness variance the split between shadows and
+/// highlights has to explain for the image to be considered bimodal. An
+/// evenly spread lightness explains 0.75.
+static MIN_SEPARATION: f32 = 0.85;
+
+/// The smallest share of the image each of the shadows and highlights has to
+/// cover for the image to be considered bimodal.
+static MIN_MODE_SHARE: f32 = 0.1;
+
+/// How the lightness of an image is distributed, as returned by
+/// `lightness_modes`.
+#[derive(Debug, Clone)]
+pub struct LightnessModes {
+    /// `true` if the image is split between clearly separate shadows and
+    /// highlights, e.g. a silhouette against the sky, in which case a single
+    /// palette misrepresents it and `shadows` and `highlights` should be
+    /// used instead.
+    pub is_bimodal: bool,
+
+    /// The share of the image's lightness variance, between `0.0` and `1.0`,
+    /// explained by splitting it at `threshold`.
+    pub separation: f32,
+
+    /// The L* value the image's pixels are split into shadows and highlights
+    /// at.
+    pub threshold: f32,
+
+    /// The share of the image, between `0.0` and `1.0`, darker than
+    /// `threshold`.
+    pub shadow_share: f32,
+
+    /// The palette of the pixels darker than `threshold`, or `None` if none
+    /// of them are interesting.
+    pub shadows: Option<Distil>,
+
+    /// The palette of the pixels at least as light as `threshold`, or `None`
+    /// if none of them are interesting.
+    pub highlights: Option<Distil>,
+}
+
+/// Looks for separate shadow and highlight modes in the lightness of `img`
+/// by splitting it where the two halves are the most distinct (Otsu's
+/// method), and distils each half.
+///
+/// Returns `DistilError::Uninteresting` if `img` has no opaque pixels.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::extract::lightness_modes;
+/// use distil_core::{gen, Color};
+///
+/// let silhouette = gen::blocks(60, 40, &[(Color::new(20, 18, 30), 1), (Color::new(250, 170, 90), 2)]);
+/// let modes = lightness_modes(&silhouette).unwrap();
+///
+/// if modes.is_bimodal {
+///     let (shadows, highlights) = (modes.shadows.unwrap(), modes.highlights.unwrap());
+/// }
+/// ```
+pub fn lightness_modes(img: &Image) -> Result<LightnessModes, DistilError> {
+    let scaled_img = scale_img(img, MODES_SAMPLE_COUNT);
+
+    let lightness: Vec<Option<f32>> = scaled_img.pixels()
+        .map(|px| if px[3] == 0 { None } else { Some(Lab::from_rgb(&[px[0], px[1], px[2]]).l) })
+        .collect();
+
+    let mut histogram = [0usize; 101];
+
+    for &l in lightness.iter().flatten() {
+        histogram[l.round().clamp(0.0, 100.0) as usize] += 1;
+    }
+
+    let total = histogram.iter().sum::<usize>();
+
+    if total == 0 {
+        return Err(DistilError::Uninteresting);
+    }
+
+    let (threshold, separation) = otsu(&histogram, total);
+    let shadow_count = histogram[..threshold].iter().sum::<usize>();
+    let shadow_share = shadow_count as f32 / total as f32;
+
+    let half = |is_shadow: bool| {
+        let mut masked = scaled_img.clone();
+
+        for (px, l) in masked.pixels_mut().zip(&lightness) {
+            if l.map_or(true, |l| (l.round() < threshold as f32) != is_shadow) {
+                px[3] = 0;
+            }
+        }
+
+        Distil::from_image(&masked).ok()
+    };
+
+    Ok(LightnessModes {
+        is_bimodal: separation >= MIN_SEPARATION && shadow_share.min(1.0 - shadow_share) >= MIN_MODE_SHARE,
+        separation,
+        threshold: threshold as f32,
+        shadow_share,
+        shadows: half(true),
+        highlights: half(false),
+    })
+}
+
+/// Returns the bin splitting `histogram` into the two most distinct classes,
+/// i.e. with the highest between-class variance, along with the share of the
+/// total variance that variance makes up.
+fn otsu(histogram: &[usize], total: usize) -> (usize, f32) {
+    let total = total as f64;
+    let mean = histogram.iter().enumerate().map(|(i, &count)| i as f64 * count as f64).sum::<f64>() / total;
+    let variance = histogram.iter()
+        .enumerate()
+        .map(|(i, &count)| (i as f64 - mean).powi(2) * count as f64)
+        .sum::<f64>() / total;
+
+    let mut best = (0, 0.0);
+    let (mut weight, mut sum) = (0.0, 0.0);
+
+    for (i, &count) in histogram.iter().enumerate().take(histogram.len() - 1) {
+        weight += count as f64 / total;
+        sum += i as f64 * count as f64 / total;
+
+        if weight <= 0.0 || weight >= 1.0 {
+            continue;
+        }
+
+        let between = (mean * weight - sum).powi(2) / (weight * (1.0 - weight));
+
+        if between > best.1 {
+            best = (i + 1, between);
+        }
+    }
+
+    if variance == 0.0 {
+        return (best.0, 0.0);
+    }
+
+    (best.0, (best.1 / variance) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lightness_modes;
+    use crate::{delta_e, gen, Color};
+
+    #[test]
+    fn modes() {
+        let silhouette = gen::blocks(60, 40, &[(Color::new(30, 25, 40), 1), (Color::new(250, 170, 90), 2)]);
+        let modes = lightness_modes(&silhouette).unwrap();
+
+        assert!(modes.is_bimodal);
+        assert!((modes.shadow_share - 1.0 / 3.0).abs() < 0.02);
+        assert!(delta_e(modes.shadows.unwrap().colors[0], [30, 25, 40]) < 3.0);
+        assert!(delta_e(modes.highlights.unwrap().colors[0], [250, 170, 90]) < 3.0);
+
+        let ramp = gen::gradient(100, 10, Color::new(0, 0, 0), Color::new(255, 255, 255));
+        assert!(!lightness_modes(&ramp).unwrap().is_bimodal);
+    }
+}
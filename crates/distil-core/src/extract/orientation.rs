@@ -0,0 +1,198 @@
+use super::signature::distil_distance;
+use super::DistilBuilder;
+use crate::{Distil, DistilError, Image};
+
+/// A rotation or a flip of an image, as checked by
+/// `DistilBuilder::orientation_report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageTransform {
+    /// A quarter turn clockwise.
+    Rotate90,
+
+    /// A half turn.
+    Rotate180,
+
+    /// A quarter turn counterclockwise.
+    Rotate270,
+
+    /// A mirror image, left to right.
+    FlipHorizontal,
+
+    /// A mirror image, top to bottom.
+    FlipVertical,
+}
+
+impl ImageTransform {
+    /// Every rotation and flip, in the order `orientation_report` checks
+    /// them.
+    pub fn all() -> [ImageTransform; 5] {
+        [
+            ImageTransform::Rotate90,
+            ImageTransform::Rotate180,
+            ImageTransform::Rotate270,
+            ImageTransform::FlipHorizontal,
+            ImageTransform::FlipVertical,
+        ]
+    }
+
+    /// Returns `img` rotated or flipped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::ImageTransform;
+    /// use distil_core::Image;
+    ///
+    /// let mut img = Image::new(2, 1);
+    /// img.put_pixel(0, 0, [255, 0, 0, 255]);
+    ///
+    /// let rotated = ImageTransform::Rotate90.apply(&img);
+    ///
+    /// assert_eq!(rotated.dimensions(), (1, 2));
+    /// assert_eq!(rotated.get_pixel(0, 0), [255, 0, 0, 255]);
+    /// ```
+    pub fn apply(self, img: &Image) -> Image {
+        let (width, height) = img.dimensions();
+        let (new_width, new_height) = match self {
+            ImageTransform::Rotate90 | ImageTransform::Rotate270 => (height, width),
+            _ => (width, height),
+        };
+
+        let mut transformed = Image::new(new_width, new_height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (new_x, new_y) = match self {
+                    ImageTransform::Rotate90 => (height - 1 - y, x),
+                    ImageTransform::Rotate180 => (width - 1 - x, height - 1 - y),
+                    ImageTransform::Rotate270 => (y, width - 1 - x),
+                    ImageTransform::FlipHorizontal => (width - 1 - x, y),
+                    ImageTransform::FlipVertical => (x, height - 1 - y),
+                };
+
+                transformed.put_pixel(new_x, new_y, img.get_pixel(x, y));
+            }
+        }
+
+        transformed
+    }
+}
+
+/// How far the palette of each rotation and flip of an image is from the
+/// palette of the image itself, as returned by
+/// `DistilBuilder::orientation_report`.
+#[derive(Debug, Clone)]
+pub struct OrientationReport {
+    /// The palette of the image as it is.
+    pub palette: Distil,
+
+    /// Each transform, in the order of `ImageTransform::all`, with the
+    /// share-weighted CIEDE2000 distance between the palette of the
+    /// transformed image and `palette`, both ways.
+    pub distances: Vec<(ImageTransform, f32)>,
+}
+
+impl OrientationReport {
+    /// Returns the transform whose palette is the furthest from the
+    /// original's, along with its distance.
+    pub fn worst(&self) -> Option<(ImageTransform, f32)> {
+        self.distances.iter().copied().max_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    /// Whether the palette of every transform is within `tolerance` of the
+    /// original's.
+    pub fn is_invariant(&self, tolerance: f32) -> bool {
+        self.distances.iter().all(|&(_, distance)| distance <= tolerance)
+    }
+}
+
+impl DistilBuilder {
+    /// Distils `img` and each of its rotations and flips with this
+    /// configuration and reports how far their palettes are from each
+    /// other's, to check that a pipeline doesn't depend on the orientation
+    /// of its images, e.g. after configuring `sampling`.
+    ///
+    /// Some configurations aren't meant to be invariant: `regions` stay
+    /// where they are while the image turns underneath them, and
+    /// `pixel_filter`s see the pixels in a different order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::SamplingStrategy;
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let img = gen::blocks(60, 30, &[(Color::new(0, 128, 128), 2), (Color::new(255, 127, 80), 1)]);
+    /// let builder = Distil::builder().deterministic(true).sampling(SamplingStrategy::CenterWeighted);
+    ///
+    /// let report = builder.orientation_report(&img).unwrap();
+    /// assert!(report.is_invariant(2.0), "{:?}", report.worst());
+    /// ```
+    pub fn orientation_report(&self, img: &Image) -> Result<OrientationReport, DistilError> {
+        let palette = self.from_image(img)?;
+        let distances = ImageTransform::all()
+            .iter()
+            .map(|&transform| {
+                let transformed = self.from_image(&transform.apply(img))?;
+                Ok((transform, distil_distance(&palette, &transformed)))
+            })
+            .collect::<Result<_, DistilError>>()?;
+
+        Ok(OrientationReport { palette, distances })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImageTransform;
+    use crate::extract::SamplingStrategy;
+    use crate::{gen, Color, Distil, Image};
+
+    #[test]
+    fn transforms() {
+        let mut img = Image::new(3, 2);
+        img.put_pixel(0, 0, [255, 0, 0, 255]);
+
+        let corner = |transform: ImageTransform| {
+            let transformed = transform.apply(&img);
+            let (width, _) = transformed.dimensions();
+            let i = transformed.pixels().position(|px| px[3] == 255).unwrap() as u32;
+
+            (transformed.dimensions(), (i % width, i / width))
+        };
+
+        assert_eq!(corner(ImageTransform::Rotate90), ((2, 3), (1, 0)));
+        assert_eq!(corner(ImageTransform::Rotate180), ((3, 2), (2, 1)));
+        assert_eq!(corner(ImageTransform::Rotate270), ((2, 3), (0, 2)));
+        assert_eq!(corner(ImageTransform::FlipHorizontal), ((3, 2), (2, 0)));
+        assert_eq!(corner(ImageTransform::FlipVertical), ((3, 2), (0, 1)));
+
+        for transform in ImageTransform::all() {
+            let mut back = transform.apply(&img);
+
+            while back != img {
+                back = transform.apply(&back);
+            }
+        }
+    }
+
+    #[test]
+    fn invariance() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let gold = Color::new(240, 200, 40);
+        let img = gen::checkerboard(48, 32, 8, teal, coral);
+        let striped = gen::blocks(60, 30, &[(teal, 3), (coral, 2), (gold, 1)]);
+
+        for sampling in [SamplingStrategy::Uniform, SamplingStrategy::CenterWeighted, SamplingStrategy::EdgeEnergy] {
+            let builder = Distil::builder().deterministic(true).sampling(sampling);
+
+            for img in [&img, &striped] {
+                let report = builder.orientation_report(img).unwrap();
+
+                assert_eq!(report.distances.len(), ImageTransform::all().len());
+                assert!(report.is_invariant(2.0), "{:?} {:?}", sampling, report.worst());
+            }
+        }
+    }
+}
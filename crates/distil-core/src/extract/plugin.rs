@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+/// A trait object plugged into a `DistilBuilder`, e.g. its
+/// `MergeStrategy`, `None` being the default. Two builders only have equal
+/// plugins when both use the default or they share the same one.
+pub(super) struct Plugin<T: ?Sized>(pub(super) Option<Arc<T>>);
+
+impl<T: ?Sized> Clone for Plugin<T> {
+    fn clone(&self) -> Plugin<T> {
+        Plugin(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Default for Plugin<T> {
+    fn default() -> Plugin<T> {
+        Plugin(None)
+    }
+}
+
+impl<T: ?Sized> PartialEq for Plugin<T> {
+    fn eq(&self, other: &Plugin<T>) -> bool {
+        match (&self.0, &other.0) {
+            (Some(x), Some(y)) => Arc::as_ptr(x) as *const () == Arc::as_ptr(y) as *const (),
+            (x, y) => x.is_none() && y.is_none(),
+        }
+    }
+}
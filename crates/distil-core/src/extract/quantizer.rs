@@ -0,0 +1,264 @@
+use std::fmt;
+use std::sync::Arc;
+
+#[cfg(feature = "neuquant")]
+use color_quant::NeuQuant;
+#[cfg(feature = "neuquant")]
+use itertools::Itertools;
+
+use super::histogram::Histogram;
+use super::kmeans::KMeansQuantizer;
+use super::wu::WuQuantizer;
+use super::plugin::Plugin;
+#[cfg(feature = "neuquant")]
+use super::{NQ_PALETTE_SIZE, NQ_SAMPLE_FACTION};
+use super::DistilBuilder;
+
+/// The number of colors the k-means, median-cut and Wu quantizers reduce an
+/// image to by default, before similar ones are merged.
+pub(super) static QUANTIZED_COLORS: usize = 64;
+
+/// Reduces the sampled pixels of an image to a handful of colors, which are
+/// then counted and merged into the palette by the `MergeStrategy`.
+/// Decoding, sampling and merging stay the same whichever quantizer is
+/// used, so they can be traded for accuracy or speed per use case.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::extract::Quantizer;
+/// use distil_core::Distil;
+///
+/// /// Rounds each channel to the nearest multiple of 64.
+/// #[derive(Debug)]
+/// struct Posterize;
+///
+/// impl Quantizer for Posterize {
+///     fn name(&self) -> &'static str {
+///         "posterize"
+///     }
+///
+///     fn quantize(&self, pixels: &[u8]) -> Vec<[u8; 3]> {
+///         let round = |channel: u8| ((channel as u32 + 32) / 64 * 64).min(255) as u8;
+///
+///         pixels.chunks_exact(4).map(|rgba| [round(rgba[0]), round(rgba[1]), round(rgba[2])]).collect()
+///     }
+/// }
+///
+/// if let Ok(distilled) = Distil::builder().quantizer(Posterize).from_path_str("./images/img-1.jpg") {
+///     // Do something with the returned `Distil` struct…
+/// }
+/// ```
+pub trait Quantizer: fmt::Debug + Send + Sync {
+    /// A short name for the quantizer, e.g. `"k-means"`, as reported by
+    /// `DistilBuilder::debug`.
+    fn name(&self) -> &'static str;
+
+    /// Reduces `pixels`, the RGBA channels of the sampled pixels, to a
+    /// handful of colors. Each color counts once towards the frequency of
+    /// its palette color for every time it's returned, so quantizers
+    /// usually return the color each pixel was quantized to.
+    fn quantize(&self, pixels: &[u8]) -> Vec<[u8; 3]>;
+}
+
+/// The NeuQuant neural network quantizer, and the default with the
+/// `neuquant` feature. It returns its palette rather than a color per
+/// pixel, and isn't deterministic on noisy images.
+#[cfg(feature = "neuquant")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NeuQuantQuantizer {
+    /// How many pixels NeuQuant skips while learning, between 1 (every
+    /// pixel, slowest) and 30.
+    pub sample_faction: i32,
+
+    /// The number of colors of NeuQuant's palette, between 64 and 256.
+    pub palette_size: usize,
+}
+
+#[cfg(feature = "neuquant")]
+impl Default for NeuQuantQuantizer {
+    fn default() -> NeuQuantQuantizer {
+        NeuQuantQuantizer {
+            sample_faction: NQ_SAMPLE_FACTION,
+            palette_size: NQ_PALETTE_SIZE,
+        }
+    }
+}
+
+#[cfg(feature = "neuquant")]
+impl Quantizer for NeuQuantQuantizer {
+    fn name(&self) -> &'static str {
+        "NeuQuant"
+    }
+
+    fn quantize(&self, pixels: &[u8]) -> Vec<[u8; 3]> {
+        let quantized = NeuQuant::new(self.sample_faction.clamp(1, 30), self.palette_size.clamp(64, 256), pixels);
+
+        quantized.color_map_rgb()
+            .iter()
+            .chunks(3)
+            .into_iter()
+            .map(|rgb_iter| {
+                let rgb_slice: Vec<u8> = rgb_iter.cloned().collect();
+                [rgb_slice[0], rgb_slice[1], rgb_slice[2]]
+            })
+            .collect()
+    }
+}
+
+/// Replaces each pixel with the average color of its histogram bucket, and
+/// the default without the `neuquant` feature. Cruder than the others but
+/// the fastest, leaving most of the work to the `MergeStrategy`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HistogramQuantizer;
+
+impl Quantizer for HistogramQuantizer {
+    fn name(&self) -> &'static str {
+        "histogram"
+    }
+
+    fn quantize(&self, pixels: &[u8]) -> Vec<[u8; 3]> {
+        let mut histogram = Histogram::default();
+
+        for rgba in pixels.chunks_exact(4) {
+            histogram.add_unfiltered([rgba[0], rgba[1], rgba[2], rgba[3]]);
+        }
+
+        pixels.chunks_exact(4).filter_map(|rgba| histogram.average(rgba)).collect()
+    }
+}
+
+impl fmt::Debug for Plugin<dyn Quantizer> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(ref quantizer) => quantizer.fmt(f),
+            #[cfg(feature = "neuquant")]
+            None => f.write_str("NeuQuantQuantizer"),
+            #[cfg(not(feature = "neuquant"))]
+            None => HistogramQuantizer.fmt(f),
+        }
+    }
+}
+
+impl DistilBuilder {
+    /// Sets the quantizer the sampled pixels are reduced with before similar
+    /// colors are merged. Defaults to NeuQuant, configured with
+    /// `sample_faction` and `palette_size`, to `HistogramQuantizer` without
+    /// the `neuquant` feature, or to `WuQuantizer` when `deterministic`.
+    /// Ignored once `warm_start` is set.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::KMeansQuantizer;
+    /// use distil_core::Distil;
+    ///
+    /// let builder = Distil::builder().quantizer(KMeansQuantizer::default());
+    ///
+    /// if let Ok(distilled) = builder.from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn quantizer<Q: Quantizer + 'static>(mut self, quantizer: Q) -> DistilBuilder {
+        self.quantizer = Plugin(Some(Arc::new(quantizer)));
+        self
+    }
+
+    /// Quantizes `pixels` with the configured quantizer, or with k-means
+    /// from the prior palette when warm started.
+    pub(super) fn quantized(&self, pixels: &[u8]) -> Vec<[u8; 3]> {
+        if !self.prior.is_empty() {
+            return KMeansQuantizer::default().quantize_from(pixels, &self.prior);
+        }
+
+        match self.quantizer.0 {
+            Some(ref quantizer) => quantizer.quantize(pixels),
+            None if self.deterministic => WuQuantizer::default().quantize(pixels),
+            None => self.default_quantizer().quantize(pixels),
+        }
+    }
+
+    /// The name of the configured quantizer.
+    pub(super) fn quantizer_name(&self) -> &'static str {
+        if !self.prior.is_empty() {
+            return KMeansQuantizer::default().name();
+        }
+
+        match self.quantizer.0 {
+            Some(ref quantizer) => quantizer.name(),
+            None if self.deterministic => WuQuantizer::default().name(),
+            None => self.default_quantizer().name(),
+        }
+    }
+
+    #[cfg(feature = "neuquant")]
+    fn default_quantizer(&self) -> NeuQuantQuantizer {
+        NeuQuantQuantizer {
+            sample_faction: self.sample_faction,
+            palette_size: self.palette_size,
+        }
+    }
+
+    #[cfg(not(feature = "neuquant"))]
+    fn default_quantizer(&self) -> HistogramQuantizer {
+        HistogramQuantizer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::{KMeansQuantizer, MedianCutQuantizer};
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn quantizers() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let img = gen::blocks(40, 40, &[(teal, 3), (coral, 1)]);
+
+        let builders = [
+            Distil::builder().quantizer(HistogramQuantizer),
+            Distil::builder().quantizer(KMeansQuantizer::default()),
+            Distil::builder().quantizer(MedianCutQuantizer::default()),
+            Distil::builder().quantizer(WuQuantizer::default()),
+        ];
+
+        for builder in &builders {
+            let distilled = builder.from_image(&img).unwrap();
+            let name = builder.quantizer_name();
+
+            // The boundary between the two blurs into a few more colors
+            // when the image is scaled down.
+            assert!(delta_e(distilled.colors[0], teal) < 2.0, "{}", name);
+            assert!(delta_e(distilled.colors[1], coral) < 2.0, "{}", name);
+        }
+    }
+
+    #[test]
+    fn unfiltered() {
+        let black = Color::new(0, 0, 0);
+        let white = Color::new(255, 255, 255);
+        let img = gen::blocks(40, 40, &[(black, 1), (Color::new(0, 128, 128), 2), (white, 1)]);
+
+        let kept = Distil::builder().quantizer(HistogramQuantizer).min_black(0).max_white(255).from_image(&img).unwrap();
+        assert!(kept.colors.iter().any(|&color| delta_e(color, black) < 2.0));
+        assert!(kept.colors.iter().any(|&color| delta_e(color, white) < 2.0));
+
+        let filtered = Distil::builder().quantizer(HistogramQuantizer).from_image(&img).unwrap();
+        assert!(filtered.colors.iter().all(|&color| delta_e(color, black) > 2.0 && delta_e(color, white) > 2.0));
+
+        let mut faded = gen::solid(20, 20, Color::new(200, 60, 60));
+        faded.pixels_mut().for_each(|px| px[3] = 150);
+
+        assert!(Distil::builder().quantizer(HistogramQuantizer).min_alpha(100).from_image(&faded).is_ok());
+    }
+
+    #[test]
+    fn debug() {
+        let builder = Distil::builder().quantizer(WuQuantizer::default());
+
+        assert_eq!(builder.quantizer_name(), "Wu");
+        assert_ne!(builder, Distil::builder());
+    }
+}
@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+
+use super::DistilBuilder;
+use crate::Image;
+
+/// The largest weight a region can have, which keeps a heavily weighted
+/// region from multiplying the number of pixels the quantizer has to learn
+/// from without bound.
+static MAX_REGION_WEIGHT: f32 = 16.0;
+
+/// A rectangle of an image, e.g. a face found by a detection library, along
+/// with how much its pixels count while sampling. Passed to
+/// `DistilBuilder::regions`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+
+    /// How many pixels each of the region's pixels counts as, between `0.0`
+    /// and `16.0`. `0.0` leaves the region out of the palette entirely,
+    /// while a weight over `1.0` makes the palette focus on it.
+    pub weight: f32,
+}
+
+impl Region {
+    /// Returns a region of the passed rectangle with `weight`.
+    pub fn new(x: u32, y: u32, width: u32, height: u32, weight: f32) -> Region {
+        Region {
+            x,
+            y,
+            width,
+            height,
+            weight: weight.clamp(0.0, MAX_REGION_WEIGHT),
+        }
+    }
+
+    /// Returns a region of the passed rectangle that's left out of the
+    /// palette.
+    pub fn exclude(x: u32, y: u32, width: u32, height: u32) -> Region {
+        Region::new(x, y, width, height, 0.0)
+    }
+
+    fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+}
+
+impl DistilBuilder {
+    /// Weights the pixels within each of `regions` while sampling, e.g. to
+    /// leave faces out of a product photo's palette or to pick the colors of
+    /// an avatar's ring from the face alone. Where regions overlap the last
+    /// one wins, and pixels outside of every region count once. Replaces any
+    /// regions set before.
+    ///
+    /// Regions are in the pixels of the image passed to `from_image`, before
+    /// it's scaled down.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::Region;
+    /// use distil_core::Distil;
+    ///
+    /// let face = Region::exclude(120, 80, 64, 64);
+    ///
+    /// if let Ok(distilled) = Distil::builder().regions(&[face]).from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn regions(mut self, regions: &[Region]) -> DistilBuilder {
+        self.regions = regions.iter().map(|region| Region::new(region.x, region.y, region.width, region.height, region.weight)).collect();
+        self
+    }
+
+    /// Returns the configuration to distil `scaled_img`, a scaled down copy
+    /// of `img`, with the regions moved onto it.
+    pub(super) fn scaled(&self, img: &Image, scaled_img: &Image) -> Cow<'_, DistilBuilder> {
+        if self.regions.is_empty() || img.dimensions() == scaled_img.dimensions() {
+            return Cow::Borrowed(self);
+        }
+
+        let scale_x = scaled_img.width() as f32 / img.width().max(1) as f32;
+        let scale_y = scaled_img.height() as f32 / img.height().max(1) as f32;
+
+        let mut scaled = self.clone();
+        scaled.regions = self.regions.iter()
+            .map(|region| {
+                let x = (region.x as f32 * scale_x).floor() as u32;
+                let y = (region.y as f32 * scale_y).floor() as u32;
+                let right = ((region.x as f32 + region.width as f32) * scale_x).ceil() as u32;
+                let bottom = ((region.y as f32 + region.height as f32) * scale_y).ceil() as u32;
+
+                Region { x, y, width: right.saturating_sub(x), height: bottom.saturating_sub(y), ..*region }
+            })
+            .collect();
+
+        Cow::Owned(scaled)
+    }
+
+    /// Returns the weight of the pixel at `x`, `y`.
+    pub(super) fn weight_at(&self, x: u32, y: u32) -> f32 {
+        self.regions.iter().rev().find(|region| region.contains(x, y)).map_or(1.0, |region| region.weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::get_pixels;
+    use super::Region;
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn regions() {
+        let skin = Color::new(224, 172, 140);
+        let shirt = Color::new(40, 90, 200);
+        let img = gen::blocks(40, 20, &[(skin, 1), (shirt, 3)]);
+
+        let excluded = Distil::builder().regions(&[Region::exclude(0, 0, 10, 20)]);
+        assert_eq!(get_pixels(&img, &excluded).unwrap().len(), 600 * 4);
+
+        let focused = Distil::builder().regions(&[Region::new(0, 0, 10, 20, 6.0), Region::new(0, 0, 5, 20, 0.5)]);
+        assert_eq!(get_pixels(&img, &focused).unwrap().len(), (100 * 6 + 50 + 600) * 4);
+
+        let distilled = Distil::builder().regions(&[Region::exclude(0, 0, 10, 20)]).from_image(&img).unwrap();
+        assert!(distilled.colors.iter().all(|&color| delta_e(color, skin) > 10.0));
+
+        let scaled = gen::blocks(400, 200, &[(skin, 1), (shirt, 3)]);
+        let distilled = Distil::builder().regions(&[Region::new(0, 0, 100, 200, 16.0)]).from_image(&scaled).unwrap();
+        assert!(delta_e(distilled.colors[0], skin) < 5.0);
+    }
+}
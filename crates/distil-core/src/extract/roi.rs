@@ -0,0 +1,171 @@
+use super::regions::Region;
+use super::DistilBuilder;
+use crate::{Distil, DistilError, Image};
+
+/// The value of the first channel of a mask pixel from which it counts as
+/// white, so that anti-aliased mask edges are split down the middle.
+static MASK_THRESHOLD: u8 = 128;
+
+impl Distil {
+    /// `from_image_region` `Distil`s the `width` × `height` region of `img`
+    /// whose top left corner is at `(x, y)`, e.g. a bounding box found by a
+    /// detection model. The region is cropped before the image is scaled
+    /// down, so it's sampled as finely as a whole image would be. It's
+    /// clamped to the image, and an empty region is
+    /// `DistilError::Uninteresting`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let img = gen::blocks(40, 20, &[(Color::new(0, 128, 128), 1), (Color::new(255, 127, 80), 1)]);
+    /// let distilled = Distil::from_image_region(&img, 20, 0, 20, 20).unwrap();
+    ///
+    /// let (coral, distance) = distilled.nearest([255, 127, 80]).unwrap();
+    ///
+    /// assert!(distance < 2.0);
+    /// assert!(distilled.weights[coral] > 0.99);
+    /// ```
+    pub fn from_image_region(img: &Image, x: u32, y: u32, width: u32, height: u32) -> Result<Distil, DistilError> {
+        DistilBuilder::default().from_image_region(img, x, y, width, height)
+    }
+
+    /// `from_image_masked` `Distil`s the pixels of `img` that are white in
+    /// `mask`, e.g. a segmentation mask. Only the first channel of `mask`
+    /// is read, and values of 128 and over count as white. The mask is
+    /// stretched over the image when their sizes differ.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let img = gen::blocks(40, 20, &[(Color::new(0, 128, 128), 1), (Color::new(255, 127, 80), 1)]);
+    /// let mask = gen::blocks(4, 2, &[(Color::new(0, 0, 0), 1), (Color::new(255, 255, 255), 1)]);
+    /// let distilled = Distil::from_image_masked(&img, &mask).unwrap();
+    ///
+    /// let (coral, distance) = distilled.nearest([255, 127, 80]).unwrap();
+    ///
+    /// assert!(distance < 2.0);
+    /// assert!(distilled.weights[coral] > 0.99);
+    /// ```
+    pub fn from_image_masked(img: &Image, mask: &Image) -> Result<Distil, DistilError> {
+        DistilBuilder::default().from_image_masked(img, mask)
+    }
+}
+
+impl DistilBuilder {
+    /// Like `Distil::from_image_region`, with this configuration. `regions`
+    /// stay in the pixels of `img`, not of the region.
+    pub fn from_image_region(&self, img: &Image, x: u32, y: u32, width: u32, height: u32) -> Result<Distil, DistilError> {
+        if x >= img.width() || y >= img.height() || width == 0 || height == 0 {
+            return Err(DistilError::Uninteresting);
+        }
+
+        self.offset_regions(x, y).from_image(&img.crop(x, y, width, height))
+    }
+
+    /// Like `Distil::from_image_masked`, with this configuration. Pixels
+    /// outside of the mask are left out even when `min_alpha` is `0`.
+    pub fn from_image_masked(&self, img: &Image, mask: &Image) -> Result<Distil, DistilError> {
+        let (width, height) = img.dimensions();
+
+        if mask.width() == 0 || mask.height() == 0 {
+            return Err(DistilError::Uninteresting);
+        }
+
+        let is_white = |x: u32, y: u32| {
+            mask.get_pixel((x as u64 * mask.width() as u64 / width as u64) as u32,
+                           (y as u64 * mask.height() as u64 / height as u64) as u32)[0] >= MASK_THRESHOLD
+        };
+
+        // Masked out pixels are made fully transparent, and the image is
+        // cropped to the bounds of the mask before it's scaled down.
+        let mut masked = img.clone();
+        let (mut left, mut top, mut right, mut bottom) = (width, height, 0, 0);
+
+        for y in 0..height {
+            for x in 0..width {
+                if is_white(x, y) {
+                    left = left.min(x);
+                    top = top.min(y);
+                    right = right.max(x + 1);
+                    bottom = bottom.max(y + 1);
+                } else {
+                    let rgba = masked.get_pixel(x, y);
+                    masked.put_pixel(x, y, [rgba[0], rgba[1], rgba[2], 0]);
+                }
+            }
+        }
+
+        if left >= right || top >= bottom {
+            return Err(DistilError::Uninteresting);
+        }
+
+        let config = self.clone().min_alpha(self.min_alpha.max(1));
+
+        config.from_image_region(&masked, left, top, right - left, bottom - top)
+    }
+
+    /// Returns this configuration with its regions moved by `-x`, `-y`, for
+    /// a crop of the image whose top left corner is at `(x, y)`.
+    fn offset_regions(&self, x: u32, y: u32) -> DistilBuilder {
+        let mut offset = self.clone();
+
+        offset.regions = self.regions.iter()
+            .map(|region| {
+                let left = region.x.saturating_sub(x);
+                let top = region.y.saturating_sub(y);
+                let right = (region.x + region.width).saturating_sub(x);
+                let bottom = (region.y + region.height).saturating_sub(y);
+
+                Region { x: left, y: top, width: right.saturating_sub(left), height: bottom.saturating_sub(top), ..*region }
+            })
+            .collect();
+
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{delta_e, gen, Color, Distil, DistilError};
+
+    #[test]
+    fn region() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let img = gen::blocks(400, 100, &[(teal, 3), (coral, 1)]);
+
+        let distilled = Distil::from_image_region(&img, 300, 0, 100, 100).unwrap();
+        let (i, distance) = distilled.nearest(coral.rgb()).unwrap();
+        assert!(distance < 2.0);
+        assert!(distilled.weights[i] > 0.99);
+
+        let clamped = Distil::from_image_region(&img, 350, 50, 500, 500).unwrap();
+        assert!(delta_e(clamped.colors[0], coral) < 2.0);
+
+        assert!(matches!(Distil::from_image_region(&img, 400, 0, 10, 10), Err(DistilError::Uninteresting)));
+    }
+
+    #[test]
+    fn masked() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let img = gen::blocks(400, 100, &[(teal, 3), (coral, 1)]);
+        let mask = gen::blocks(8, 2, &[(Color::new(0, 0, 0), 3), (Color::new(255, 255, 255), 1)]);
+
+        let distilled = Distil::from_image_masked(&img, &mask).unwrap();
+        let (i, distance) = distilled.nearest(coral.rgb()).unwrap();
+        assert!(distance < 2.0);
+        assert!(distilled.weights[i] > 0.99);
+
+        let kept = Distil::builder().min_alpha(0).from_image_masked(&img, &mask).unwrap();
+        let (i, _) = kept.nearest(coral.rgb()).unwrap();
+        assert!(kept.weights[i] > 0.99);
+
+        let empty = gen::solid(8, 2, Color::new(0, 0, 0));
+        assert!(matches!(Distil::from_image_masked(&img, &empty), Err(DistilError::Uninteresting)));
+    }
+}
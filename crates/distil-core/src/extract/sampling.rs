@@ -0,0 +1,169 @@
+use super::DistilBuilder;
+use crate::Image;
+
+/// The weight of the pixel at the very center of the image with
+/// `SamplingStrategy::CenterWeighted`. It falls to 1 halfway to the corners.
+static CENTER_WEIGHT: f32 = 4.0;
+
+/// The weight of a pixel without any edge energy with
+/// `SamplingStrategy::EdgeEnergy`, so flat areas still count a little.
+static MIN_EDGE_WEIGHT: f32 = 0.25;
+
+/// The largest weight a pixel can get from its edge energy.
+static MAX_EDGE_WEIGHT: f32 = 8.0;
+
+/// How pixels are weighted while sampling, so that the subject of a photo
+/// can outrank a large but uninteresting background. Passed to
+/// `DistilBuilder::sampling`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SamplingStrategy {
+    /// Every pixel counts the same.
+    #[default]
+    Uniform,
+
+    /// Pixels count more the closer they are to the center of the image,
+    /// from 4 times at the center to about half at the corners, for photos
+    /// with a centered subject.
+    CenterWeighted,
+
+    /// Pixels count in proportion to how much the colors around them
+    /// change, a simple saliency map that favors detailed areas over flat
+    /// backgrounds and skies wherever they are in the image.
+    EdgeEnergy,
+}
+
+impl DistilBuilder {
+    /// Sets how pixels are weighted while sampling. Weights combine with
+    /// those of `regions` and `alpha_weighted`. Defaults to
+    /// `SamplingStrategy::Uniform`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::SamplingStrategy;
+    /// use distil_core::Distil;
+    ///
+    /// let builder = Distil::builder().sampling(SamplingStrategy::CenterWeighted);
+    ///
+    /// if let Ok(distilled) = builder.from_path_str("./images/img-1.jpg") {
+    ///     // Do something with the returned `Distil` struct…
+    /// }
+    /// ```
+    pub fn sampling(mut self, sampling: SamplingStrategy) -> DistilBuilder {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Returns the weight of each pixel of `img`, row by row, or `None` if
+    /// every pixel counts the same.
+    pub(super) fn sampling_weights(&self, img: &Image) -> Option<Vec<f32>> {
+        match self.sampling {
+            SamplingStrategy::Uniform => None,
+            SamplingStrategy::CenterWeighted => Some(center_weights(img)),
+            SamplingStrategy::EdgeEnergy => Some(edge_weights(img)),
+        }
+    }
+}
+
+/// Weights the pixels of `img` by `CENTER_WEIGHT / (1 + 6d²)`, where `d` is
+/// the distance to the center with the corners at 1. Only basic arithmetic
+/// is involved, so the weights are the same on every platform.
+fn center_weights(img: &Image) -> Vec<f32> {
+    let (width, height) = img.dimensions();
+    let half_width = width as f32 / 2.0;
+    let half_height = height as f32 / 2.0;
+
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let dx = (x as f32 + 0.5 - half_width) / half_width;
+            let dy = (y as f32 + 0.5 - half_height) / half_height;
+            let squared_distance = (dx * dx + dy * dy) / 2.0;
+
+            CENTER_WEIGHT / (1.0 + 6.0 * squared_distance)
+        })
+        .collect()
+}
+
+/// Weights the pixels of `img` by their edge energy, the sum of the
+/// differences between their channels and those of their four neighbors,
+/// relative to the average energy of the image.
+fn edge_weights(img: &Image) -> Vec<f32> {
+    let (width, height) = img.dimensions();
+
+    let energies: Vec<u32> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let rgba = img.get_pixel(x, y);
+
+            [(x.saturating_sub(1), y), ((x + 1).min(width - 1), y), (x, y.saturating_sub(1)), (x, (y + 1).min(height - 1))]
+                .iter()
+                .map(|&(x, y)| difference(rgba, img.get_pixel(x, y)))
+                .sum()
+        })
+        .collect();
+
+    let mean = energies.iter().map(|&energy| energy as f32).sum::<f32>() / energies.len().max(1) as f32;
+
+    if mean <= 0.0 {
+        return vec![1.0; energies.len()];
+    }
+
+    energies.iter().map(|&energy| (MIN_EDGE_WEIGHT + energy as f32 / mean).min(MAX_EDGE_WEIGHT)).collect()
+}
+
+fn difference(x: [u8; 4], y: [u8; 4]) -> u32 {
+    x.iter().zip(&y).take(3).map(|(&x, &y)| x.abs_diff(y) as u32).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SamplingStrategy;
+    use crate::{delta_e, gen, Color, Distil, Image};
+
+    fn weight_of(distilled: &Distil, color: Color) -> f32 {
+        distilled.colors.iter().zip(&distilled.weights).find(|&(&c, _)| delta_e(c, color) < 5.0).map_or(0.0, |(_, &weight)| weight)
+    }
+
+    #[test]
+    fn center_weighted() {
+        let subject = Color::new(200, 60, 60);
+        let mut img = gen::solid(30, 30, Color::new(90, 120, 160));
+
+        for y in 10..20 {
+            for x in 10..20 {
+                img.put_pixel(x, y, [200, 60, 60, 255]);
+            }
+        }
+
+        let uniform = Distil::builder().deterministic(true).from_image(&img).unwrap();
+        let centered = Distil::builder().deterministic(true).sampling(SamplingStrategy::CenterWeighted).from_image(&img).unwrap();
+
+        assert!((weight_of(&uniform, subject) - 100.0 / 900.0).abs() < 0.01);
+        assert!(weight_of(&centered, subject) > 1.5 * weight_of(&uniform, subject));
+    }
+
+    #[test]
+    fn edge_energy() {
+        let flat = Color::new(0, 128, 128);
+        let mut img = Image::new(40, 20);
+
+        for y in 0..20 {
+            for x in 0..40 {
+                let rgba = match (x < 20, (x + y) % 2 == 0) {
+                    (true, _) => [0, 128, 128, 255],
+                    (false, true) => [200, 60, 60, 255],
+                    (false, false) => [240, 200, 40, 255],
+                };
+
+                img.put_pixel(x, y, rgba);
+            }
+        }
+
+        let uniform = Distil::builder().deterministic(true).from_image(&img).unwrap();
+        let salient = Distil::builder().deterministic(true).sampling(SamplingStrategy::EdgeEnergy).from_image(&img).unwrap();
+
+        assert!((weight_of(&uniform, flat) - 0.5).abs() < 0.02);
+        assert!(weight_of(&salient, flat) < 0.25);
+    }
+}
@@ -0,0 +1,146 @@
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::grid::lab_grid;
+use super::{scale_img, MAX_SAMPLE_COUNT};
+use crate::{Distil, DistilError, Image};
+
+/// The number of most-frequent palette colors kept in a signature.
+static SIGNATURE_PALETTE_SIZE: usize = 8;
+
+/// The number of columns and rows of the grid kept in a signature.
+static SIGNATURE_GRID_SIZE: u32 = 4;
+
+/// The average CIEDE2000 distance at which two signatures are considered to
+/// have nothing in common.
+static SIGNATURE_MAX_DISTANCE: f32 = 25.0;
+
+/// A compact description of an image's colors, as returned by `signature`,
+/// that survives resizing and re-encoding and can be compared with
+/// `similarity`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    /// The most frequent colors of the image's palette along with their
+    /// share of the palette, from most to least frequent.
+    pub palette: Vec<(Lab, f32)>,
+
+    /// The average color of each cell of a 4 × 4 grid over the image, row by
+    /// row, or `None` for cells with no opaque pixels.
+    pub grid: Vec<Option<Lab>>,
+}
+
+/// Returns the `Signature` of `img`: the fingerprint of its palette and a
+/// coarse map of where its colors are. Two copies of an image at different
+/// sizes or compression levels have close signatures, which makes it a cheap
+/// first pass when looking for duplicates.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::extract::{signature, similarity};
+/// use distil_core::Image;
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let a = signature(&img).unwrap();
+///     let b = signature(&img).unwrap();
+///
+///     assert_eq!(similarity(&a, &b), 1.0);
+/// }
+/// ```
+pub fn signature(img: &Image) -> Result<Signature, DistilError> {
+    let scaled_img = scale_img(img, MAX_SAMPLE_COUNT);
+    let distilled = Distil::from_image(&scaled_img)?;
+    let palette = distilled.lab_colors
+        .iter()
+        .copied()
+        .zip(distilled.shares())
+        .take(SIGNATURE_PALETTE_SIZE)
+        .collect();
+
+    Ok(Signature {
+        palette,
+        grid: lab_grid(&scaled_img, SIGNATURE_GRID_SIZE, SIGNATURE_GRID_SIZE),
+    })
+}
+
+/// Scores how alike two signatures are between `0.0`, nothing in common,
+/// and `1.0`, identical. The palettes and the grids are compared with
+/// CIEDE2000 and count equally.
+///
+/// Duplicates typically score above 0.9, but the threshold worth acting on
+/// depends on the collection and should be checked against a perceptual
+/// hash or the images themselves.
+pub fn similarity(a: &Signature, b: &Signature) -> f32 {
+    let palette_distance = (palette_distance(&a.palette, &b.palette) + palette_distance(&b.palette, &a.palette)) / 2.0;
+    let distance = (palette_distance + grid_distance(&a.grid, &b.grid)) / 2.0;
+
+    (1.0 - distance / SIGNATURE_MAX_DISTANCE).clamp(0.0, 1.0)
+}
+
+/// Returns the average distance, weighted by share, from each color of `a`
+/// to its nearest color in `b`.
+fn palette_distance(a: &[(Lab, f32)], b: &[(Lab, f32)]) -> f32 {
+    let total = a.iter().map(|&(_, share)| share).sum::<f32>();
+
+    if total == 0.0 || b.is_empty() {
+        return if a.is_empty() && b.is_empty() { 0.0 } else { SIGNATURE_MAX_DISTANCE };
+    }
+
+    a.iter()
+        .map(|&(lab_x, share)| {
+            share * b.iter().map(|&(lab_y, _)| DE2000::new(lab_x, lab_y)).fold(f32::INFINITY, f32::min)
+        })
+        .sum::<f32>() / total
+}
+
+/// Returns the symmetric, share-weighted distance between two palettes: the
+/// average of `palette_distance` both ways.
+pub(super) fn distil_distance(a: &Distil, b: &Distil) -> f32 {
+    let shares = |distil: &Distil| -> Vec<(Lab, f32)> { distil.lab_colors.iter().copied().zip(distil.shares()).collect() };
+    let (a, b) = (shares(a), shares(b));
+
+    (palette_distance(&a, &b) + palette_distance(&b, &a)) / 2.0
+}
+
+/// Returns the average distance between the cells of two grids. A cell
+/// that's empty in only one of them counts as the maximum distance.
+fn grid_distance(a: &[Option<Lab>], b: &[Option<Lab>]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return SIGNATURE_MAX_DISTANCE;
+    }
+
+    a.iter()
+        .zip(b)
+        .map(|cells| {
+            match cells {
+                (Some(lab_x), Some(lab_y)) => DE2000::new(*lab_x, *lab_y),
+                (None, None) => 0.0,
+                _ => SIGNATURE_MAX_DISTANCE,
+            }
+        })
+        .sum::<f32>() / a.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{signature, similarity};
+    use crate::{gen, imaging, Color, Image};
+
+    #[test]
+    fn duplicates() {
+        let img = Image::open(Path::new("./images/img-1.jpg")).unwrap();
+        let (width, height) = img.dimensions();
+
+        let original = signature(&img).unwrap();
+        let resized = signature(&gen::jitter(&imaging::resize(&img, width / 3, height / 3), 4, 7)).unwrap();
+        let other = signature(&gen::blocks(60, 40, &[(Color::new(30, 160, 70), 1), (Color::new(240, 220, 40), 1)]))
+            .unwrap();
+
+        assert_eq!(similarity(&original, &original), 1.0);
+        assert!(similarity(&original, &resized) > 0.9);
+        assert!(similarity(&original, &other) < 0.5);
+    }
+}
@@ -0,0 +1,268 @@
+use lab::Lab;
+
+use super::events::{PaletteChange, Subscribers};
+use super::kmeans::{histogram, nearest, seeds};
+use super::{get_pixels, DistilBuilder};
+use crate::palette::distil_palette;
+use crate::{Distil, DistilError, Image};
+
+static STREAM_COLORS: usize = 16;
+static STREAM_DECAY: f32 = 0.8;
+static STREAM_SEED: u64 = 1;
+
+/// The weight under which a cluster is considered to have died out, and is
+/// moved to a color of the latest frame that no cluster is near.
+static MIN_CLUSTER_WEIGHT: f32 = 0.5;
+
+/// Distils a stream of frames, e.g. from a camera or a video, with online
+/// mini-batch k-means: each frame moves the clusters towards its pixels
+/// rather than the palette being distilled from scratch, so live palettes
+/// adapt smoothly without any of the previous frames being kept around.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::extract::StreamDistiller;
+/// use distil_core::{gen, Color};
+///
+/// let mut stream = StreamDistiller::default();
+///
+/// for _ in 0..3 {
+///     stream.push(&gen::solid(32, 32, Color::new(0, 128, 128))).unwrap();
+/// }
+///
+/// let palette = stream.distil().unwrap();
+/// assert_eq!(palette.colors[0], Color::new(0, 128, 128));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamDistiller {
+    builder: DistilBuilder,
+    colors: usize,
+    decay: f32,
+    centers: Vec<Lab>,
+    weights: Vec<f32>,
+    subscribers: Subscribers,
+}
+
+impl Default for StreamDistiller {
+    fn default() -> StreamDistiller {
+        StreamDistiller::new(DistilBuilder::default())
+    }
+}
+
+impl StreamDistiller {
+    /// Creates a stream whose frames are sampled and filtered the way
+    /// `builder` samples and filters images. Its quantizer isn't used.
+    pub fn new(builder: DistilBuilder) -> StreamDistiller {
+        StreamDistiller {
+            builder,
+            colors: STREAM_COLORS,
+            decay: STREAM_DECAY,
+            centers: Vec::new(),
+            weights: Vec::new(),
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// Sets the number of clusters, before similar ones are merged into the
+    /// palette. Defaults to 16.
+    pub fn colors(mut self, colors: usize) -> StreamDistiller {
+        self.colors = colors.max(1);
+        self
+    }
+
+    /// Sets how much of the weight of the previous frames is kept when a
+    /// frame is pushed, between `0.0` (none, the palette follows the latest
+    /// frame) and `1.0` (all of it, the palette settles). Defaults to `0.8`.
+    pub fn decay(mut self, decay: f32) -> StreamDistiller {
+        self.decay = decay.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Calls `callback` with the palette of each pushed frame that's at
+    /// least `threshold` away, on average in CIEDE2000, from the palette it
+    /// was last called with, so that e.g. a UI is only re-themed when the
+    /// scene changes. It's called with the first palette too.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::extract::StreamDistiller;
+    /// use distil_core::{gen, Color};
+    ///
+    /// let mut stream = StreamDistiller::default();
+    /// stream.on_change(10.0, |change| println!("Re-theme with {}", change.palette.colors[0]));
+    ///
+    /// stream.push(&gen::solid(32, 32, Color::new(0, 128, 128))).unwrap();
+    /// ```
+    pub fn on_change<F: Fn(&PaletteChange) + Send + Sync + 'static>(&mut self, threshold: f32, callback: F) {
+        self.subscribers.add(threshold, callback);
+    }
+
+    /// Moves the clusters towards the pixels of `frame` and returns the
+    /// updated palette, calling the `on_change` callbacks it's far enough
+    /// away for.
+    pub fn push(&mut self, frame: &Image) -> Result<Distil, DistilError> {
+        let sampled = self.builder.sampled(frame);
+        let config = self.builder.keyed(&sampled);
+        let config = config.scaled(frame, &sampled);
+        let pixels = get_pixels(&sampled, &config)?;
+        let (buckets, _) = histogram(&pixels);
+
+        if self.centers.is_empty() {
+            self.centers = seeds(&buckets, self.colors, STREAM_SEED);
+            self.weights = vec![0.0; self.centers.len()];
+        }
+
+        for weight in &mut self.weights {
+            *weight *= self.decay;
+        }
+
+        self.revive(&buckets);
+
+        // Each bucket moves its nearest cluster by a step that shrinks as
+        // the cluster gains weight, as in Sculley's mini-batch k-means.
+        for &(lab, count) in &buckets {
+            let i = nearest(&self.centers, lab);
+            self.weights[i] += count;
+
+            let rate = count / self.weights[i];
+            let center = &mut self.centers[i];
+
+            *center = Lab {
+                l: center.l + rate * (lab.l - center.l),
+                a: center.a + rate * (lab.a - center.a),
+                b: center.b + rate * (lab.b - center.b),
+            };
+        }
+
+        let palette = self.distil()?;
+        self.subscribers.notify(&palette);
+
+        Ok(palette)
+    }
+
+    /// Returns the palette of the frames pushed so far.
+    pub fn distil(&self) -> Result<Distil, DistilError> {
+        let mut palette: Vec<(Lab, usize)> = self.centers.iter()
+            .zip(&self.weights)
+            .map(|(&center, &weight)| (center, weight.round() as usize))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+
+        if palette.is_empty() {
+            return Err(DistilError::Uninteresting);
+        }
+
+        palette.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+        let members: Vec<(Lab, f32, f32)> = self.centers.iter()
+            .zip(&self.weights)
+            .map(|(&center, &weight)| (center, weight, 0.0))
+            .collect();
+
+        Ok(self.builder.with_members(distil_palette(self.builder.merged(palette, &mut Vec::new())), &members))
+    }
+
+    /// Adds clusters until there are `colors` of them, and moves those that
+    /// have died out, at the colors of `buckets` furthest from every
+    /// cluster, so that colors new to the stream get clusters of their own.
+    fn revive(&mut self, buckets: &[(Lab, f32)]) {
+        let dead: Vec<usize> = (0..self.centers.len()).filter(|&i| self.weights[i] < MIN_CLUSTER_WEIGHT).collect();
+        let missing = self.colors.saturating_sub(self.centers.len());
+
+        for i in dead.into_iter().map(Some).chain((0..missing).map(|_| None)) {
+            let furthest = buckets.iter()
+                .map(|&(lab, _)| (lab, squared_distance(lab, self.centers[nearest(&self.centers, lab)])))
+                .max_by(|(_, x), (_, y)| x.total_cmp(y));
+
+            let lab = match furthest {
+                Some((lab, distance)) if distance > 0.0 => lab,
+                _ => return,
+            };
+
+            match i {
+                Some(i) => {
+                    self.centers[i] = lab;
+                    self.weights[i] = 0.0;
+                }
+                None => {
+                    self.centers.push(lab);
+                    self.weights.push(0.0);
+                }
+            }
+        }
+    }
+}
+
+fn squared_distance(x: Lab, y: Lab) -> f32 {
+    (x.l - y.l).powi(2) + (x.a - y.a).powi(2) + (x.b - y.b).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::StreamDistiller;
+    use crate::{delta_e, gen, Color};
+
+    #[test]
+    fn adapts() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let mut stream = StreamDistiller::default();
+
+        for _ in 0..3 {
+            stream.push(&gen::blocks(40, 20, &[(teal, 3), (coral, 1)])).unwrap();
+        }
+
+        let before = stream.distil().unwrap();
+        assert!(delta_e(before.colors[0], teal) < 2.0);
+
+        for _ in 0..10 {
+            stream.push(&gen::blocks(40, 20, &[(teal, 1), (coral, 3)])).unwrap();
+        }
+
+        let after = stream.distil().unwrap();
+        assert!(delta_e(after.colors[0], coral) < 2.0);
+        assert!((after.weights.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn new_colors() {
+        let mut stream = StreamDistiller::default().colors(4);
+        stream.push(&gen::solid(16, 16, Color::new(0, 128, 128))).unwrap();
+
+        let blue = Color::new(20, 20, 220);
+
+        for _ in 0..5 {
+            stream.push(&gen::solid(16, 16, blue)).unwrap();
+        }
+
+        assert!(stream.distil().unwrap().colors.iter().any(|&color| delta_e(color, blue) < 2.0));
+    }
+
+    #[test]
+    fn on_change() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+
+        let mut stream = StreamDistiller::default().decay(0.0);
+        stream.on_change(10.0, move |_| {
+            counted.fetch_add(1, Ordering::SeqCst);
+        });
+
+        for _ in 0..5 {
+            stream.push(&gen::solid(16, 16, Color::new(0, 128, 128))).unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        for _ in 0..5 {
+            stream.push(&gen::solid(16, 16, Color::new(20, 20, 220))).unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,175 @@
+use lab::Lab;
+
+use super::grid::lab_grid;
+use super::scale_img;
+use crate::{contrast_ratio, Color, Distil, DistilError, Image};
+
+/// The number of pixels the image is scaled down to before being scored.
+static WALLPAPER_SAMPLE_COUNT: u32 = 4000;
+
+/// The number of columns and rows of the grid text is assumed to be placed
+/// on.
+static WALLPAPER_GRID_SIZE: u32 = 6;
+
+/// The contrast ratio text needs for full marks, WCAG AA for body text.
+static TARGET_CONTRAST: f32 = 4.5;
+
+/// The average Lab distance between neighbouring pixels at which an image
+/// is considered as busy as it gets.
+static MAX_NEIGHBOUR_DISTANCE: f32 = 15.0;
+
+/// Colors with a Lab chroma below this are considered grey and have no
+/// dominant hue.
+static MAX_GREY_CHROMA: f32 = 8.0;
+
+/// How suitable an image is as a wallpaper behind UI text, as returned by
+/// `wallpaper_suitability`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WallpaperScore {
+    /// The overall score, between `0.0` and `1.0`, combining the contrast
+    /// headroom of the best text color with how calm the image is.
+    pub score: f32,
+
+    /// The text color, white or black, with the most contrast headroom.
+    pub text: Color,
+
+    /// The lowest contrast ratio white text has with any area of the image.
+    pub white_contrast: f32,
+
+    /// The lowest contrast ratio black text has with any area of the image.
+    pub black_contrast: f32,
+
+    /// How busy the image is, between `0.0` for flat color and `1.0` for
+    /// noise. Busy images make text hard to read whatever its contrast.
+    pub busyness: f32,
+
+    /// The HSL hue, in degrees, of the image's dominant color, or `None` if
+    /// that color is a grey.
+    pub dominant_hue: Option<f32>,
+}
+
+/// Scores how suitable `img` is as a wallpaper or background behind white
+/// or black UI text.
+///
+/// The image is split into a grid and the contrast of white and black text
+/// is measured against the least favourable cell, since icons and text can
+/// end up anywhere. The better of the two is then penalised by how busy the
+/// image is.
+///
+/// Returns `DistilError::Uninteresting` if `img` has no opaque pixels.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::extract::wallpaper_suitability;
+/// use distil_core::{gen, Color};
+///
+/// let img = gen::gradient(80, 40, Color::new(20, 30, 70), Color::new(40, 20, 60));
+/// let wallpaper = wallpaper_suitability(&img).unwrap();
+///
+/// assert_eq!(wallpaper.text, Color::new(255, 255, 255));
+/// assert!(wallpaper.score > 0.8);
+/// ```
+pub fn wallpaper_suitability(img: &Image) -> Result<WallpaperScore, DistilError> {
+    let scaled_img = scale_img(img, WALLPAPER_SAMPLE_COUNT);
+    let cells: Vec<Lab> = lab_grid(&scaled_img, WALLPAPER_GRID_SIZE, WALLPAPER_GRID_SIZE).into_iter().flatten().collect();
+
+    if cells.is_empty() {
+        return Err(DistilError::Uninteresting);
+    }
+
+    let (white, black) = (Color::new(255, 255, 255), Color::new(0, 0, 0));
+    let worst_contrast = |text: Color| {
+        cells.iter().map(|&lab| contrast_ratio(lab, text)).fold(f32::INFINITY, f32::min)
+    };
+
+    let (white_contrast, black_contrast) = (worst_contrast(white), worst_contrast(black));
+    let (text, contrast) = if white_contrast >= black_contrast {
+        (white, white_contrast)
+    } else {
+        (black, black_contrast)
+    };
+
+    let busyness = busyness(&scaled_img);
+    let headroom = ((contrast - 1.0) / (TARGET_CONTRAST - 1.0)).clamp(0.0, 1.0);
+
+    // An image made up only of near-black or near-white pixels has no
+    // palette, and so no hue.
+    let dominant_hue = Distil::from_image(&scaled_img)
+        .ok()
+        .map(|distilled| distilled.lab_colors[0])
+        .filter(|lab| (lab.a * lab.a + lab.b * lab.b).sqrt() >= MAX_GREY_CHROMA)
+        .map(|lab| Color::from(lab).hsl().0);
+
+    Ok(WallpaperScore {
+        score: headroom * (1.0 - busyness),
+        text,
+        white_contrast,
+        black_contrast,
+        busyness,
+        dominant_hue,
+    })
+}
+
+/// Returns the average Lab distance between opaque neighbouring pixels,
+/// relative to `MAX_NEIGHBOUR_DISTANCE`.
+fn busyness(img: &Image) -> f32 {
+    let (width, height) = img.dimensions();
+    let (mut total, mut count) = (0.0, 0usize);
+
+    let mut distance = |a: [u8; 4], b: [u8; 4]| {
+        if a[3] == 0 || b[3] == 0 {
+            return;
+        }
+
+        let (lab_a, lab_b) = (Lab::from_rgb(&[a[0], a[1], a[2]]), Lab::from_rgb(&[b[0], b[1], b[2]]));
+        total += ((lab_a.l - lab_b.l).powi(2) + (lab_a.a - lab_b.a).powi(2) + (lab_a.b - lab_b.b).powi(2)).sqrt();
+        count += 1;
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            let px = img.get_pixel(x, y);
+
+            if x + 1 < width {
+                distance(px, img.get_pixel(x + 1, y));
+            }
+
+            if y + 1 < height {
+                distance(px, img.get_pixel(x, y + 1));
+            }
+        }
+    }
+
+    if count == 0 {
+        return 0.0;
+    }
+
+    (total / count as f32 / MAX_NEIGHBOUR_DISTANCE).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wallpaper_suitability;
+    use crate::{gen, Color};
+
+    #[test]
+    fn wallpaper() {
+        let calm = wallpaper_suitability(&gen::solid(40, 40, Color::new(240, 230, 200))).unwrap();
+        let busy = wallpaper_suitability(&gen::noise(40, 40, 3)).unwrap();
+        let split = wallpaper_suitability(&gen::blocks(40, 40, &[(Color::new(0, 0, 0), 1), (Color::new(255, 255, 255), 1)]))
+            .unwrap();
+
+        assert_eq!(calm.text, Color::new(0, 0, 0));
+        assert!(calm.score > 0.9);
+        assert!(calm.busyness < 0.01);
+        assert!(calm.dominant_hue.is_some_and(|hue| (30.0..60.0).contains(&hue)));
+
+        assert!(busy.busyness > 0.9);
+        assert!(busy.score < 0.1);
+
+        assert!(split.white_contrast < 1.1 && split.black_contrast < 1.1);
+        assert!(split.score < 0.1);
+        assert_eq!(split.dominant_hue, None);
+    }
+}
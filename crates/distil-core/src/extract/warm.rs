@@ -0,0 +1,53 @@
+use super::DistilBuilder;
+use crate::Distil;
+
+impl DistilBuilder {
+    /// Starts clustering from the colors of `prior`, e.g. the palette of the
+    /// previous frame of a video or of the previous photo of a product
+    /// shoot, so that similar images get consistent palettes and
+    /// near-identical ones converge in an iteration or two.
+    ///
+    /// The sampled pixels are quantized with `KMeansQuantizer`, its
+    /// clusters starting at the colors of `prior` and the rest seeded with
+    /// k-means++, in place of the configured `quantizer`. Replaces any
+    /// prior set before.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let frame = gen::blocks(40, 20, &[(Color::new(0, 128, 128), 3), (Color::new(255, 127, 80), 1)]);
+    /// let previous = Distil::from_image(&frame).unwrap();
+    ///
+    /// let next = Distil::builder().warm_start(&previous).from_image(&gen::jitter(&frame, 2, 1)).unwrap();
+    /// ```
+    pub fn warm_start(mut self, prior: &Distil) -> DistilBuilder {
+        self.prior = prior.lab_colors.clone();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extract::KMeansQuantizer;
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn warm_start() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let frame = gen::blocks(40, 20, &[(teal, 3), (coral, 1)]);
+
+        let previous = Distil::builder().quantizer(KMeansQuantizer::default()).from_image(&frame).unwrap();
+        let next = Distil::builder().warm_start(&previous).from_image(&gen::jitter(&frame, 2, 1)).unwrap();
+
+        assert_eq!(next.colors.len(), previous.colors.len());
+
+        for (&color, &prior) in next.colors.iter().zip(&previous.colors) {
+            assert!(delta_e(color, prior) < 2.0);
+        }
+
+        assert_eq!(Distil::builder().warm_start(&previous).quantizer_name(), "k-means");
+    }
+}
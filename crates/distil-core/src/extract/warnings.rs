@@ -0,0 +1,153 @@
+use std::fmt;
+
+use super::debug::Filtered;
+use super::key::ChromaKey;
+use super::DistilBuilder;
+use crate::{Distil, DistilError, Image};
+
+/// The share of the sampled image that can be transparent before
+/// `DistilWarning::MostlyTransparent` is raised.
+static MAX_TRANSPARENT_SHARE: f32 = 0.5;
+
+/// The share of the opaque pixels that can be left out before
+/// `DistilWarning::MostlyFiltered` is raised.
+static MAX_FILTERED_SHARE: f32 = 0.75;
+
+/// The number of sampled pixels under which `DistilWarning::FewSamples` is
+/// raised.
+static MIN_RELIABLE_SAMPLES: usize = 100;
+
+/// A condition that didn't stop an image from being distilled but may make
+/// its palette less trustworthy, as returned by `from_image_with_warnings`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistilWarning {
+    /// Most of the image was too transparent to be sampled. Holds the share
+    /// of the pixels that were.
+    MostlyTransparent(f32),
+
+    /// Most of the opaque pixels were left out as too dark, too light,
+    /// excluded, outside of the regions or by the pixel filter. Holds the
+    /// share of the opaque pixels that were.
+    MostlyFiltered(f32),
+
+    /// So few pixels were sampled that the palette may be noisy, e.g. for a
+    /// tiny image or a small `sample_count`. Holds the number sampled.
+    FewSamples(usize),
+
+    /// `ChromaKey::Detect` found no key color, so nothing was keyed out.
+    KeyNotFound,
+}
+
+impl fmt::Display for DistilWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DistilWarning::MostlyTransparent(share) => {
+                write!(f, "{:.0}% of the image is too transparent to be sampled", share * 100.0)
+            }
+            DistilWarning::MostlyFiltered(share) => {
+                write!(f, "{:.0}% of the opaque pixels were filtered out", share * 100.0)
+            }
+            DistilWarning::FewSamples(count) => write!(f, "Only {} pixels were sampled", count),
+            DistilWarning::KeyNotFound => write!(f, "No chroma key color was found"),
+        }
+    }
+}
+
+impl Distil {
+    /// `from_image_with_warnings` `Distil`s `img` like `from_image`, and
+    /// also returns the `DistilWarning`s for what may have made the palette
+    /// less trustworthy, so that a caller can flag a palette rather than
+    /// choose between trusting it and discarding it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let (distilled, warnings) = Distil::from_image_with_warnings(&gen::solid(6, 6, Color::new(0, 128, 128))).unwrap();
+    ///
+    /// for warning in &warnings {
+    ///     eprintln!("{}", warning);
+    /// }
+    /// ```
+    pub fn from_image_with_warnings(img: &Image) -> Result<(Distil, Vec<DistilWarning>), DistilError> {
+        DistilBuilder::default().from_image_with_warnings(img)
+    }
+}
+
+impl DistilBuilder {
+    /// Like `Distil::from_image_with_warnings`, with this configuration.
+    pub fn from_image_with_warnings(&self, img: &Image) -> Result<(Distil, Vec<DistilWarning>), DistilError> {
+        let distil = self.from_image(img)?;
+
+        let scaled_img = self.sampled(img);
+        let config = self.keyed(&scaled_img);
+        let mut warnings = Vec::new();
+
+        if matches!(self.key, Some((ChromaKey::Detect, _))) && config.excluded.len() == self.excluded.len() {
+            warnings.push(DistilWarning::KeyNotFound);
+        }
+
+        let config = config.scaled(img, &scaled_img);
+        let reasons = config.filter_reasons(&scaled_img);
+
+        let total = reasons.len().max(1);
+        let transparent = reasons.iter().filter(|reason| matches!(reason, Some(Filtered::Transparent))).count();
+        let sampled = reasons.iter().filter(|reason| reason.is_none()).count();
+        let opaque = reasons.len() - transparent;
+
+        if transparent as f32 > MAX_TRANSPARENT_SHARE * total as f32 {
+            warnings.push(DistilWarning::MostlyTransparent(transparent as f32 / total as f32));
+        }
+
+        if opaque > 0 && (opaque - sampled) as f32 > MAX_FILTERED_SHARE * opaque as f32 {
+            warnings.push(DistilWarning::MostlyFiltered((opaque - sampled) as f32 / opaque as f32));
+        }
+
+        if sampled < MIN_RELIABLE_SAMPLES {
+            warnings.push(DistilWarning::FewSamples(sampled));
+        }
+
+        Ok((distil, warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DistilWarning;
+    use crate::extract::ChromaKey;
+    use crate::{gen, Color, Distil};
+
+    #[test]
+    fn warnings() {
+        let teal = Color::new(0, 128, 128);
+
+        let (_, clean) = Distil::from_image_with_warnings(&gen::solid(40, 40, teal)).unwrap();
+        assert!(clean.is_empty());
+
+        let (_, tiny) = Distil::from_image_with_warnings(&gen::solid(5, 5, teal)).unwrap();
+        assert_eq!(tiny, [DistilWarning::FewSamples(25)]);
+
+        let mut logo = gen::solid(40, 40, teal);
+
+        for (i, px) in logo.pixels_mut().enumerate() {
+            if i % 40 >= 10 {
+                px[3] = 0;
+            }
+        }
+
+        // A quarter of the logo is opaque, but scaling it down for sampling
+        // blends the alpha of its edge, so a little more than three quarters
+        // ends up too transparent.
+        let (_, transparent) = Distil::from_image_with_warnings(&logo).unwrap();
+        assert!(matches!(transparent[..], [DistilWarning::MostlyTransparent(share)] if (0.7..0.85).contains(&share)));
+
+        let dark = gen::blocks(40, 40, &[(teal, 1), (Color::new(2, 2, 2), 4)]);
+        let (_, filtered) = Distil::from_image_with_warnings(&dark).unwrap();
+        assert!(matches!(filtered[..], [DistilWarning::MostlyFiltered(share)] if (0.75..0.85).contains(&share)));
+
+        let grey = gen::solid(40, 40, Color::new(128, 128, 128));
+        let (_, unkeyed) = Distil::builder().chroma_key(ChromaKey::Detect, 15.0).from_image_with_warnings(&grey).unwrap();
+        assert_eq!(unkeyed, [DistilWarning::KeyNotFound]);
+    }
+}
@@ -0,0 +1,262 @@
+use super::quantizer::{Quantizer, QUANTIZED_COLORS};
+
+/// The number of cells per side of the moment tables: 32 for the 5 most
+/// significant bits of each channel, plus an empty first cell.
+static SIDE: usize = 33;
+
+/// Xiaolin Wu's quantizer: the box of colors whose split reduces the
+/// variance the most is split next, using cumulative moments of a 5 bit per
+/// channel histogram. Fast, deterministic and usually more accurate than
+/// median-cut.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WuQuantizer {
+    /// The maximum number of boxes, up to 256. Defaults to 64.
+    pub colors: usize,
+}
+
+impl Default for WuQuantizer {
+    fn default() -> WuQuantizer {
+        WuQuantizer { colors: QUANTIZED_COLORS }
+    }
+}
+
+impl Quantizer for WuQuantizer {
+    fn name(&self) -> &'static str {
+        "Wu"
+    }
+
+    fn quantize(&self, pixels: &[u8]) -> Vec<[u8; 3]> {
+        if pixels.is_empty() {
+            return Vec::new();
+        }
+
+        let moments = Moments::new(pixels);
+        let mut cubes = vec![Cube { r0: 0, r1: SIDE - 1, g0: 0, g1: SIDE - 1, b0: 0, b1: SIDE - 1 }];
+        let mut variances = vec![0.0];
+        let mut next = 0;
+
+        while cubes.len() < self.colors.clamp(1, 256) {
+            match moments.cut(&mut cubes[next]) {
+                Some(cube) => {
+                    variances[next] = moments.variance(&cubes[next]);
+                    variances.push(moments.variance(&cube));
+                    cubes.push(cube);
+                }
+                None => variances[next] = 0.0,
+            }
+
+            let largest = variances.iter().enumerate().max_by(|(_, x), (_, y)| x.total_cmp(y));
+
+            match largest {
+                Some((i, &variance)) if variance > 0.0 => next = i,
+                _ => break,
+            }
+        }
+
+        let mut tags = vec![0; SIDE * SIDE * SIDE];
+        let mut colors = Vec::with_capacity(cubes.len());
+
+        for (i, cube) in cubes.iter().enumerate() {
+            for r in cube.r0 + 1..=cube.r1 {
+                for g in cube.g0 + 1..=cube.g1 {
+                    for b in cube.b0 + 1..=cube.b1 {
+                        tags[index(r, g, b)] = i;
+                    }
+                }
+            }
+
+            let [r, g, b, weight] = moments.sums(cube);
+            let mean = |sum: f64| (sum / weight.max(1.0)).round() as u8;
+            colors.push([mean(r), mean(g), mean(b)]);
+        }
+
+        pixels.chunks_exact(4).map(|rgba| colors[tags[cell(rgba)]]).collect()
+    }
+}
+
+/// A box of histogram cells, exclusive of its lower bounds and inclusive of
+/// its upper ones.
+#[derive(Debug, Clone, Copy)]
+struct Cube {
+    r0: usize,
+    r1: usize,
+    g0: usize,
+    g1: usize,
+    b0: usize,
+    b1: usize,
+}
+
+impl Cube {
+    fn volume(&self) -> usize {
+        (self.r1 - self.r0) * (self.g1 - self.g0) * (self.b1 - self.b0)
+    }
+
+    fn bounds(&mut self, channel: usize) -> (&mut usize, &mut usize) {
+        match channel {
+            0 => (&mut self.r0, &mut self.r1),
+            1 => (&mut self.g0, &mut self.g1),
+            _ => (&mut self.b0, &mut self.b1),
+        }
+    }
+}
+
+/// Cumulative moments of the histogram: the count of pixels, the sums of
+/// each channel and the sum of squares, of every cell up to each cell.
+struct Moments {
+    weights: Vec<f64>,
+    reds: Vec<f64>,
+    greens: Vec<f64>,
+    blues: Vec<f64>,
+    squares: Vec<f64>,
+}
+
+impl Moments {
+    fn new(pixels: &[u8]) -> Moments {
+        let mut moments = Moments {
+            weights: vec![0.0; SIDE * SIDE * SIDE],
+            reds: vec![0.0; SIDE * SIDE * SIDE],
+            greens: vec![0.0; SIDE * SIDE * SIDE],
+            blues: vec![0.0; SIDE * SIDE * SIDE],
+            squares: vec![0.0; SIDE * SIDE * SIDE],
+        };
+
+        for rgba in pixels.chunks_exact(4) {
+            let i = cell(rgba);
+            let (r, g, b) = (rgba[0] as f64, rgba[1] as f64, rgba[2] as f64);
+
+            moments.weights[i] += 1.0;
+            moments.reds[i] += r;
+            moments.greens[i] += g;
+            moments.blues[i] += b;
+            moments.squares[i] += r * r + g * g + b * b;
+        }
+
+        for table in [&mut moments.weights, &mut moments.reds, &mut moments.greens, &mut moments.blues, &mut moments.squares] {
+            accumulate(table);
+        }
+
+        moments
+    }
+
+    /// Returns the sums of the reds, greens, blues and weights in `cube`.
+    fn sums(&self, cube: &Cube) -> [f64; 4] {
+        [
+            volume(cube, &self.reds),
+            volume(cube, &self.greens),
+            volume(cube, &self.blues),
+            volume(cube, &self.weights),
+        ]
+    }
+
+    /// The sum of the squared distances of the colors in `cube` to their
+    /// mean, or `0.0` if it can't be split any further.
+    fn variance(&self, cube: &Cube) -> f64 {
+        if cube.volume() <= 1 {
+            return 0.0;
+        }
+
+        let [r, g, b, weight] = self.sums(cube);
+
+        if weight == 0.0 {
+            return 0.0;
+        }
+
+        volume(cube, &self.squares) - (r * r + g * g + b * b) / weight
+    }
+
+    /// Splits `cube` along the channel and at the position that leaves the
+    /// least variance, shrinking `cube` to the lower half and returning the
+    /// upper one, or `None` if it can't be split.
+    fn cut(&self, cube: &mut Cube) -> Option<Cube> {
+        let whole = self.sums(cube);
+
+        let (channel, at) = (0..3)
+            .filter_map(|channel| self.maximize(cube, channel, whole).map(|(score, at)| (score, channel, at)))
+            .max_by(|(x, ..), (y, ..)| x.total_cmp(y))
+            .map(|(_, channel, at)| (channel, at))?;
+
+        let mut upper = *cube;
+        *upper.bounds(channel).0 = at;
+        *cube.bounds(channel).1 = at;
+
+        Some(upper)
+    }
+
+    /// Finds the position along `channel` that splits `cube` into the two
+    /// halves with the most between-class variance, and that variance.
+    fn maximize(&self, cube: &Cube, channel: usize, whole: [f64; 4]) -> Option<(f64, usize)> {
+        let mut lower = *cube;
+        let (first, last) = {
+            let (start, end) = lower.bounds(channel);
+            (*start + 1, *end)
+        };
+
+        let mut best = None;
+        let mut max = 0.0;
+
+        for at in first..last {
+            *lower.bounds(channel).1 = at;
+
+            let half = self.sums(&lower);
+            let other = [whole[0] - half[0], whole[1] - half[1], whole[2] - half[2], whole[3] - half[3]];
+
+            if half[3] == 0.0 || other[3] == 0.0 {
+                continue;
+            }
+
+            let score = (half[0] * half[0] + half[1] * half[1] + half[2] * half[2]) / half[3] +
+                        (other[0] * other[0] + other[1] * other[1] + other[2] * other[2]) / other[3];
+
+            if score > max {
+                max = score;
+                best = Some((score, at));
+            }
+        }
+
+        best
+    }
+}
+
+fn index(r: usize, g: usize, b: usize) -> usize {
+    (r * SIDE + g) * SIDE + b
+}
+
+/// The histogram cell of `rgba`.
+fn cell(rgba: &[u8]) -> usize {
+    index((rgba[0] >> 3) as usize + 1, (rgba[1] >> 3) as usize + 1, (rgba[2] >> 3) as usize + 1)
+}
+
+/// Turns `table` into a table of the sums of every cell up to each cell.
+fn accumulate(table: &mut [f64]) {
+    for r in 1..SIDE {
+        for g in 1..SIDE {
+            for b in 1..SIDE {
+                table[index(r, g, b)] += table[index(r - 1, g, b)] + table[index(r, g - 1, b)] + table[index(r, g, b - 1)] -
+                                         table[index(r - 1, g - 1, b)] - table[index(r - 1, g, b - 1)] -
+                                         table[index(r, g - 1, b - 1)] + table[index(r - 1, g - 1, b - 1)];
+            }
+        }
+    }
+}
+
+/// The sum of the cells of `table` in `cube`, from its cumulative moments.
+fn volume(cube: &Cube, table: &[f64]) -> f64 {
+    table[index(cube.r1, cube.g1, cube.b1)] - table[index(cube.r1, cube.g1, cube.b0)] -
+    table[index(cube.r1, cube.g0, cube.b1)] + table[index(cube.r1, cube.g0, cube.b0)] -
+    table[index(cube.r0, cube.g1, cube.b1)] + table[index(cube.r0, cube.g1, cube.b0)] +
+    table[index(cube.r0, cube.g0, cube.b1)] - table[index(cube.r0, cube.g0, cube.b0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separates_colors() {
+        let pixels = [[0, 128, 128, 255], [0, 128, 128, 255], [255, 127, 80, 255], [20, 20, 220, 255]].concat();
+        let quantized = WuQuantizer { colors: 3 }.quantize(&pixels);
+
+        assert_eq!(quantized, vec![[0, 128, 128], [0, 128, 128], [255, 127, 80], [20, 20, 220]]);
+        assert_eq!(WuQuantizer { colors: 1 }.quantize(&pixels)[0], [69, 101, 139]);
+    }
+}
@@ -0,0 +1,18 @@
+/// An encoded image format Distil can decode, for restricting which ones
+/// are accepted with `DistilBuilder::allow_formats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    Jpeg,
+    Png,
+    WebP,
+    Gif,
+    Bmp,
+    Tiff,
+}
+
+impl Format {
+    /// Every format Distil can decode.
+    pub fn all() -> [Format; 6] {
+        [Format::Jpeg, Format::Png, Format::WebP, Format::Gif, Format::Bmp, Format::Tiff]
+    }
+}
@@ -0,0 +1,158 @@
+//! Synthetic images with known palettes, so that extraction can be checked
+//! against ground truth without binary fixtures.
+//!
+//! ## Example
+//!
+//! ```
+//! use distil_core::{delta_e, gen, Color, Distil};
+//!
+//! let teal = Color::new(0, 128, 128);
+//! let coral = Color::new(240, 110, 90);
+//! let img = gen::blocks(90, 30, &[(teal, 2), (coral, 1)]);
+//!
+//! let distilled = Distil::from_image(&img).unwrap();
+//!
+//! for expected in [teal, coral] {
+//!     assert!(distilled.colors.iter().any(|&color| delta_e(color, expected) < 5.0));
+//! }
+//! ```
+
+use crate::{Color, Image};
+
+/// Returns an image filled with `color`.
+pub fn solid(width: u32, height: u32, color: Color) -> Image {
+    blocks(width, height, &[(color, 1)])
+}
+
+/// Returns an image made of vertical bands of color, left to right, each as
+/// wide as its weight's share of the total weight, to the nearest pixel.
+pub fn blocks(width: u32, height: u32, blocks: &[(Color, u32)]) -> Image {
+    let mut img = Image::new(width, height);
+    let total = blocks.iter().map(|&(_, weight)| weight as u64).sum::<u64>().max(1);
+    let mut start = 0;
+    let mut cumulative = 0;
+
+    for &(color, weight) in blocks {
+        cumulative += weight as u64;
+        let end = ((cumulative * width as u64 + total / 2) / total) as u32;
+        fill(&mut img, start..end, 0..height, color);
+        start = end;
+    }
+
+    img
+}
+
+/// Returns a horizontal gradient from `from` on the left edge to `to` on the
+/// right edge, interpolated in sRGB.
+pub fn gradient(width: u32, height: u32, from: Color, to: Color) -> Image {
+    let mut img = Image::new(width, height);
+    let (from, to) = (from.rgb(), to.rgb());
+    let steps = width.saturating_sub(1).max(1) as f32;
+
+    for x in 0..width {
+        let t = x as f32 / steps;
+        let channel = |i: usize| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8;
+        fill(&mut img, x..x + 1, 0..height, Color::new(channel(0), channel(1), channel(2)));
+    }
+
+    img
+}
+
+/// Returns a checkerboard of `cell_size` pixel squares, starting with `a` in
+/// the top-left corner.
+pub fn checkerboard(width: u32, height: u32, cell_size: u32, a: Color, b: Color) -> Image {
+    let mut img = Image::new(width, height);
+    let cell_size = cell_size.max(1);
+
+    for y in 0..height {
+        for x in 0..width {
+            let color = if (x / cell_size + y / cell_size) % 2 == 0 { a } else { b };
+            let [r, g, b] = color.rgb();
+            img.put_pixel(x, y, [r, g, b, 255]);
+        }
+    }
+
+    img
+}
+
+/// Returns an image of uniformly random opaque pixels. The same `seed`
+/// always produces the same image.
+pub fn noise(width: u32, height: u32, seed: u64) -> Image {
+    let mut rng = Rng::new(seed);
+    let mut img = Image::new(width, height);
+
+    for px in img.pixels_mut() {
+        let value = rng.next();
+        px.copy_from_slice(&[value as u8, (value >> 8) as u8, (value >> 16) as u8, 255]);
+    }
+
+    img
+}
+
+/// Returns `img` with every channel of every pixel moved by a random amount
+/// of at most `amplitude`, keeping alpha. The same `seed` always produces
+/// the same image.
+pub fn jitter(img: &Image, amplitude: u8, seed: u64) -> Image {
+    let mut rng = Rng::new(seed);
+    let mut jittered = img.clone();
+    let range = 2 * amplitude as u64 + 1;
+
+    for px in jittered.pixels_mut() {
+        for channel in &mut px[..3] {
+            let offset = (rng.next() % range) as i16 - amplitude as i16;
+            *channel = (*channel as i16 + offset).clamp(0, 255) as u8;
+        }
+    }
+
+    jittered
+}
+
+fn fill(img: &mut Image, xs: std::ops::Range<u32>, ys: std::ops::Range<u32>, color: Color) {
+    let [r, g, b] = color.rgb();
+
+    for y in ys {
+        for x in xs.clone() {
+            img.put_pixel(x, y, [r, g, b, 255]);
+        }
+    }
+}
+
+/// A xorshift64* generator, which is plenty for test images and seeding
+/// k-means, and keeps the crate free of dependencies.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng(seed.max(1))
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, checkerboard, gradient, jitter, noise};
+    use crate::Color;
+
+    #[test]
+    fn ground_truth() {
+        let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+
+        let banded = blocks(3, 1, &[(red, 2), (blue, 1)]);
+        assert_eq!(banded.pixels().collect::<Vec<_>>(), vec![[255, 0, 0, 255], [255, 0, 0, 255], [0, 0, 255, 255]]);
+
+        let ramp = gradient(3, 1, Color::new(0, 0, 0), Color::new(200, 100, 0));
+        assert_eq!(ramp.get_pixel(1, 0), [100, 50, 0, 255]);
+
+        let board = checkerboard(4, 4, 2, red, blue);
+        assert_eq!((board.get_pixel(1, 1), board.get_pixel(2, 1)), ([255, 0, 0, 255], [0, 0, 255, 255]));
+
+        assert_eq!(noise(8, 8, 7), noise(8, 8, 7));
+        assert!(jitter(&banded, 3, 7).pixels().all(|px| px[0] >= 252 || px[0] <= 3));
+    }
+}
@@ -0,0 +1,222 @@
+//! The only module that uses the `image` crate, so that bumping it never
+//! changes Distil's public API.
+
+use std::fs::File;
+use std::io::{self, BufReader, Cursor};
+use std::path::Path;
+use std::time::Duration;
+
+use image::codecs::gif::GifDecoder;
+use image::imageops::FilterType::Gaussian;
+use image::{AnimationDecoder, DynamicImage, ImageBuffer, ImageError, ImageFormat, ImageReader, RgbaImage};
+
+use crate::{DistilError, Format, Image};
+
+pub(crate) fn open(path: &Path) -> Result<Image, DistilError> {
+    open_as(path, None)
+}
+
+/// Like `open`, but only decodes the `allowed` formats when there are any.
+pub(crate) fn open_as(path: &Path, allowed: Option<&[Format]>) -> Result<Image, DistilError> {
+    let io_err = |err: ImageError| DistilError::Io(format!("{:?}", path), err.to_string());
+
+    let reader = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|err| io_err(ImageError::IoError(err)))?;
+
+    is_allowed_format(reader.format(), allowed)?;
+
+    match reader.decode() {
+        Ok(img) => Ok(from_rgba_image(img.to_rgba8())),
+        Err(err) => Err(io_err(err)),
+    }
+}
+
+/// Decodes an image that's already in memory, e.g. an upload or a
+/// memory-mapped file, guessing its format from its contents. `source` is
+/// only used for errors.
+pub(crate) fn decode(bytes: &[u8], source: &str) -> Result<Image, DistilError> {
+    decode_as(bytes, source, None)
+}
+
+/// Like `decode`, but only decodes the `allowed` formats when there are any.
+pub(crate) fn decode_as(bytes: &[u8], source: &str, allowed: Option<&[Format]>) -> Result<Image, DistilError> {
+    let io_err = |err: ImageError| DistilError::Io(source.to_owned(), err.to_string());
+
+    let reader = ImageReader::new(io::Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|err| io_err(ImageError::IoError(err)))?;
+
+    is_allowed_format(reader.format(), allowed)?;
+
+    match reader.decode() {
+        Ok(img) => Ok(from_rgba_image(img.to_rgba8())),
+        Err(err) => Err(io_err(err)),
+    }
+}
+
+/// Decodes the frames of the GIF at `path` one at a time, each composited
+/// onto the frames before it, along with how long each is shown for.
+pub(crate) fn gif_frames(path: &Path)
+                         -> Result<impl Iterator<Item = Result<(Image, Duration), DistilError>>, DistilError> {
+    let label = format!("{:?}", path);
+    let io_err = move |err: ImageError| DistilError::Io(label.clone(), err.to_string());
+
+    let file = File::open(path).map_err(|err| io_err(ImageError::IoError(err)))?;
+    let decoder = GifDecoder::new(BufReader::new(file)).map_err(&io_err)?;
+
+    Ok(decoder.into_frames().map(move |frame| {
+        let frame = frame.map_err(&io_err)?;
+        let (numerator, denominator) = frame.delay().numer_denom_ms();
+        let delay = Duration::from_micros(numerator as u64 * 1000 / denominator.max(1) as u64);
+
+        Ok((from_rgba_image(frame.into_buffer()), delay))
+    }))
+}
+
+pub(crate) fn encode_png(img: &Image) -> Result<Vec<u8>, DistilError> {
+    let mut png = Cursor::new(Vec::new());
+
+    to_rgba_image(img)
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|err| DistilError::Output("PNG".to_owned(), io::Error::other(err.to_string())))?;
+
+    Ok(png.into_inner())
+}
+
+pub(crate) fn save(img: &Image, path: &Path) -> Result<(), DistilError> {
+    let rgba = to_rgba_image(img);
+
+    // JPEGs can't store alpha, so it's dropped rather than failing.
+    let result = match ImageFormat::from_path(path) {
+        Ok(ImageFormat::Jpeg) => DynamicImage::ImageRgba8(rgba).to_rgb8().save(path),
+        _ => rgba.save(path),
+    };
+
+    result.map_err(|err| {
+        let err = match err {
+            ImageError::IoError(err) => err,
+            err => io::Error::other(err.to_string()),
+        };
+
+        DistilError::Output(format!("{:?}", path), err)
+    })
+}
+
+/// Resizes `img` to fit within `width` × `height`, preserving its aspect
+/// ratio.
+pub(crate) fn resize(img: &Image, width: u32, height: u32) -> Image {
+    let resized = DynamicImage::ImageRgba8(to_rgba_image(img)).resize(width, height, Gaussian);
+    from_rgba_image(resized.to_rgba8())
+}
+
+/// Whether `path` has the extension of a format `open` can decode.
+pub(crate) fn has_image_extension(path: &Path) -> bool {
+    ImageFormat::from_path(path).is_ok_and(|format| format.reading_enabled())
+}
+
+/// Accepts any format the `image` crate was built to decode: JPEG, PNG,
+/// WebP, GIF, BMP and TIFF, or only the `allowed` ones when there are any.
+fn is_allowed_format(format: Option<ImageFormat>, allowed: Option<&[Format]>) -> Result<(), DistilError> {
+    let format = match format {
+        Some(format) if format.reading_enabled() => format,
+        _ => return Err(DistilError::UnsupportedFormat),
+    };
+
+    match allowed {
+        Some(allowed) if !allowed.iter().any(|&allowed| image_format(allowed) == format) => Err(DistilError::UnsupportedFormat),
+        _ => Ok(()),
+    }
+}
+
+fn image_format(format: Format) -> ImageFormat {
+    match format {
+        Format::Jpeg => ImageFormat::Jpeg,
+        Format::Png => ImageFormat::Png,
+        Format::WebP => ImageFormat::WebP,
+        Format::Gif => ImageFormat::Gif,
+        Format::Bmp => ImageFormat::Bmp,
+        Format::Tiff => ImageFormat::Tiff,
+    }
+}
+
+fn to_rgba_image(img: &Image) -> RgbaImage {
+    let (width, height) = img.dimensions();
+
+    // An `Image`'s buffer always matches its dimensions, so the fallback
+    // is never used.
+    ImageBuffer::from_raw(width, height, img.as_raw().to_vec()).unwrap_or_else(|| RgbaImage::new(width, height))
+}
+
+fn from_rgba_image(rgba: RgbaImage) -> Image {
+    let (width, height) = rgba.dimensions();
+
+    // As is an `RgbaImage`'s.
+    Image::from_rgba(width, height, rgba.into_raw()).unwrap_or_else(|_| Image::new(width, height))
+}
+
+#[cfg(feature = "image-interop")]
+impl From<DynamicImage> for Image {
+    fn from(img: DynamicImage) -> Image {
+        from_rgba_image(img.to_rgba8())
+    }
+}
+
+#[cfg(feature = "image-interop")]
+impl From<RgbaImage> for Image {
+    fn from(rgba: RgbaImage) -> Image {
+        from_rgba_image(rgba)
+    }
+}
+
+#[cfg(feature = "image-interop")]
+impl From<Image> for RgbaImage {
+    fn from(img: Image) -> RgbaImage {
+        let (width, height) = img.dimensions();
+
+        ImageBuffer::from_raw(width, height, img.into_raw()).unwrap_or_else(|| RgbaImage::new(width, height))
+    }
+}
+
+#[cfg(feature = "image-interop")]
+impl From<Image> for DynamicImage {
+    fn from(img: Image) -> DynamicImage {
+        DynamicImage::ImageRgba8(img.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::Image;
+
+    #[test]
+    fn formats() {
+        let mut img = Image::new(4, 4);
+
+        for px in img.pixels_mut() {
+            px.copy_from_slice(&[0, 128, 128, 255]);
+        }
+
+        for extension in &["bmp", "gif", "png", "tiff", "webp"] {
+            let path = env::temp_dir().join(format!("distil-imaging-test.{}", extension));
+
+            img.save(&path).unwrap();
+            assert_eq!(Image::open(&path).unwrap(), img, "{}", extension);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "image-interop")]
+    fn interop() {
+        use image::{DynamicImage, GenericImage, Rgba};
+
+        let mut dynamic = DynamicImage::new_rgba8(2, 2);
+        dynamic.put_pixel(1, 0, Rgba([1, 2, 3, 4]));
+
+        let img = Image::from(dynamic.clone());
+        assert_eq!(img.get_pixel(1, 0), [1, 2, 3, 4]);
+        assert_eq!(DynamicImage::from(img).as_bytes(), dynamic.as_bytes());
+    }
+}
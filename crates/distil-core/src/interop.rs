@@ -0,0 +1,263 @@
+//! Conversions between `Color` and the color types of GUI and color crates,
+//! and serde support, each behind the feature of the same name.
+
+#[cfg(feature = "serde")]
+pub(crate) use self::serde::lab_colors;
+
+#[cfg(feature = "egui")]
+mod egui {
+    use ecolor::Color32;
+
+    use crate::Color;
+
+    impl From<Color> for Color32 {
+        fn from(color: Color) -> Color32 {
+            let [r, g, b] = color.rgb();
+            Color32::from_rgb(r, g, b)
+        }
+    }
+
+    /// Alpha is discarded.
+    impl From<Color32> for Color {
+        fn from(color: Color32) -> Color {
+            let [r, g, b, _] = color.to_srgba_unmultiplied();
+            Color::new(r, g, b)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ecolor::Color32;
+
+        use crate::Color;
+
+        #[test]
+        fn round_trip() {
+            let teal = Color::new(0, 128, 128);
+
+            assert_eq!(Color32::from(teal), Color32::from_rgb(0, 128, 128));
+            assert_eq!(Color::from(Color32::from(teal)), teal);
+        }
+    }
+}
+
+#[cfg(feature = "iced")]
+mod iced {
+
+    use crate::Color;
+
+    impl From<Color> for iced_core::Color {
+        fn from(color: Color) -> iced_core::Color {
+            let [r, g, b] = color.rgb();
+            iced_core::Color::from_rgb8(r, g, b)
+        }
+    }
+
+    /// Alpha is discarded and channels outside `0.0..=1.0` are clamped.
+    impl From<iced_core::Color> for Color {
+        fn from(color: iced_core::Color) -> Color {
+            let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+            Color::new(channel(color.r), channel(color.g), channel(color.b))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+
+        use crate::Color;
+
+        #[test]
+        fn round_trip() {
+            let coral = Color::new(255, 127, 80);
+
+            assert_eq!(iced_core::Color::from(coral), iced_core::Color::from_rgb8(255, 127, 80));
+            assert_eq!(Color::from(iced_core::Color::from(coral)), coral);
+        }
+    }
+}
+
+#[cfg(feature = "palette")]
+mod palette {
+    use lab::Lab;
+    use ::palette::{self as pal, IntoColor, Srgb};
+
+    use crate::color::srgb_to_lab;
+    use crate::{Color, IntoLab};
+
+    impl From<Color> for Srgb<u8> {
+        fn from(color: Color) -> Srgb<u8> {
+            let [r, g, b] = color.rgb();
+            Srgb::new(r, g, b)
+        }
+    }
+
+    impl From<Srgb<u8>> for Color {
+        fn from(color: Srgb<u8>) -> Color {
+            Color::new(color.red, color.green, color.blue)
+        }
+    }
+
+    impl From<Color> for pal::Lab {
+        fn from(color: Color) -> pal::Lab {
+            let lab = color.into_lab();
+            pal::Lab::new(lab.l, lab.a, lab.b)
+        }
+    }
+
+    /// Out of gamut colors are clamped.
+    impl From<pal::Lab> for Color {
+        fn from(lab: pal::Lab) -> Color {
+            Color::from(lab.into_lab())
+        }
+    }
+
+    impl From<Color> for Srgb<f32> {
+        fn from(color: Color) -> Srgb<f32> {
+            Srgb::<u8>::from(color).into_format()
+        }
+    }
+
+    /// Channels outside `0.0..=1.0` are clamped.
+    impl From<Srgb<f32>> for Color {
+        fn from(color: Srgb<f32>) -> Color {
+            Color::from(color.into_format::<u8>())
+        }
+    }
+
+    impl From<Color> for pal::Lch {
+        fn from(color: Color) -> pal::Lch {
+            pal::Lab::from(color).into_color()
+        }
+    }
+
+    /// Out of gamut colors are clamped.
+    impl From<pal::Lch> for Color {
+        fn from(lch: pal::Lch) -> Color {
+            Color::from(lch.into_lab())
+        }
+    }
+
+    impl IntoLab for pal::Lab {
+        fn into_lab(self) -> Lab {
+            Lab { l: self.l, a: self.a, b: self.b }
+        }
+    }
+
+    impl IntoLab for pal::Lch {
+        fn into_lab(self) -> Lab {
+            let lab: pal::Lab = self.into_color();
+            lab.into_lab()
+        }
+    }
+
+    impl IntoLab for Srgb<u8> {
+        fn into_lab(self) -> Lab {
+            Color::from(self).into_lab()
+        }
+    }
+
+    impl IntoLab for Srgb<f32> {
+        fn into_lab(self) -> Lab {
+            srgb_to_lab([self.red, self.green, self.blue])
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ::palette::{Lab, Lch, Srgb};
+
+        use crate::{delta_e, Color};
+
+        #[test]
+        fn round_trip() {
+            let navy = Color::new(0, 0, 128);
+
+            assert_eq!(Srgb::<u8>::from(navy), Srgb::new(0, 0, 128));
+            assert_eq!(Color::from(Srgb::<u8>::from(navy)), navy);
+            assert_eq!(Color::from(Lab::from(navy)), navy);
+            assert_eq!(Color::from(Lch::from(navy)), navy);
+            assert_eq!(Color::from(Srgb::<f32>::from(navy)), navy);
+        }
+
+        #[test]
+        fn into_lab() {
+            let navy = Color::new(0, 0, 128);
+
+            assert!(delta_e(navy, Lab::from(navy)) < 0.01);
+            assert!(delta_e(navy, Lch::from(navy)) < 0.01);
+            assert!(delta_e(navy, Srgb::new(0.0, 0.0, 128.0 / 255.0)) < 0.1);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::fmt;
+
+    use ::serde::de::{self, Visitor};
+    use ::serde::{Deserializer, Serializer};
+
+    use crate::Color;
+
+    /// Colors are serialized as hex strings, e.g. `"#008080"`, and can be
+    /// deserialized from any string `Color::from_str` accepts.
+    impl ::serde::Serialize for Color {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.hex())
+        }
+    }
+
+    impl<'de> ::serde::Deserialize<'de> for Color {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+            deserializer.deserialize_str(ColorVisitor)
+        }
+    }
+
+    struct ColorVisitor;
+
+    impl<'de> Visitor<'de> for ColorVisitor {
+        type Value = Color;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a CSS color")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Color, E> {
+            value.parse().map_err(E::custom)
+        }
+    }
+
+    /// (De)serializes the `lab_colors` of a `Distil` as `[l, a, b]` arrays,
+    /// since `Lab` doesn't implement serde's traits itself.
+    pub(crate) mod lab_colors {
+        use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+        use lab::Lab;
+
+        pub(crate) fn serialize<S: Serializer>(colors: &[Lab], serializer: S) -> Result<S::Ok, S::Error> {
+            colors.iter().map(|lab| [lab.l, lab.a, lab.b]).collect::<Vec<_>>().serialize(serializer)
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Lab>, D::Error> {
+            let colors = Vec::<[f32; 3]>::deserialize(deserializer)?;
+            Ok(colors.into_iter().map(|[l, a, b]| Lab { l, a, b }).collect())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ::serde::de::value::{Error, StrDeserializer};
+        use ::serde::de::IntoDeserializer;
+        use ::serde::Deserialize;
+
+        use crate::Color;
+
+        #[test]
+        fn color() {
+            let teal: StrDeserializer<Error> = "rgb(0, 128, 128)".into_deserializer();
+            let invalid: StrDeserializer<Error> = "teal-ish".into_deserializer();
+
+            assert_eq!(Color::deserialize(teal).unwrap(), Color::new(0, 128, 128));
+            assert!(Color::deserialize(invalid).is_err());
+        }
+    }
+}
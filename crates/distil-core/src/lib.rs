@@ -0,0 +1,63 @@
+//! Distil creates a colour palette from the most frequently used colours in
+//! an image.
+//!
+//! - `extract` distils images into palettes.
+//! - `palette` holds the distilled `Distil` palette and what can be done
+//!   with it: set operations, compliance checks and descriptions.
+//! - `gen` builds synthetic images with known palettes for tests and
+//!   examples.
+//! - `render` produces images from palettes: swatches, grades and
+//!   heatmaps. It's only available with the `render` feature.
+//!
+//! Palettes are quantized with NeuQuant when the `neuquant` feature is
+//! enabled, and with a dependency-free histogram otherwise. Both features
+//! are enabled by default.
+//!
+//! Exporting palettes and grades to other formats lives in the
+//! `distil-export` crate.
+//!
+//! ## Threads
+//!
+//! `Distil::from_dir` is the only entry point that spawns threads, and only
+//! with the `parallel` feature. Everything else runs on the calling thread.
+//!
+//! ## Panics
+//!
+//! No public function panics, whatever image, bytes, path or configuration
+//! it's passed: zero-sized images, truncated files and out of range options
+//! are reported as a `DistilError` or clamped instead. The only exceptions
+//! are running out of memory, e.g. rendering a palette billions of pixels
+//! wide, and the methods documented to panic, such as `Image::get_pixel`
+//! outside of the image. `unwrap`, `expect` and `panic!` are denied outside
+//! of tests, but that lint doesn't see failed assertions or out of bounds
+//! indexing, so the `public_api_never_panics` property test also feeds the
+//! public functions arbitrary images, bytes and rectangles.
+
+#![cfg_attr(not(test),
+            deny(clippy::unwrap_used, clippy::expect_used, clippy::panic, clippy::todo, clippy::unimplemented))]
+
+pub use crate::buffer::Image;
+pub use crate::color::{analogous, complementary, contrast_ratio, delta_e, lab_to_linear_rgb, lab_to_rgb, lab_to_srgb, srgb_to_lab, triadic, Color,
+                       IntoLab};
+pub use crate::error::DistilError;
+pub use crate::extract::{DirConfig, DistilBuilder, PyramidConfig};
+pub use crate::format::Format;
+pub use crate::palette::Distil;
+pub use lab::Lab;
+
+/// The version of the `image` crate that `Image` converts to and from.
+#[cfg(feature = "image-interop")]
+pub use image;
+
+pub mod extract;
+pub mod gen;
+pub mod palette;
+#[cfg(feature = "render")]
+pub mod render;
+
+mod buffer;
+mod color;
+mod error;
+mod format;
+mod imaging;
+mod interop;
@@ -0,0 +1,197 @@
+use std::cmp::{Ordering, Reverse};
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use super::{balance_colors, distil_palette, lab_palette, member_palette, with_members, Distil};
+use crate::extract::{remove_similar_colors, MIN_DISTANCE_FOR_UNIQUENESS};
+
+impl Distil {
+    /// Distils multiple palettes, e.g. those of an album of photos, into
+    /// one. Their colors are averaged in Lab space, weighted by their
+    /// counts, and merged until no two are similar, as when distilling a
+    /// single image.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let album: Vec<Distil> = ["./images/img-1.jpg", "./images/img-3.jpg", "./images/img-4.jpg"]
+    ///     .iter()
+    ///     .filter_map(|path| Distil::from_path_str(path).ok())
+    ///     .collect();
+    ///
+    /// let album_palette = Distil::merge(&album);
+    /// ```
+    pub fn merge(distils: &[Distil]) -> Distil {
+        let mut palette: Vec<(Lab, usize)> = distils.iter().flat_map(lab_palette).collect();
+        palette.sort_by_key(|&(_, count)| Reverse(count));
+
+        let members: Vec<_> = distils.iter().flat_map(member_palette).collect();
+        with_members(distil_palette(remove_similar_colors(palette, MIN_DISTANCE_FOR_UNIQUENESS)), &members)
+    }
+
+    /// Combines the colors of two palettes. Each color of `other` that's
+    /// within `tolerance` (a CIEDE2000 distance) of a color in `self` is
+    /// merged into it, with their counts summed; the rest are added as is.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let a = Distil::from_path_str("./images/img-1.jpg");
+    /// let b = Distil::from_path_str("./images/img-3.jpg");
+    ///
+    /// if let (Ok(a), Ok(b)) = (a, b) {
+    ///     let both = a.union(&b, 10.0);
+    /// }
+    /// ```
+    pub fn union(&self, other: &Distil, tolerance: f32) -> Distil {
+        let mut palette = lab_palette(self);
+
+        for y in lab_palette(other) {
+            let matched = palette.iter()
+                .map(|&(lab, _)| DE2000::new(lab, y.0))
+                .enumerate()
+                .filter(|&(_, delta)| delta < tolerance)
+                .min_by(|&(_, a), &(_, b)| a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+                .map(|(i, _)| i);
+
+            match matched {
+                Some(i) => palette[i] = balance_colors(palette[i], y),
+                None => palette.push(y),
+            }
+        }
+
+        palette.sort_by_key(|&(_, count)| Reverse(count));
+
+        let members: Vec<_> = member_palette(self).into_iter().chain(member_palette(other)).collect();
+        with_members(distil_palette(palette), &members)
+    }
+
+    /// Returns the colors that appear in both palettes, i.e. the colors of
+    /// `self` that are within `tolerance` of a color in `other`. Each pair of
+    /// matching colors is merged into one, with their counts summed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let a = Distil::from_path_str("./images/img-1.jpg");
+    /// let b = Distil::from_path_str("./images/img-3.jpg");
+    ///
+    /// if let (Ok(a), Ok(b)) = (a, b) {
+    ///     let shared = a.intersection(&b, 10.0);
+    /// }
+    /// ```
+    pub fn intersection(&self, other: &Distil, tolerance: f32) -> Distil {
+        let others = lab_palette(other);
+
+        let mut palette: Vec<(Lab, usize)> = lab_palette(self)
+            .into_iter()
+            .filter_map(|x| {
+                closest(x.0, &others, tolerance).map(|y| balance_colors(x, y))
+            })
+            .collect();
+
+        palette.sort_by_key(|&(_, count)| Reverse(count));
+
+        let ours = lab_palette(self);
+        let members: Vec<_> = member_palette(self)
+            .into_iter()
+            .filter(|&(lab, _, _)| closest(lab, &others, tolerance).is_some())
+            .chain(member_palette(other).into_iter().filter(|&(lab, _, _)| closest(lab, &ours, tolerance).is_some()))
+            .collect();
+
+        with_members(distil_palette(palette), &members)
+    }
+
+    /// Returns the colors of `self` that aren't within `tolerance` of any
+    /// color in `other`, e.g. the colors unique to a product photo when
+    /// compared to the rest of a catalog.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// let photo = Distil::from_path_str("./images/img-1.jpg");
+    /// let baseline = Distil::from_path_str("./images/img-3.jpg");
+    ///
+    /// if let (Ok(photo), Ok(baseline)) = (photo, baseline) {
+    ///     let unique = photo.difference(&baseline, 10.0);
+    /// }
+    /// ```
+    pub fn difference(&self, other: &Distil, tolerance: f32) -> Distil {
+        let others = lab_palette(other);
+
+        let palette = lab_palette(self)
+            .into_iter()
+            .filter(|&(lab, _)| closest(lab, &others, tolerance).is_none())
+            .collect();
+
+        let members: Vec<_> = member_palette(self)
+            .into_iter()
+            .filter(|&(lab, _, _)| closest(lab, &others, tolerance).is_none())
+            .collect();
+
+        with_members(distil_palette(palette), &members)
+    }
+}
+
+/// Returns the color in `palette` closest to `lab`, as long as it's within
+/// `tolerance`.
+fn closest(lab: Lab, palette: &[(Lab, usize)], tolerance: f32) -> Option<(Lab, usize)> {
+    palette.iter()
+        .map(|&color| (DE2000::new(lab, color.0), color))
+        .filter(|&(delta, _)| delta < tolerance)
+        .min_by(|&(a, _), &(b, _)| a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+        .map(|(_, color)| color)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn arithmetic() {
+        let a = Distil::from_path(Path::new("./images/img-1.jpg")).unwrap();
+        let b = Distil::from_path(Path::new("./images/img-3.jpg")).unwrap();
+
+        let doubled = a.union(&a, 10.0);
+        assert_eq!(doubled.colors.len(), a.colors.len());
+        assert!(doubled.lab_colors.iter().zip(a.lab_colors.iter()).all(|(&x, &y)| delta_e(x, y) < 0.01));
+        assert_eq!(doubled.counts[0], a.counts[0] * 2);
+
+        assert_eq!(a.intersection(&a, 10.0).colors.len(), a.colors.len());
+        assert!(a.difference(&a, 10.0).colors.is_empty());
+
+        let unique = a.difference(&b, 10.0);
+        let shared = a.intersection(&b, 10.0);
+        assert_eq!(unique.colors.len() + shared.colors.len(), a.colors.len());
+    }
+
+    #[test]
+    fn merge() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(240, 110, 90);
+        let gold = Color::new(230, 180, 40);
+
+        let a = Distil::from_image(&gen::blocks(60, 20, &[(teal, 1), (coral, 1)])).unwrap();
+        let b = Distil::from_image(&gen::blocks(60, 20, &[(coral, 1), (gold, 1)])).unwrap();
+        let merged = Distil::merge(&[a.clone(), b.clone()]);
+
+        for expected in [teal, coral, gold] {
+            assert_eq!(merged.colors.iter().filter(|&&color| delta_e(color, expected) < 5.0).count(), 1);
+        }
+
+        assert_eq!(merged.counts.iter().sum::<usize>(), a.counts.iter().sum::<usize>() + b.counts.iter().sum::<usize>());
+        assert!((merged.weights.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!(Distil::merge(&[]).colors.is_empty());
+    }
+}
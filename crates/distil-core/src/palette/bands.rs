@@ -0,0 +1,124 @@
+use lab::Lab;
+
+use crate::{Color, Distil};
+
+/// The colors of a palette that fall within a range of lightness, as
+/// returned by `Distil::by_lightness_bands`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightnessBand {
+    /// The lowest L* value of the band, inclusive.
+    pub min: f32,
+
+    /// The highest L* value of the band, exclusive except for the last band.
+    pub max: f32,
+
+    /// The colors of the band, organised from most-frequent to
+    /// least-frequent.
+    pub colors: Vec<Color>,
+
+    /// The share of the whole palette, between `0.0` and `1.0`, each color
+    /// in `colors` covers, at the same index.
+    pub weights: Vec<f32>,
+
+    /// The share of the whole palette, between `0.0` and `1.0`, the band
+    /// covers.
+    pub weight: f32,
+}
+
+impl Distil {
+    /// Groups the colors of the palette into `bands` equally wide bands of
+    /// lightness, from darkest to lightest, e.g. shadows, midtones and
+    /// highlights when `bands` is `3`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let bands = distilled.by_lightness_bands(3);
+    ///     let (shadows, midtones, highlights) = (&bands[0], &bands[1], &bands[2]);
+    /// }
+    /// ```
+    pub fn by_lightness_bands(&self, bands: usize) -> Vec<LightnessBand> {
+        let bands = bands.max(1);
+        let width = 100.0 / bands as f32;
+
+        let mut grouped: Vec<LightnessBand> = (0..bands)
+            .map(|i| {
+                LightnessBand {
+                    min: i as f32 * width,
+                    max: (i + 1) as f32 * width,
+                    colors: Vec::new(),
+                    weights: Vec::new(),
+                    weight: 0.0,
+                }
+            })
+            .collect();
+
+        for ((lab, &color), weight) in self.lab_colors.iter().zip(&self.colors).zip(self.shares()) {
+            let band = &mut grouped[((lab.l / width).max(0.0) as usize).min(bands - 1)];
+
+            band.colors.push(color);
+            band.weights.push(weight);
+            band.weight += weight;
+        }
+
+        grouped
+    }
+
+    /// Returns the `count` most frequent colors of the palette sorted from
+    /// darkest to lightest, the stops of a gradient map or a duotone when
+    /// `count` is `2`. Pixels are mapped by their lightness along the stops,
+    /// evenly spaced, with `Grade::gradient_map`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let duotone = distilled.gradient_stops(2);
+    /// }
+    /// ```
+    pub fn gradient_stops(&self, count: usize) -> Vec<Lab> {
+        let mut stops: Vec<Lab> = self.lab_colors.iter().take(count).copied().collect();
+
+        stops.sort_by(|x, y| x.l.total_cmp(&y.l));
+        stops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lab::Lab;
+
+    use crate::palette::distil_palette;
+
+    #[test]
+    fn bands() {
+        let palette = distil_palette(vec![(Lab { l: 50.0, a: 10.0, b: 10.0 }, 2),
+                                          (Lab { l: 10.0, a: 0.0, b: 0.0 }, 1),
+                                          (Lab { l: 90.0, a: 0.0, b: -10.0 }, 1)]);
+
+        let bands = palette.by_lightness_bands(3);
+
+        assert_eq!(bands.iter().map(|band| band.colors.len()).collect::<Vec<_>>(), vec![1, 1, 1]);
+        assert_eq!(bands.iter().map(|band| band.weight).collect::<Vec<_>>(), vec![0.25, 0.5, 0.25]);
+        assert_eq!(bands[1].colors[0], palette.colors[0]);
+        assert_eq!(palette.by_lightness_bands(1)[0].colors.len(), 3);
+    }
+
+    #[test]
+    fn gradient_stops() {
+        let palette = distil_palette(vec![(Lab { l: 50.0, a: 10.0, b: 10.0 }, 2),
+                                          (Lab { l: 10.0, a: 0.0, b: 0.0 }, 1),
+                                          (Lab { l: 90.0, a: 0.0, b: -10.0 }, 1)]);
+
+        let lightness = |stops: Vec<Lab>| stops.iter().map(|stop| stop.l).collect::<Vec<_>>();
+
+        assert_eq!(lightness(palette.gradient_stops(2)), vec![10.0, 50.0]);
+        assert_eq!(lightness(palette.gradient_stops(5)), vec![10.0, 50.0, 90.0]);
+        assert!(palette.gradient_stops(0).is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use std::cmp::Ordering;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use crate::{Color, Distil, IntoLab};
+
+/// The result of checking a palette against an approved set of brand colors
+/// with `Distil::check_against`.
+#[derive(Debug, Clone)]
+pub struct ComplianceReport {
+    /// The colors of the palette covering at least the minimum share of the
+    /// image that aren't within tolerance of any brand color, organised
+    /// from most-frequent to least-frequent.
+    pub violations: Vec<Violation>,
+
+    /// The share of the image, between `0.0` and `1.0`, covered by colors
+    /// that are within tolerance of a brand color.
+    pub compliant_coverage: f32,
+}
+
+impl ComplianceReport {
+    /// Returns `true` if every significant color of the palette is within
+    /// tolerance of a brand color.
+    pub fn is_compliant(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// A palette color which isn't within tolerance of any brand color.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// The offending palette color.
+    pub color: Color,
+
+    /// The share of the image, between `0.0` and `1.0`, that the color
+    /// covers, as in `Distil::weights`.
+    pub coverage: f32,
+
+    /// The brand color closest to `color`, or `None` if no brand colors were
+    /// passed.
+    pub nearest: Option<Color>,
+
+    /// The CIEDE2000 distance between `color` and `nearest`, or infinity if
+    /// there's no `nearest`.
+    pub distance: f32,
+}
+
+impl Distil {
+    /// Checks that every significant color of the palette is within
+    /// `tolerance` (a CIEDE2000 distance) of one of the approved `brand`
+    /// colors, and lists the ones that aren't along with how much of the
+    /// image they cover.
+    ///
+    /// Colors covering less than `min_coverage` of the image, between `0.0`
+    /// and `1.0`, e.g. the anti-aliased edges of a logo, aren't counted as
+    /// violations.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{Color, Distil};
+    ///
+    /// let brand: Vec<Color> = vec!["#1d3557".parse().unwrap(), "#e63946".parse().unwrap()];
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let report = distilled.check_against(&brand, 10.0, 0.01);
+    ///
+    ///     for violation in report.violations {
+    ///         println!("{} covers {:.1}%", violation.color, violation.coverage * 100.0);
+    ///     }
+    /// }
+    /// ```
+    pub fn check_against(&self, brand: &[Color], tolerance: f32, min_coverage: f32) -> ComplianceReport {
+        let brand_labs: Vec<Lab> = brand.iter().map(|&color| color.into_lab()).collect();
+
+        let mut violations = Vec::new();
+        let mut compliant_coverage = 0.0;
+
+        for (i, &lab) in self.lab_colors.iter().enumerate() {
+            let coverage = self.weights.get(i).copied().unwrap_or(0.0);
+
+            match nearest_color(lab, &brand_labs) {
+                Some((_, distance)) if distance <= tolerance => {
+                    compliant_coverage += coverage;
+                }
+                _ if coverage < min_coverage => {}
+                nearest => {
+                    violations.push(Violation {
+                        color: self.colors[i],
+                        coverage,
+                        nearest: nearest.map(|(j, _)| brand[j]),
+                        distance: nearest.map_or(f32::INFINITY, |(_, distance)| distance),
+                    });
+                }
+            }
+        }
+
+        ComplianceReport {
+            violations,
+            compliant_coverage,
+        }
+    }
+}
+
+/// Returns the index of the color in `palette` closest to `lab` along with
+/// its CIEDE2000 distance, or `None` if `palette` is empty.
+pub(crate) fn nearest_color(lab: Lab, palette: &[Lab]) -> Option<(usize, f32)> {
+    palette.iter()
+        .map(|&other| DE2000::new(lab, other))
+        .enumerate()
+        .min_by(|&(_, a), &(_, b)| a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{Color, Distil, IntoLab};
+
+    #[test]
+    fn check_against() {
+        let distilled = Distil::from_path(Path::new("./images/img-1.jpg")).unwrap();
+        let report = distilled.check_against(&distilled.colors, 1.0, 0.0);
+        assert!(report.is_compliant());
+        assert!((report.compliant_coverage - 1.0).abs() < 0.001);
+
+        let report = distilled.check_against(&distilled.colors[..1], 1.0, 0.0);
+        assert_eq!(report.violations.len(), distilled.colors.len() - 1);
+        assert!(report.violations.iter().all(|violation| violation.nearest == Some(distilled.colors[0])));
+        assert!(report.violations.iter().zip(&distilled.weights[1..]).all(|(violation, &weight)| violation.coverage == weight));
+    }
+
+    #[test]
+    fn min_coverage() {
+        let teal = Color::new(0, 128, 128);
+        let colors = [teal, Color::new(255, 127, 80)];
+        let distilled = Distil {
+            colors: colors.to_vec(),
+            counts: vec![1, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.98, 0.02],
+        };
+
+        let strict = distilled.check_against(&[teal], 1.0, 0.0);
+        assert_eq!(strict.violations.len(), 1);
+        assert_eq!(strict.violations[0].coverage, 0.02);
+        assert_eq!(strict.compliant_coverage, 0.98);
+
+        let lenient = distilled.check_against(&[teal], 1.0, 0.05);
+        assert!(lenient.is_compliant());
+        assert_eq!(lenient.compliant_coverage, 0.98);
+    }
+}
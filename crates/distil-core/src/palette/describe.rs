@@ -0,0 +1,193 @@
+use lab::Lab;
+
+use crate::{Color, Distil};
+
+/// Broad hue families, by the upper bound of their HSL hue in degrees. Red
+/// wraps around from 345° to 15°.
+static HUE_FAMILIES: &[(&str, f32)] = &[
+    ("red", 15.0),
+    ("orange", 45.0),
+    ("yellow", 70.0),
+    ("green", 160.0),
+    ("teal", 195.0),
+    ("blue", 255.0),
+    ("purple", 290.0),
+    ("pink", 345.0),
+    ("red", 360.0),
+];
+
+/// Colors with a Lab chroma below this are described as greys.
+static MAX_GREY_CHROMA: f32 = 8.0;
+
+/// How much more chromatic than the dominant color a color has to be to be
+/// described as an accent.
+static MIN_ACCENT_CHROMA_GAIN: f32 = 15.0;
+
+/// Colors covering less of the palette than this aren't mentioned.
+static MIN_MENTIONED_SHARE: f32 = 0.02;
+
+impl Distil {
+    /// Summarises the palette in a short, deterministic, natural-language
+    /// phrase such as "predominantly deep teal with warm orange accents",
+    /// for use in image descriptions and alt text.
+    ///
+    /// Colors are classified by their hue, lightness and chroma; the same
+    /// palette always produces the same phrase.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let alt = format!("A photo that's {}.", distilled.describe());
+    /// }
+    /// ```
+    pub fn describe(&self) -> String {
+        let entries: Vec<(Lab, f32)> = self.lab_colors
+            .iter()
+            .copied()
+            .zip(self.shares())
+            .filter(|&(_, share)| share >= MIN_MENTIONED_SHARE)
+            .collect();
+
+        let (primary, primary_share) = match entries.first() {
+            Some(&entry) => entry,
+            None => return String::from("colorless"),
+        };
+
+        let primary_name = describe_color(primary);
+
+        let mut accents: Vec<String> = Vec::new();
+
+        for &(lab, _) in &entries[1..] {
+            let name = accent_name(lab);
+
+            if chroma(lab) >= chroma(primary) + MIN_ACCENT_CHROMA_GAIN && !accents.contains(&name) &&
+               accents.len() < 2 {
+                accents.push(name);
+            }
+        }
+
+        let mut others: Vec<String> = Vec::new();
+
+        for &(lab, _) in &entries[1..] {
+            let name = describe_color(lab);
+
+            if name != primary_name && !others.contains(&name) && others.len() < 2 {
+                others.push(name);
+            }
+        }
+
+        if primary_share < 0.3 && accents.is_empty() && !others.is_empty() {
+            let mut names = vec![primary_name];
+            names.extend(others);
+            return format!("a mix of {}", join_names(&names));
+        }
+
+        let lead = if primary_share >= 0.5 { "predominantly" } else { "mostly" };
+
+        if !accents.is_empty() {
+            return format!("{} {} with {} accents", lead, primary_name, join_names(&accents));
+        }
+
+        match others.first() {
+            Some(other) => format!("{} {} and {}", lead, primary_name, other),
+            None => format!("{} {}", lead, primary_name),
+        }
+    }
+}
+
+/// Names a color by its lightness, chroma and hue, e.g. "deep teal" or
+/// "pale grey".
+fn describe_color(lab: Lab) -> String {
+    let hue = hue_family(lab);
+
+    if chroma(lab) < MAX_GREY_CHROMA {
+        return match lab.l {
+            l if l < 20.0 => String::from("black"),
+            l if l > 92.0 => String::from("white"),
+            l if l < 40.0 => String::from("dark grey"),
+            l if l > 75.0 => String::from("light grey"),
+            _ => String::from("grey"),
+        };
+    }
+
+    if lab.l < 45.0 && (hue == "orange" || hue == "red" || hue == "yellow") && chroma(lab) < 50.0 {
+        return String::from(if lab.l < 25.0 { "dark brown" } else { "brown" });
+    }
+
+    let lightness = match lab.l {
+        l if l < 25.0 => Some("very dark"),
+        l if l < 45.0 => Some("deep"),
+        l if l > 85.0 => Some("pale"),
+        l if l > 70.0 => Some("light"),
+        _ => None,
+    };
+
+    let saturation = match chroma(lab) {
+        c if c < 20.0 => Some("muted"),
+        c if c > 70.0 => Some("vivid"),
+        _ => None,
+    };
+
+    let mut words: Vec<&str> = Vec::new();
+    words.extend(lightness);
+    words.extend(saturation);
+    words.push(hue);
+    words.join(" ")
+}
+
+/// Names an accent color by its temperature and hue, e.g. "warm orange".
+fn accent_name(lab: Lab) -> String {
+    let hue = hue_family(lab);
+
+    let temperature = match hue {
+        "red" | "orange" | "yellow" | "pink" => "warm",
+        _ => "cool",
+    };
+
+    format!("{} {}", temperature, hue)
+}
+
+fn hue_family(lab: Lab) -> &'static str {
+    let (hue, _, _) = Color::from(lab).hsl();
+
+    HUE_FAMILIES.iter()
+        .find(|&&(_, max_hue)| hue < max_hue)
+        .map_or("red", |&(name, _)| name)
+}
+
+fn chroma(lab: Lab) -> f32 {
+    (lab.a * lab.a + lab.b * lab.b).sqrt()
+}
+
+fn join_names(names: &[String]) -> String {
+    match names.len() {
+        0 => String::new(),
+        1 => names[0].clone(),
+        n => format!("{} and {}", names[..n - 1].join(", "), names[n - 1]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::palette::distil_palette;
+    use crate::{Color, IntoLab};
+
+    #[test]
+    fn describe() {
+        let teal = Color::new(0, 90, 90).into_lab();
+        let orange = Color::new(240, 130, 20).into_lab();
+        let grey = Color::new(120, 120, 120).into_lab();
+
+        let palette = distil_palette(vec![(teal, 6), (orange, 2), (grey, 2)]);
+        assert_eq!(palette.describe(), "predominantly deep teal with warm orange accents");
+
+        let palette = distil_palette(vec![(grey, 4), (teal, 3), (orange, 3)]);
+        assert_eq!(palette.describe(), "mostly grey with cool teal and warm orange accents");
+
+        let palette = distil_palette(vec![(grey, 1)]);
+        assert_eq!(palette.describe(), "predominantly grey");
+    }
+}
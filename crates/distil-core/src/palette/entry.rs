@@ -0,0 +1,92 @@
+use lab::Lab;
+
+use crate::{Color, Distil};
+
+/// A color of a palette along with how much of the image it accounts for,
+/// as returned by `Distil::entries`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteEntry {
+    /// The color, as in `Distil::colors`.
+    pub color: Color,
+
+    /// The Lab value of the color before it was rounded into RGB, as in
+    /// `Distil::lab_colors`.
+    pub lab: Lab,
+
+    /// The number of colors of the quantizer's palette distilled into the
+    /// color, as in `Distil::counts`.
+    pub count: usize,
+
+    /// `count` as a share of the whole palette, between `0.0` and `1.0`, as
+    /// returned by `Distil::shares`.
+    pub share: f32,
+
+    /// The share of the sampled pixels, between `0.0` and `1.0`, closest to
+    /// the color, as in `Distil::weights`.
+    pub weight: f32,
+
+    /// The spread in Lab units of the colors merged into the color, as in
+    /// `Distil::spreads`.
+    pub spread: f32,
+}
+
+impl Distil {
+    /// Returns each color of the palette with its count, share, weight and
+    /// spread, from most to least frequent, so that they don't have to be
+    /// zipped together from the parallel vectors of `Distil` by index.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     for entry in distilled.entries() {
+    ///         println!("{} covers {:.0}% of the image", entry.color, entry.weight * 100.0);
+    ///     }
+    /// }
+    /// ```
+    pub fn entries(&self) -> Vec<PaletteEntry> {
+        let shares = self.shares();
+
+        self.colors
+            .iter()
+            .enumerate()
+            .map(|(i, &color)| {
+                PaletteEntry {
+                    color,
+                    lab: self.lab_colors.get(i).copied().unwrap_or_else(|| color.lab()),
+                    count: self.counts.get(i).copied().unwrap_or(0),
+                    share: shares.get(i).copied().unwrap_or(0.0),
+                    weight: self.weights.get(i).copied().unwrap_or(0.0),
+                    spread: self.spreads.get(i).copied().unwrap_or(0.0),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Distil, IntoLab};
+
+    #[test]
+    fn entries() {
+        let colors = [Color::new(0, 128, 128), Color::new(255, 127, 80)];
+        let distilled = Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![1.5, 0.0],
+            weights: vec![0.6, 0.4],
+        };
+
+        let entries = distilled.entries();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].color, colors[0]);
+        assert_eq!(entries[0].lab, distilled.lab_colors[0]);
+        assert_eq!((entries[0].count, entries[0].share, entries[0].weight, entries[0].spread), (3, 0.75, 0.6, 1.5));
+        assert_eq!((entries[1].count, entries[1].share, entries[1].weight, entries[1].spread), (1, 0.25, 0.4, 0.0));
+    }
+}
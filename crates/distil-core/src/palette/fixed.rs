@@ -0,0 +1,72 @@
+use crate::{Color, Distil, DistilError};
+
+/// What `Distil::top_n` does when the palette has fewer colors than were
+/// asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// Cycles through the palette again from its most frequent color.
+    Repeat,
+
+    /// Fills the remaining slots with the given color.
+    Fill(Color),
+
+    /// Returns `DistilError::TooFewColors`.
+    Error,
+}
+
+impl Distil {
+    /// Returns the `N` most frequent colors of the palette as a fixed-size
+    /// array, for callers with a set number of color slots such as shader
+    /// uniforms or small displays. `padding` decides what happens when the
+    /// palette has fewer than `N` colors.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::palette::Padding;
+    /// use distil_core::{Color, Distil};
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let [primary, secondary, accent, background] =
+    ///         distilled.top_n::<4>(Padding::Fill(Color::new(0, 0, 0))).unwrap();
+    /// }
+    /// ```
+    pub fn top_n<const N: usize>(&self, padding: Padding) -> Result<[Color; N], DistilError> {
+        let found = self.colors.len();
+
+        if found < N && (padding == Padding::Error || (padding == Padding::Repeat && found == 0)) {
+            return Err(DistilError::TooFewColors(N, found));
+        }
+
+        Ok(std::array::from_fn(|i| {
+            match self.colors.get(i) {
+                Some(&color) => color,
+                None => {
+                    match padding {
+                        Padding::Fill(color) => color,
+                        _ => self.colors[i % found],
+                    }
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::palette::{distil_palette, Padding};
+    use crate::{Color, DistilError, IntoLab};
+
+    #[test]
+    fn top_n() {
+        let red = Color::new(200, 30, 30);
+        let blue = Color::new(30, 30, 200);
+        let distil = distil_palette(vec![(red.into_lab(), 3), (blue.into_lab(), 1)]);
+
+        assert_eq!(distil.top_n::<1>(Padding::Error).unwrap(), [red]);
+        assert_eq!(distil.top_n::<3>(Padding::Repeat).unwrap(), [red, blue, red]);
+        assert_eq!(distil.top_n::<3>(Padding::Fill(Color::new(0, 0, 0))).unwrap(),
+                   [red, blue, Color::new(0, 0, 0)]);
+        assert!(matches!(distil.top_n::<3>(Padding::Error), Err(DistilError::TooFewColors(3, 2))));
+    }
+}
@@ -0,0 +1,352 @@
+//! Distilled palettes and what can be done with them.
+
+use lab::Lab;
+
+use crate::extract::{GreedyMerge, MergeStrategy};
+use crate::{lab_to_linear_rgb, Color};
+
+pub use self::bands::LightnessBand;
+pub use self::compliance::{ComplianceReport, Violation};
+pub use self::entry::PaletteEntry;
+pub use self::fixed::Padding;
+pub use self::names::NamedColor;
+pub use self::swatches::Swatches;
+pub use self::theme::Theme;
+
+#[cfg(feature = "render")]
+pub(crate) use self::compliance::nearest_color;
+
+mod arithmetic;
+mod bands;
+mod compliance;
+mod describe;
+mod entry;
+mod fixed;
+mod names;
+mod nearest;
+mod swatches;
+mod theme;
+
+/// Represents a distilled image.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Distil {
+    /// `colors` contains all of the colors the image was distilled down
+    /// into organised from the one covering the most of the image to the
+    /// least, as measured by `weights`.
+    pub colors: Vec<Color>,
+
+    /// `counts` contains, at the same index as `colors`, the number of
+    /// entries of the quantizer's palette that were distilled down into each
+    /// color. `shares` turns them into fractions of the palette.
+    ///
+    /// It's what weights a color's importance when distilling multiple
+    /// palettes into one with `merge`.
+    pub counts: Vec<usize>,
+
+    /// `lab_colors` contains the Lab value of each color in `colors`, at the
+    /// same index, as it was before being rounded into RGB.
+    ///
+    /// Passing these to the color helpers rather than `colors` avoids
+    /// converting back and forth between RGB and Lab.
+    #[cfg_attr(feature = "serde", serde(with = "crate::interop::lab_colors"))]
+    pub lab_colors: Vec<Lab>,
+
+    /// `spreads` contains, at the same index as `colors`, the standard
+    /// deviation in Lab units of the colors that were merged into each color.
+    ///
+    /// A low spread means the image really has that one shade, while a high
+    /// one means the color is the average of a wide range of shades.
+    pub spreads: Vec<f32>,
+
+    /// `weights` contains, at the same index as `colors`, the share of the
+    /// sampled pixels, between `0.0` and `1.0`, that are closest to each
+    /// color. They add up to `1.0`.
+    ///
+    /// Unlike `counts`, which counts entries of the quantizer's palette,
+    /// they reflect how much of the image each color actually covers, e.g.
+    /// for drawing proportional swatches.
+    pub weights: Vec<f32>,
+}
+
+impl Distil {
+    /// Returns the linear-light RGB value of each color in `colors`, at the
+    /// same index, with channels between `0.0` and `1.0`. They're computed
+    /// from `lab_colors` rather than by undoing the gamma of the rounded
+    /// sRGB values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let [r, g, b] = distilled.linear_colors()[0];
+    /// }
+    /// ```
+    pub fn linear_colors(&self) -> Vec<[f32; 3]> {
+        self.lab_colors.iter().map(|&lab| lab_to_linear_rgb(lab)).collect()
+    }
+
+    /// Returns the share of the palette, between `0.0` and `1.0`, each color
+    /// in `colors` covers, at the same index.
+    /// For the share of the image each color covers use `weights` instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     for (color, share) in distilled.colors.iter().zip(distilled.shares()) {
+    ///         println!("{} {:.0}%", color, share * 100.0);
+    ///     }
+    /// }
+    /// ```
+    pub fn shares(&self) -> Vec<f32> {
+        let total = self.counts.iter().sum::<usize>().max(1) as f32;
+        self.counts.iter().map(|&count| count as f32 / total).collect()
+    }
+
+    /// Returns the Shannon entropy, in bits, of how the image is distributed
+    /// across the colors of the palette, as weighted by `counts`.
+    ///
+    /// It's `0.0` for a palette of a single color and `log2(n)` for `n`
+    /// equally common colors, so a low entropy suggests a flat graphic and a
+    /// high one a photo.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let is_photo = distilled.entropy() > 2.5;
+    /// }
+    /// ```
+    pub fn entropy(&self) -> f32 {
+        let total = self.counts.iter().sum::<usize>() as f32;
+
+        self.counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f32 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Returns the palette as a JSON array of its colors, as hex strings,
+    /// and their `weights`, ready to be stored or sent to a web frontend.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let json = distilled.to_json();
+    /// }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let colors: Vec<String> = self.colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| format!(r#"{{"hex":"{}","weight":{:.4}}}"#, color, self.weights.get(i).unwrap_or(&0.0)))
+            .collect();
+
+        format!("[{}]", colors.join(","))
+    }
+}
+
+/// Averages two colors in Lab space, weighting each by its count, and returns
+/// the averaged color along with the combined count.
+pub(crate) fn balance_colors((lab_x, count_x): (Lab, usize), (lab_y, count_y): (Lab, usize)) -> (Lab, usize) {
+    let (lx, ax, bx) = (lab_x.l, lab_x.a, lab_x.b);
+    let (ly, ay, by) = (lab_y.l, lab_y.a, lab_y.b);
+
+    let total = (count_x + count_y) as f32;
+    let (weight_x, weight_y) = (count_x as f32, count_y as f32);
+
+    let balanced_l = (lx * weight_x + ly * weight_y) / total;
+    let balanced_a = (ax * weight_x + ay * weight_y) / total;
+    let balanced_b = (bx * weight_x + by * weight_y) / total;
+
+    let balanced_lab = Lab {
+        l: balanced_l,
+        a: balanced_a,
+        b: balanced_b,
+    };
+
+    (balanced_lab, count_x + count_y)
+}
+
+/// The inverse of `distil_palette`, turning a `Distil` back into the Lab
+/// palette the pipeline works with.
+pub(crate) fn lab_palette(distil: &Distil) -> Vec<(Lab, usize)> {
+    distil.lab_colors.iter().zip(&distil.counts).map(|(&lab, &count)| (lab, count)).collect()
+}
+
+/// Organises the produced color palette into something that's useful for a
+/// user.
+pub(crate) fn distil_palette(palette: Vec<(Lab, usize)>) -> Distil {
+    let mut colors = Vec::new();
+    let mut counts = Vec::new();
+    let mut lab_colors = Vec::new();
+
+    for &(lab_color, count) in palette.iter() {
+        colors.push(Color::from(lab_color));
+        counts.push(count);
+        lab_colors.push(lab_color);
+    }
+
+    let mut distil = Distil {
+        spreads: vec![0.0; colors.len()],
+        weights: Vec::new(),
+        colors,
+        counts,
+        lab_colors,
+    };
+
+    distil.weights = distil.shares();
+    distil
+}
+
+/// Like `lab_palette`, but with the weight and spread of each color, ready to
+/// be passed to `with_members` as members.
+pub(crate) fn member_palette(distil: &Distil) -> Vec<(Lab, f32, f32)> {
+    distil.lab_colors
+        .iter()
+        .enumerate()
+        .map(|(i, &lab)| {
+            let weight = *distil.weights.get(i).unwrap_or(&0.0);
+            let spread = *distil.spreads.get(i).unwrap_or(&0.0);
+            (lab, weight, spread)
+        })
+        .collect()
+}
+
+/// Sets the weights and spreads of `distil` from the `members` (color, weight
+/// and spread) that were merged into its colors, counting each member towards
+/// the color it's closest to.
+pub(crate) fn with_members(distil: Distil, members: &[(Lab, f32, f32)]) -> Distil {
+    by_weight(with_members_by(distil, members, &GreedyMerge), 0)
+}
+
+/// Like `with_members`, counting each member towards the colors of `distil`
+/// by its memberships under `strategy`.
+pub(crate) fn with_members_by(mut distil: Distil, members: &[(Lab, f32, f32)], strategy: &dyn MergeStrategy) -> Distil {
+    let mut sums = vec![(0.0f32, 0.0f32); distil.lab_colors.len()];
+
+    for &(lab, weight, spread) in members {
+        let distances: Vec<f32> = distil.lab_colors.iter().map(|&color| squared_distance(lab, color)).collect();
+
+        for ((sum, distance), membership) in sums.iter_mut().zip(&distances).zip(strategy.memberships(&distances)) {
+            sum.0 += membership * weight * (spread * spread + distance);
+            sum.1 += membership * weight;
+        }
+    }
+
+    distil.spreads = sums.iter()
+        .map(|&(sum, weight)| if weight > 0.0 { (sum / weight).sqrt() } else { 0.0 })
+        .collect();
+
+    let total: f32 = sums.iter().map(|&(_, weight)| weight).sum();
+
+    if total > 0.0 {
+        distil.weights = sums.iter().map(|&(_, weight)| weight / total).collect();
+    }
+
+    distil
+}
+
+/// Orders the colors of `distil` after the first `pinned` ones, which are
+/// left in place, from the one covering the most of the image to the least,
+/// and drops those that cover none of it, such as entries a quantizer never
+/// trained on a flat image.
+pub(crate) fn by_weight(distil: Distil, pinned: usize) -> Distil {
+    let pinned = pinned.min(distil.colors.len());
+    let weight = |i: usize| *distil.weights.get(i).unwrap_or(&0.0);
+
+    let mut rest: Vec<usize> = (pinned..distil.colors.len()).filter(|&i| weight(i) > 0.0).collect();
+
+    // Without any weights to go by, e.g. with no members, the palette is
+    // left as it is rather than emptied.
+    if rest.is_empty() {
+        return distil;
+    }
+
+    rest.sort_by(|&x, &y| weight(y).total_cmp(&weight(x)));
+
+    let order: Vec<usize> = (0..pinned).chain(rest).collect();
+
+    Distil {
+        colors: order.iter().map(|&i| distil.colors[i]).collect(),
+        counts: order.iter().map(|&i| distil.counts[i]).collect(),
+        lab_colors: order.iter().map(|&i| distil.lab_colors[i]).collect(),
+        spreads: order.iter().map(|&i| distil.spreads[i]).collect(),
+        weights: order.iter().map(|&i| weight(i)).collect(),
+    }
+}
+
+fn squared_distance(x: Lab, y: Lab) -> f32 {
+    (x.l - y.l).powi(2) + (x.a - y.a).powi(2) + (x.b - y.b).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use lab::Lab;
+
+    use super::distil_palette;
+    use crate::{delta_e, gen, Color, Distil};
+
+    #[test]
+    fn entropy() {
+        let lab = |l| Lab { l, a: 0.0, b: 0.0 };
+
+        assert_eq!(distil_palette(vec![(lab(50.0), 7)]).entropy(), 0.0);
+        assert_eq!(distil_palette(vec![(lab(20.0), 3), (lab(80.0), 3)]).entropy(), 1.0);
+        assert_eq!(distil_palette(Vec::new()).entropy(), 0.0);
+    }
+
+    #[test]
+    fn weights() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(240, 110, 90);
+        let distilled = Distil::from_image(&gen::blocks(120, 40, &[(teal, 3), (coral, 1)])).unwrap();
+
+        let weight = |expected| {
+            distilled.colors.iter()
+                .zip(&distilled.weights)
+                .filter(|&(&color, _)| delta_e(color, expected) < 5.0)
+                .map(|(_, &weight)| weight)
+                .sum::<f32>()
+        };
+
+        assert!((distilled.weights.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!((weight(teal) - 0.75).abs() < 0.02);
+        assert!((weight(coral) - 0.25).abs() < 0.02);
+    }
+
+    #[test]
+    fn by_weight() {
+        let teal = Color::new(0, 128, 128);
+        let distilled = Distil::from_image(&gen::solid(40, 40, teal)).unwrap();
+
+        assert!(delta_e(distilled.colors[0], teal) < 2.0);
+        assert!(distilled.weights.iter().all(|&weight| weight > 0.0));
+        assert!(distilled.weights.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert_eq!(distilled.counts.len(), distilled.colors.len());
+    }
+
+    #[test]
+    fn to_json() {
+        let teal = Color::new(0, 128, 128).lab();
+        let coral = Color::new(240, 110, 90).lab();
+
+        assert_eq!(distil_palette(vec![(teal, 3), (coral, 1)]).to_json(),
+                   r##"[{"hex":"#008080","weight":0.7500},{"hex":"#f06e5a","weight":0.2500}]"##);
+        assert_eq!(distil_palette(Vec::new()).to_json(), "[]");
+    }
+}
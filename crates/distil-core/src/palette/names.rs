@@ -0,0 +1,404 @@
+use std::cmp::Ordering;
+
+use delta_e::DE2000;
+use lab::Lab;
+
+use crate::{Color, Distil, IntoLab};
+
+/// Everyday color names used for human-friendly descriptions, e.g. in alt
+/// text and chat-bot summaries.
+static DESCRIPTIVE_NAMES: &[(&str, [u8; 3])] = &[
+    ("black", [20, 20, 20]),
+    ("charcoal", [54, 69, 79]),
+    ("slate", [112, 128, 144]),
+    ("grey", [128, 128, 128]),
+    ("silver", [192, 192, 192]),
+    ("white", [245, 245, 245]),
+    ("cream", [255, 253, 208]),
+    ("beige", [225, 198, 153]),
+    ("tan", [210, 180, 140]),
+    ("brown", [123, 63, 0]),
+    ("chocolate", [80, 45, 22]),
+    ("rust", [183, 65, 14]),
+    ("maroon", [128, 0, 0]),
+    ("burgundy", [128, 0, 32]),
+    ("crimson", [220, 20, 60]),
+    ("red", [210, 35, 42]),
+    ("coral", [255, 127, 80]),
+    ("salmon", [250, 128, 114]),
+    ("pink", [255, 182, 193]),
+    ("hot pink", [255, 105, 180]),
+    ("magenta", [255, 0, 255]),
+    ("plum", [142, 69, 133]),
+    ("purple", [128, 0, 128]),
+    ("lavender", [181, 126, 220]),
+    ("violet", [127, 0, 255]),
+    ("indigo", [75, 0, 130]),
+    ("navy", [0, 0, 128]),
+    ("blue", [0, 87, 183]),
+    ("royal blue", [65, 105, 225]),
+    ("sky blue", [135, 206, 235]),
+    ("cyan", [0, 255, 255]),
+    ("turquoise", [64, 224, 208]),
+    ("teal", [0, 128, 128]),
+    ("deep teal", [0, 85, 85]),
+    ("mint", [152, 255, 152]),
+    ("green", [0, 150, 60]),
+    ("forest green", [34, 89, 34]),
+    ("olive", [128, 128, 0]),
+    ("lime", [191, 255, 0]),
+    ("khaki", [195, 176, 145]),
+    ("mustard", [225, 173, 1]),
+    ("gold", [255, 200, 0]),
+    ("yellow", [255, 235, 60]),
+    ("amber", [255, 191, 0]),
+    ("tangerine", [242, 133, 0]),
+    ("orange", [255, 120, 0]),
+    ("peach", [255, 203, 164]),
+];
+
+/// The named colors of CSS, which are those of X11. Aliases are left out:
+/// `aqua` is `cyan`, `fuchsia` is `magenta` and each `grey` is a `gray`.
+static CSS_NAMES: &[(&str, [u8; 3])] = &[
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
+
+/// The hue ranges, in degrees, matched to each colored square emoji. Red
+/// wraps around from 345° to 15°.
+static EMOJI_HUES: &[(&str, f32)] = &[
+    ("🟧", 45.0),
+    ("🟨", 70.0),
+    ("🟩", 170.0),
+    ("🟦", 255.0),
+    ("🟪", 345.0),
+];
+
+/// The named color closest to a color, as returned by `Color::closest_named`
+/// and `Color::closest_named_in`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NamedColor<'a> {
+    /// The name, e.g. `"teal"`.
+    pub name: &'a str,
+
+    /// The color the name stands for.
+    pub color: Color,
+
+    /// The CIEDE2000 distance between the color that was looked up and
+    /// `color`, to leave out names that are too far off.
+    pub distance: f32,
+}
+
+impl Color {
+    /// Returns the CSS named color closest to the color by CIEDE2000, e.g.
+    /// `"teal"` or `"coral"`, for tags and search indexing.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Color;
+    ///
+    /// let named = Color::new(0, 130, 126).closest_named();
+    ///
+    /// assert_eq!(named.name, "teal");
+    /// assert!(named.distance < 2.0);
+    /// ```
+    pub fn closest_named(&self) -> NamedColor<'static> {
+        let lab = self.into_lab();
+
+        CSS_NAMES.iter()
+            .map(|&(name, rgb)| NamedColor { name, color: Color::from(rgb), distance: DE2000::new(lab, Lab::from_rgb(&rgb)) })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .unwrap_or(NamedColor { name: "black", color: Color::new(0, 0, 0), distance: f32::INFINITY })
+    }
+
+    /// Like `closest_named`, but looks the color up in `names`, e.g. a larger
+    /// name database or a brand's own color names. Returns `None` if `names`
+    /// is empty.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Color;
+    ///
+    /// let names = [("Pantone 2144 C", Color::new(0, 101, 189)), ("Pantone 7621 C", Color::new(177, 40, 47))];
+    /// let named = Color::new(10, 100, 180).closest_named_in(&names).unwrap();
+    ///
+    /// assert_eq!(named.name, "Pantone 2144 C");
+    /// ```
+    pub fn closest_named_in<'a>(&self, names: &'a [(&'a str, Color)]) -> Option<NamedColor<'a>> {
+        let lab = self.into_lab();
+
+        names.iter()
+            .map(|&(name, color)| NamedColor { name, color, distance: DE2000::new(lab, color.into_lab()) })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Returns the everyday name closest to the color, e.g. `"navy"` or
+    /// `"tangerine"`.
+    pub fn descriptive_name(&self) -> &'static str {
+        nearest_name(self.into_lab(), DESCRIPTIVE_NAMES)
+    }
+
+    /// Returns the colored square emoji closest to the color, e.g. `"🟦"`.
+    ///
+    /// Each emoji only comes in one shade, so chromatic colors are matched on
+    /// their hue alone — a navy is 🟦, not ⬛ — dark oranges are 🟫 and
+    /// greys are ⬛ or ⬜ depending on their lightness.
+    pub fn emoji(&self) -> &'static str {
+        let (hue, saturation, lightness) = self.hsl();
+
+        if saturation < 0.15 || !(0.08..=0.95).contains(&lightness) {
+            return if lightness < 0.5 { "⬛" } else { "⬜" };
+        }
+
+        if (15.0..45.0).contains(&hue) && lightness < 0.35 {
+            return "🟫";
+        }
+
+        if !(15.0..345.0).contains(&hue) {
+            return "🟥";
+        }
+
+        EMOJI_HUES.iter()
+            .find(|&&(_, max_hue)| hue < max_hue)
+            .map_or("🟥", |&(emoji, _)| emoji)
+    }
+}
+
+impl Distil {
+    /// Describes the `count` most frequent colors of the palette with an
+    /// emoji and an everyday name each, e.g. `"🟦 navy, 🟧 tangerine"`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     println!("{}", distilled.emoji_description(3));
+    /// }
+    /// ```
+    pub fn emoji_description(&self, count: usize) -> String {
+        let descriptors: Vec<String> = self.colors
+            .iter()
+            .take(count)
+            .map(|color| format!("{} {}", color.emoji(), color.descriptive_name()))
+            .collect();
+
+        descriptors.join(", ")
+    }
+}
+
+impl Distil {
+    /// Returns the CSS names of the `count` most frequent colors of the
+    /// palette, without repeats, e.g. `["teal", "coral", "darkslategray"]`
+    /// for search indexing.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let tags = distilled.named_tags(3).join(", ");
+    /// }
+    /// ```
+    pub fn named_tags(&self, count: usize) -> Vec<&'static str> {
+        let mut tags = Vec::new();
+
+        for color in self.colors.iter().take(count) {
+            let name = color.closest_named().name;
+
+            if !tags.contains(&name) {
+                tags.push(name);
+            }
+        }
+
+        tags
+    }
+}
+
+/// Returns the name of the entry of `table` closest to `lab` by CIEDE2000.
+pub(crate) fn nearest_name(lab: Lab, table: &'static [(&'static str, [u8; 3])]) -> &'static str {
+    table.iter()
+        .map(|&(name, rgb)| (name, DE2000::new(lab, Lab::from_rgb(&rgb))))
+        .min_by(|&(_, a), &(_, b)| a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+        .map_or("", |(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::palette::distil_palette;
+    use crate::{Color, IntoLab};
+
+    #[test]
+    fn names() {
+        assert_eq!(Color::new(0, 0, 120).descriptive_name(), "navy");
+        assert_eq!(Color::new(245, 135, 5).descriptive_name(), "tangerine");
+        assert_eq!(Color::new(0, 0, 120).emoji(), "🟦");
+        assert_eq!(Color::new(245, 135, 5).emoji(), "🟧");
+    }
+
+    #[test]
+    fn closest_named() {
+        assert_eq!(Color::new(0, 128, 128).closest_named().name, "teal");
+        assert_eq!(Color::new(0, 128, 128).closest_named().distance, 0.0);
+        assert_eq!(Color::new(250, 125, 85).closest_named().name, "coral");
+        assert_eq!(Color::new(0, 255, 255).closest_named().name, "cyan");
+        assert_eq!(Color::new(40, 40, 40).closest_named_in(&[]), None);
+
+        let navy = Color::new(0, 0, 128).into_lab();
+        let teal = Color::new(2, 126, 130).into_lab();
+        let palette = distil_palette(vec![(navy, 3), (teal, 2), (Color::new(0, 0, 130).into_lab(), 1)]);
+
+        assert_eq!(palette.named_tags(5), ["navy", "teal"]);
+    }
+
+    #[test]
+    fn emoji_description() {
+        let navy = Color::new(0, 0, 128).into_lab();
+        let tangerine = Color::new(242, 133, 0).into_lab();
+        let palette = distil_palette(vec![(navy, 3), (tangerine, 1)]);
+
+        assert_eq!(palette.emoji_description(5), "🟦 navy, 🟧 tangerine");
+        assert_eq!(palette.emoji_description(1), "🟦 navy");
+    }
+}
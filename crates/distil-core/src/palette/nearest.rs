@@ -0,0 +1,57 @@
+use lab::Lab;
+
+use super::compliance::nearest_color;
+use crate::Distil;
+
+impl Distil {
+    /// `nearest` returns the index of the color in `colors` closest to `rgb`
+    /// by CIEDE2000, along with its distance, e.g. to snap a color picked by
+    /// a user to the image's palette. Returns `None` if the palette is
+    /// empty.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let img = gen::blocks(40, 20, &[(Color::new(0, 128, 128), 3), (Color::new(255, 127, 80), 1)]);
+    /// let distilled = Distil::from_image(&img).unwrap();
+    ///
+    /// if let Some((i, distance)) = distilled.nearest([250, 120, 90]) {
+    ///     println!("{} is {:.1} away", distilled.colors[i].hex(), distance);
+    /// }
+    /// ```
+    pub fn nearest(&self, rgb: [u8; 3]) -> Option<(usize, f32)> {
+        nearest_color(Lab::from_rgb(&rgb), &self.lab_colors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Distil, IntoLab};
+
+    #[test]
+    fn nearest() {
+        let colors = [Color::new(0, 128, 128), Color::new(255, 127, 80)];
+        let distilled = Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        };
+
+        let (i, distance) = distilled.nearest([0, 128, 128]).unwrap();
+        assert_eq!(i, 0);
+        assert!(distance < 0.001);
+
+        let (i, distance) = distilled.nearest([250, 120, 90]).unwrap();
+        assert_eq!(i, 1);
+        assert!(distance > 0.0 && distance < 5.0);
+
+        assert_eq!(distilled.nearest([10, 90, 100]).map(|(i, _)| i), Some(0));
+
+        let empty = Distil { colors: vec![], counts: vec![], lab_colors: vec![], spreads: vec![], weights: vec![] };
+        assert_eq!(empty.nearest([0, 0, 0]), None);
+    }
+}
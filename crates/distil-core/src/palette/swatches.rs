@@ -0,0 +1,147 @@
+use crate::{Color, Distil};
+
+/// The weights of how close a color's saturation and lightness are to the
+/// target of a swatch, and of how much of the image it covers, as in
+/// Vibrant.js.
+static SATURATION_WEIGHT: f32 = 3.0;
+static LIGHTNESS_WEIGHT: f32 = 6.5;
+static POPULATION_WEIGHT: f32 = 0.5;
+
+/// The semantic swatches of a palette, as returned by `Distil::swatches`.
+/// Each is `None` when no color of the palette falls within its range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swatches {
+    /// A saturated color of medium lightness.
+    pub vibrant: Option<Color>,
+
+    /// A saturated dark color.
+    pub dark_vibrant: Option<Color>,
+
+    /// A saturated light color.
+    pub light_vibrant: Option<Color>,
+
+    /// A desaturated color of medium lightness.
+    pub muted: Option<Color>,
+
+    /// A desaturated dark color.
+    pub dark_muted: Option<Color>,
+
+    /// A desaturated light color.
+    pub light_muted: Option<Color>,
+}
+
+/// The range and ideal of the saturation and lightness of a swatch, all
+/// between `0.0` and `1.0`.
+struct Target {
+    saturation: (f32, f32, f32),
+    lightness: (f32, f32, f32),
+}
+
+static VIBRANT: Target = Target { saturation: (0.35, 1.0, 1.0), lightness: (0.3, 0.5, 0.7) };
+static LIGHT_VIBRANT: Target = Target { saturation: (0.35, 1.0, 1.0), lightness: (0.55, 0.74, 1.0) };
+static DARK_VIBRANT: Target = Target { saturation: (0.35, 1.0, 1.0), lightness: (0.0, 0.26, 0.45) };
+static MUTED: Target = Target { saturation: (0.0, 0.3, 0.4), lightness: (0.3, 0.5, 0.7) };
+static LIGHT_MUTED: Target = Target { saturation: (0.0, 0.3, 0.4), lightness: (0.55, 0.74, 1.0) };
+static DARK_MUTED: Target = Target { saturation: (0.0, 0.3, 0.4), lightness: (0.0, 0.26, 0.45) };
+
+impl Distil {
+    /// Classifies the colors of the palette into the swatches of Android's
+    /// Palette and Vibrant.js, by their HSL saturation and lightness: vibrant
+    /// and muted, each dark, medium and light. Each swatch gets the color
+    /// closest to its ideal saturation and lightness, favouring frequent
+    /// colors, and no color fills two swatches.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let swatches = distilled.swatches();
+    ///
+    ///     if let Some(vibrant) = swatches.vibrant {
+    ///         println!("Accent color: {}", vibrant);
+    ///     }
+    /// }
+    /// ```
+    pub fn swatches(&self) -> Swatches {
+        let max_weight = self.weights.iter().cloned().fold(0.0, f32::max);
+        let mut used = vec![false; self.colors.len()];
+
+        let mut pick = |target: &Target| {
+            let (min_s, target_s, max_s) = target.saturation;
+            let (min_l, target_l, max_l) = target.lightness;
+
+            let best = self.colors.iter()
+                .enumerate()
+                .filter(|&(i, _)| !used[i])
+                .filter_map(|(i, color)| {
+                    let (_, s, l) = color.hsl();
+
+                    if s < min_s || s > max_s || l < min_l || l > max_l {
+                        return None;
+                    }
+
+                    let population = match max_weight > 0.0 {
+                        true => self.weights.get(i).cloned().unwrap_or(0.0) / max_weight,
+                        false => 0.0,
+                    };
+
+                    let score = SATURATION_WEIGHT * (1.0 - (s - target_s).abs()) +
+                                LIGHTNESS_WEIGHT * (1.0 - (l - target_l).abs()) +
+                                POPULATION_WEIGHT * population;
+
+                    Some((i, score))
+                })
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(i, _)| i)?;
+
+            used[best] = true;
+            Some(self.colors[best])
+        };
+
+        // The same order as Vibrant.js, so vibrant swatches get first pick.
+        let vibrant = pick(&VIBRANT);
+        let light_vibrant = pick(&LIGHT_VIBRANT);
+        let dark_vibrant = pick(&DARK_VIBRANT);
+        let muted = pick(&MUTED);
+        let light_muted = pick(&LIGHT_MUTED);
+        let dark_muted = pick(&DARK_MUTED);
+
+        Swatches {
+            vibrant,
+            dark_vibrant,
+            light_vibrant,
+            muted,
+            dark_muted,
+            light_muted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::distil_palette;
+    use crate::{Color, IntoLab};
+
+    #[test]
+    fn swatches() {
+        let red = Color::new(220, 40, 40);
+        let navy = Color::new(20, 30, 110);
+        let pink = Color::new(250, 170, 190);
+        let slate = Color::new(110, 120, 130);
+        let grey = Color::new(60, 58, 55);
+
+        let palette = [(slate, 40), (red, 30), (navy, 20), (pink, 10), (grey, 5)];
+        let distilled = distil_palette(palette.iter().map(|&(color, count)| (color.into_lab(), count)).collect());
+        let swatches = distilled.swatches();
+        let color = |target: Color| distilled.colors.iter().find(|&&c| crate::delta_e(c, target) < 1.0).cloned();
+
+        assert_eq!(swatches.vibrant, color(red));
+        assert_eq!(swatches.dark_vibrant, color(navy));
+        assert_eq!(swatches.light_vibrant, color(pink));
+        assert_eq!(swatches.muted, color(slate));
+        assert_eq!(swatches.dark_muted, color(grey));
+        assert_eq!(swatches.light_muted, None);
+    }
+}
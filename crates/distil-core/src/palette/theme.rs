@@ -0,0 +1,299 @@
+use lab::Lab;
+
+use crate::{contrast_ratio, Color, Distil, DistilError};
+
+/// The contrast ratio accents need with the background to be told apart
+/// from it, WCAG's minimum for UI components.
+static MIN_ACCENT_CONTRAST: f32 = 3.0;
+
+/// What a slot loses for missing its contrast target, large enough that
+/// any assignment meeting every target wins over one that doesn't.
+static CONTRAST_PENALTY: f32 = 10.0;
+
+/// A theme filled from a palette by `Distil::fill_theme`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The color behind everything else.
+    pub background: Color,
+
+    /// The color of text on `background`.
+    pub foreground: Color,
+
+    /// The accent colors, from most to least chromatic.
+    pub accents: Vec<Color>,
+
+    /// The contrast ratio between `foreground` and `background`.
+    pub contrast: f32,
+}
+
+impl Distil {
+    /// Assigns the colors of the palette to the slots of a theme: a
+    /// background, a foreground and `accents` accent colors, each color
+    /// being used at most once.
+    ///
+    /// The background favours frequent, calm colors, the foreground favours
+    /// colors with at least `min_contrast` against the background and the
+    /// accents favour chromatic colors that stand out from it. Every
+    /// possible background is tried and the remaining slots are filled with
+    /// an optimal (Hungarian) assignment, keeping the best theme overall.
+    ///
+    /// Returns `DistilError::TooFewColors` if the palette has fewer than
+    /// `accents + 2` colors.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     if let Ok(theme) = distilled.fill_theme(2, 4.5) {
+    ///         let (background, text) = (theme.background, theme.foreground);
+    ///     }
+    /// }
+    /// ```
+    pub fn fill_theme(&self, accents: usize, min_contrast: f32) -> Result<Theme, DistilError> {
+        let slots = accents + 2;
+        let found = self.lab_colors.len();
+
+        if found < slots {
+            return Err(DistilError::TooFewColors(slots, found));
+        }
+
+
+        let mut best: Option<(f32, usize, Vec<usize>)> = None;
+
+        for (background, (&bg, share)) in self.lab_colors.iter().zip(self.shares()).enumerate() {
+            let others: Vec<usize> = (0..found).filter(|&i| i != background).collect();
+
+            // One row for the foreground followed by one for each accent,
+            // and one column for each color that isn't the background.
+            let costs: Vec<Vec<f32>> = (0..accents + 1)
+                .map(|slot| {
+                    others.iter()
+                        .map(|&i| {
+                            if slot == 0 {
+                                -foreground_score(bg, self.lab_colors[i], min_contrast)
+                            } else {
+                                -accent_score(bg, self.lab_colors[i])
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let assignment = hungarian(&costs);
+            let cost: f32 = assignment.iter().enumerate().map(|(slot, &column)| costs[slot][column]).sum();
+            let score = share + (1.0 - chroma(bg) / 100.0).max(0.0) - cost;
+
+            if best.as_ref().map_or(true, |(best_score, _, _)| score > *best_score) {
+                best = Some((score, background, assignment.iter().map(|&column| others[column]).collect()));
+            }
+        }
+
+        let (_, background, assigned) = best.ok_or(DistilError::TooFewColors(slots, found))?;
+        let mut accent_labs: Vec<Lab> = assigned[1..].iter().map(|&i| self.lab_colors[i]).collect();
+        accent_labs.sort_by(|a, b| chroma(*b).total_cmp(&chroma(*a)));
+
+        let (bg, fg) = (self.lab_colors[background], self.lab_colors[assigned[0]]);
+
+        Ok(Theme {
+            background: Color::from(bg),
+            foreground: Color::from(fg),
+            accents: accent_labs.into_iter().map(Color::from).collect(),
+            contrast: contrast_ratio(fg, bg),
+        })
+    }
+
+    /// Returns a color for text set on the color at `index`, e.g. a title
+    /// overlaid on a swatch: the other color of the palette with the most
+    /// contrast against it, as long as that's at least `min_contrast`,
+    /// otherwise black or white, whichever contrasts more. Returns `None` if
+    /// there's no color at `index`.
+    ///
+    /// WCAG AA asks for a `min_contrast` of `4.5` for body text and `3.0`
+    /// for large text. Black or white always reach `4.5`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let title_color = distilled.readable_text_color(0, 4.5).unwrap();
+    /// }
+    /// ```
+    pub fn readable_text_color(&self, index: usize, min_contrast: f32) -> Option<Color> {
+        let bg = *self.colors.get(index)?;
+
+        let member = self.colors.iter()
+            .enumerate()
+            .filter(|&(i, _)| i != index)
+            .map(|(_, &fg)| (fg, fg.contrast_ratio(bg)))
+            .filter(|&(_, contrast)| contrast >= min_contrast)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        if let Some((fg, _)) = member {
+            return Some(fg);
+        }
+
+        let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+
+        match black.contrast_ratio(bg) >= white.contrast_ratio(bg) {
+            true => Some(black),
+            false => Some(white),
+        }
+    }
+}
+
+fn chroma(lab: Lab) -> f32 {
+    (lab.a * lab.a + lab.b * lab.b).sqrt()
+}
+
+/// Scores a foreground by its contrast with the background, with diminishing
+/// returns past `min_contrast`.
+fn foreground_score(bg: Lab, fg: Lab, min_contrast: f32) -> f32 {
+    let contrast = contrast_ratio(fg, bg);
+
+    if contrast < min_contrast {
+        contrast / min_contrast - CONTRAST_PENALTY
+    } else {
+        1.0 + (contrast - min_contrast) / 21.0
+    }
+}
+
+/// Scores an accent by its chroma, as long as it stands out from the
+/// background.
+fn accent_score(bg: Lab, accent: Lab) -> f32 {
+    let score = (chroma(accent) / 100.0).min(1.0);
+
+    if contrast_ratio(accent, bg) < MIN_ACCENT_CONTRAST {
+        score - CONTRAST_PENALTY
+    } else {
+        score
+    }
+}
+
+/// Solves the assignment problem for a matrix with at most as many rows as
+/// columns, returning the column assigned to each row such that the total
+/// cost is minimal.
+fn hungarian(costs: &[Vec<f32>]) -> Vec<usize> {
+    let rows = costs.len();
+    let columns = costs.first().map_or(0, |row| row.len());
+
+    // Potentials and matches are 1-indexed, with 0 standing for "none".
+    let mut u = vec![0.0f64; rows + 1];
+    let mut v = vec![0.0f64; columns + 1];
+    let mut matched = vec![0usize; columns + 1];
+    let mut way = vec![0usize; columns + 1];
+
+    for row in 1..=rows {
+        matched[0] = row;
+        let mut column = 0;
+        let mut min = vec![f64::INFINITY; columns + 1];
+        let mut used = vec![false; columns + 1];
+
+        loop {
+            used[column] = true;
+            let current = matched[column];
+            let mut delta = f64::INFINITY;
+            let mut next = 0;
+
+            for j in 1..=columns {
+                if used[j] {
+                    continue;
+                }
+
+                let reduced = costs[current - 1][j - 1] as f64 - u[current] - v[j];
+
+                if reduced < min[j] {
+                    min[j] = reduced;
+                    way[j] = column;
+                }
+
+                if min[j] < delta {
+                    delta = min[j];
+                    next = j;
+                }
+            }
+
+            for j in 0..=columns {
+                if used[j] {
+                    u[matched[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+
+            column = next;
+
+            if matched[column] == 0 {
+                break;
+            }
+        }
+
+        while column != 0 {
+            let previous = way[column];
+            matched[column] = matched[previous];
+            column = previous;
+        }
+    }
+
+    let mut assignment = vec![0; rows];
+
+    for j in 1..=columns {
+        if matched[j] != 0 {
+            assignment[matched[j] - 1] = j - 1;
+        }
+    }
+
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hungarian;
+    use crate::palette::distil_palette;
+    use crate::{Color, DistilError, IntoLab};
+
+    #[test]
+    fn assignment() {
+        let costs = vec![vec![4.0, 1.0, 3.0], vec![2.0, 0.0, 5.0]];
+        assert_eq!(hungarian(&costs), vec![1, 0]);
+    }
+
+    #[test]
+    fn fill_theme() {
+        let navy = Color::new(20, 30, 60);
+        let cream = Color::new(245, 235, 210);
+        let orange = Color::new(240, 120, 20);
+        let teal = Color::new(20, 170, 160);
+        let slate = Color::new(50, 60, 80);
+
+        let palette = distil_palette(vec![(navy.into_lab(), 10), (slate.into_lab(), 6), (cream.into_lab(), 4),
+                                          (orange.into_lab(), 2), (teal.into_lab(), 1)]);
+
+        let theme = palette.fill_theme(2, 4.5).unwrap();
+
+        assert_eq!(theme.background, navy);
+        assert_eq!(theme.foreground, cream);
+        assert_eq!(theme.accents, vec![orange, teal]);
+        assert!(theme.contrast >= 4.5);
+        assert!(matches!(palette.fill_theme(4, 4.5), Err(DistilError::TooFewColors(6, 5))));
+    }
+
+    #[test]
+    fn readable_text_color() {
+        let navy = Color::new(20, 30, 60);
+        let cream = Color::new(245, 235, 210);
+        let orange = Color::new(240, 120, 20);
+
+        let palette = distil_palette(vec![(navy.into_lab(), 4), (orange.into_lab(), 2), (cream.into_lab(), 1)]);
+        let color = |target: Color| palette.colors.iter().find(|&&c| crate::delta_e(c, target) < 1.0).cloned();
+
+        assert_eq!(palette.readable_text_color(0, 4.5), color(cream));
+        assert_eq!(palette.readable_text_color(1, 4.5), color(navy));
+        assert_eq!(palette.readable_text_color(1, 7.0), Some(Color::new(0, 0, 0)));
+        assert_eq!(palette.readable_text_color(3, 4.5), None);
+    }
+}
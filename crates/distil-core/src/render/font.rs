@@ -0,0 +1,93 @@
+use crate::Image;
+
+/// The width of a glyph in pixels, before scaling.
+pub(super) static GLYPH_WIDTH: u32 = 5;
+
+/// The height of a glyph in pixels, before scaling.
+pub(super) static GLYPH_HEIGHT: u32 = 7;
+
+/// The characters of hex codes and percentages, as rows of 5 bits from the
+/// top, the leftmost pixel in the highest bit.
+static GLYPHS: [(char, [u8; 7]); 18] = [
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    ('a', [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111]),
+    ('b', [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110]),
+    ('c', [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('d', [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111]),
+    ('e', [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110]),
+    ('f', [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000]),
+    ('#', [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010]),
+    ('%', [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011]),
+];
+
+/// Returns the width of `text` in pixels, before scaling, with a pixel
+/// between glyphs.
+pub(super) fn text_width(text: &str) -> u32 {
+    let count = text.chars().count() as u32;
+    (count * (GLYPH_WIDTH + 1)).saturating_sub(1)
+}
+
+/// Draws `text` onto `img` with its top left corner at `(x, y)`, each pixel
+/// of the font `scale` × `scale` pixels. Characters outside of hex codes and
+/// percentages are left blank, as is anything outside of the image.
+pub(super) fn draw_text(img: &mut Image, x: u32, y: u32, scale: u32, text: &str, rgba: [u8; 4]) {
+    let (width, height) = img.dimensions();
+
+    for (i, c) in text.chars().enumerate() {
+        let Some((_, rows)) = GLYPHS.iter().find(|&&(glyph, _)| glyph == c.to_ascii_lowercase()) else {
+            continue;
+        };
+
+        let left = x as u64 + i as u64 * (GLYPH_WIDTH + 1) as u64 * scale as u64;
+
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if (bits >> (GLYPH_WIDTH - 1 - column)) & 1 == 0 {
+                    continue;
+                }
+
+                let px_left = left + column as u64 * scale as u64;
+                let px_top = y as u64 + row as u64 * scale as u64;
+
+                for px_y in px_top..(px_top + scale as u64).min(height as u64) {
+                    for px_x in px_left..(px_left + scale as u64).min(width as u64) {
+                        img.put_pixel(px_x as u32, px_y as u32, rgba);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{draw_text, text_width};
+    use crate::Image;
+
+    #[test]
+    fn glyphs() {
+        assert_eq!(text_width("#a0"), 17);
+        assert_eq!(text_width(""), 0);
+
+        let mut img = Image::new(20, 16);
+        draw_text(&mut img, 0, 1, 2, "1", [255, 255, 255, 255]);
+
+        // The top of the stem of a `1`, two pixels wide.
+        assert_eq!(img.get_pixel(4, 1), [255, 255, 255, 255]);
+        assert_eq!(img.get_pixel(5, 2), [255, 255, 255, 255]);
+        assert_eq!(img.get_pixel(3, 1), [0, 0, 0, 0]);
+        assert_eq!(img.get_pixel(4, 0), [0, 0, 0, 0]);
+
+        // Clipped to the image instead of panicking.
+        draw_text(&mut img, 15, 10, 3, "88", [255, 255, 255, 255]);
+    }
+}
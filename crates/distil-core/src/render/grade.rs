@@ -0,0 +1,384 @@
+use lab::Lab;
+
+use crate::{lab_to_rgb, Distil, Image, IntoLab};
+
+/// How far, in L*, a palette color's influence reaches when grading pixels
+/// of a different lightness.
+static GRADE_LIGHTNESS_SPREAD: f32 = 20.0;
+
+/// A color grade which can be applied to images or baked into a 3D LUT.
+///
+/// `tint`, `apply_palette_grade`, `transfer_colors` and `gradient_map` are
+/// shorthands for building a `Grade` and applying it straight away.
+#[derive(Debug, Clone)]
+pub struct Grade {
+    kind: GradeKind,
+}
+
+#[derive(Debug, Clone)]
+enum GradeKind {
+    Tint { target: Lab, strength: f32 },
+    Palette { tones: Vec<(Lab, f32)>, strength: f32 },
+    Transfer { source: ([f32; 3], [f32; 3]), target: ([f32; 3], [f32; 3]) },
+    GradientMap { stops: Vec<Lab> },
+}
+
+impl Grade {
+    /// A grade that shifts the a* and b* channels of every color toward
+    /// `color`'s, leaving lightness untouched. `strength` goes from `0.0`
+    /// (unchanged) to `1.0` (every color takes `color`'s hue and chroma).
+    /// `color` can be anything that converts into Lab, including the
+    /// `palette` crate's color types with the `palette` feature enabled.
+    pub fn tint<C: IntoLab>(color: C, strength: f32) -> Grade {
+        Grade {
+            kind: GradeKind::Tint {
+                target: color.into_lab(),
+                strength: strength.clamp(0.0, 1.0),
+            },
+        }
+    }
+
+    /// A grade with the look of `palette` — typically distilled from a
+    /// reference image — which split-tones colors in Lab space.
+    ///
+    /// The a* and b* channels of each color are shifted toward the palette
+    /// colors closest to it in lightness, weighted by how frequent those
+    /// colors are, so shadows pick up the hue of the palette's dark colors
+    /// and highlights the hue of its light ones. `strength` goes from `0.0`
+    /// (unchanged) to `1.0`.
+    pub fn palette(palette: &Distil, strength: f32) -> Grade {
+        let tones = palette.lab_colors
+            .iter()
+            .enumerate()
+            .map(|(i, &lab)| (lab, palette.counts.get(i).copied().unwrap_or(1) as f32))
+            .collect();
+
+        Grade {
+            kind: GradeKind::Palette {
+                tones,
+                strength: strength.clamp(0.0, 1.0),
+            },
+        }
+    }
+
+    /// A grade that transfers the colors of `source` onto `target` using
+    /// Reinhard et al.'s statistical color transfer: every Lab channel is
+    /// shifted and scaled so that the mean and standard deviation of
+    /// `target`'s colors match those of `source`'s.
+    pub fn transfer(source: &Image, target: &Image) -> Grade {
+        Grade {
+            kind: GradeKind::Transfer {
+                source: lab_statistics(source),
+                target: lab_statistics(target),
+            },
+        }
+    }
+
+    /// A gradient map with the `count` most frequent colors of `palette` as
+    /// its stops, from darkest to lightest: every color is replaced with
+    /// the color at its lightness along the stops, evenly spaced and
+    /// interpolated in Lab, so black maps to the first stop and white to
+    /// the last. With a `count` of `2` it's a duotone. See
+    /// `Distil::gradient_stops`.
+    pub fn gradient_map(palette: &Distil, count: usize) -> Grade {
+        Grade {
+            kind: GradeKind::GradientMap { stops: palette.gradient_stops(count) },
+        }
+    }
+
+    /// A duotone that maps shadows to `shadows` and highlights to
+    /// `highlights`, blending between them by lightness. See
+    /// `Grade::gradient_map`.
+    pub fn duotone<C: IntoLab, D: IntoLab>(shadows: C, highlights: D) -> Grade {
+        Grade {
+            kind: GradeKind::GradientMap { stops: vec![shadows.into_lab(), highlights.into_lab()] },
+        }
+    }
+
+    /// Applies the grade to every pixel of `img`, keeping alpha.
+    pub fn apply(&self, img: &Image) -> Image {
+        let mut graded = img.clone();
+
+        for px in graded.pixels_mut() {
+            let rgb = lab_to_rgb(self.grade_lab(Lab::from_rgb(&[px[0], px[1], px[2]])));
+            px[..3].copy_from_slice(&rgb);
+        }
+
+        graded
+    }
+
+    /// Returns the graded version of a single Lab color.
+    pub fn grade_lab(&self, lab: Lab) -> Lab {
+        match self.kind {
+            GradeKind::Tint { target, strength } => shift_chroma(lab, target, strength),
+            GradeKind::Palette { ref tones, strength } => {
+                let mut target = Lab { l: lab.l, a: 0.0, b: 0.0 };
+                let mut total_weight = 0.0;
+
+                for &(tone, count) in tones {
+                    let distance = (lab.l - tone.l) / GRADE_LIGHTNESS_SPREAD;
+                    let weight = count * (-distance * distance).exp();
+
+                    target.a += tone.a * weight;
+                    target.b += tone.b * weight;
+                    total_weight += weight;
+                }
+
+                if total_weight <= 0.0 {
+                    return lab;
+                }
+
+                target.a /= total_weight;
+                target.b /= total_weight;
+
+                shift_chroma(lab, target, strength)
+            }
+            GradeKind::Transfer { source, target } => {
+                let transfer = |value: f32, i: usize| {
+                    let scale = if target.1[i] > 0.0 {
+                        source.1[i] / target.1[i]
+                    } else {
+                        0.0
+                    };
+
+                    (value - target.0[i]) * scale + source.0[i]
+                };
+
+                Lab {
+                    l: transfer(lab.l, 0).clamp(0.0, 100.0),
+                    a: transfer(lab.a, 1),
+                    b: transfer(lab.b, 2),
+                }
+            }
+            GradeKind::GradientMap { ref stops } => {
+                let (first, last) = match (stops.first(), stops.last()) {
+                    (Some(&first), Some(&last)) => (first, last),
+                    _ => return lab,
+                };
+
+                if stops.len() == 1 {
+                    return first;
+                }
+
+                let position = (lab.l / 100.0).clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+                let i = (position as usize).min(stops.len() - 2);
+                let t = position - i as f32;
+                let (from, to) = match (stops.get(i), stops.get(i + 1)) {
+                    (Some(&from), Some(&to)) => (from, to),
+                    _ => (first, last),
+                };
+
+                Lab {
+                    l: from.l + (to.l - from.l) * t,
+                    a: from.a + (to.a - from.a) * t,
+                    b: from.b + (to.b - from.b) * t,
+                }
+            }
+        }
+    }
+}
+
+/// Tints `img` toward `color`. See `Grade::tint`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::tint;
+/// use distil_core::{Color, Image};
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let sepia = tint(&img, Color::new(112, 66, 20), 0.5);
+/// }
+/// ```
+pub fn tint<C: IntoLab>(img: &Image, color: C, strength: f32) -> Image {
+    Grade::tint(color, strength).apply(img)
+}
+
+/// Grades `img` with the look of `palette`. See `Grade::palette`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::apply_palette_grade;
+/// use distil_core::{Distil, Image};
+///
+/// let reference = Distil::from_path_str("./images/img-3.jpg");
+///
+/// if let (Ok(reference), Ok(img)) = (reference, Image::open(Path::new("./images/img-1.jpg"))) {
+///     let graded = apply_palette_grade(&img, &reference, 0.5);
+/// }
+/// ```
+pub fn apply_palette_grade(img: &Image, palette: &Distil, strength: f32) -> Image {
+    Grade::palette(palette, strength).apply(img)
+}
+
+/// Transfers the colors of `source` onto `target`. See `Grade::transfer`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::transfer_colors;
+/// use distil_core::Image;
+///
+/// let source = Image::open(Path::new("./images/img-3.jpg"));
+/// let target = Image::open(Path::new("./images/img-1.jpg"));
+///
+/// if let (Ok(source), Ok(target)) = (source, target) {
+///     let result = transfer_colors(&source, &target);
+/// }
+/// ```
+pub fn transfer_colors(source: &Image, target: &Image) -> Image {
+    Grade::transfer(source, target).apply(target)
+}
+
+/// Recolors `img` with a gradient map of the `count` most frequent colors
+/// of `palette`, or a duotone when `count` is `2`. See
+/// `Grade::gradient_map`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::gradient_map;
+/// use distil_core::{Distil, Image};
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let distilled = Distil::from_image(&img).unwrap();
+///     let duotone = gradient_map(&img, &distilled, 2);
+/// }
+/// ```
+pub fn gradient_map(img: &Image, palette: &Distil, count: usize) -> Image {
+    Grade::gradient_map(palette, count).apply(img)
+}
+
+/// Returns the mean and standard deviation of each Lab channel over the
+/// visible pixels of `img`.
+fn lab_statistics(img: &Image) -> ([f32; 3], [f32; 3]) {
+    let labs: Vec<[f32; 3]> = img.pixels()
+        .filter(|px| px[3] != 0)
+        .map(|px| {
+            let lab = Lab::from_rgb(&[px[0], px[1], px[2]]);
+            [lab.l, lab.a, lab.b]
+        })
+        .collect();
+
+    let mut mean = [0.0; 3];
+    let mut deviation = [0.0; 3];
+
+    if labs.is_empty() {
+        return (mean, deviation);
+    }
+
+    let count = labs.len() as f32;
+
+    for lab in &labs {
+        for i in 0..3 {
+            mean[i] += lab[i] / count;
+        }
+    }
+
+    for lab in &labs {
+        for i in 0..3 {
+            deviation[i] += (lab[i] - mean[i]).powi(2) / count;
+        }
+    }
+
+    for value in &mut deviation {
+        *value = value.sqrt();
+    }
+
+    (mean, deviation)
+}
+
+/// Moves the a* and b* channels of `lab` toward those of `target`.
+fn shift_chroma(lab: Lab, target: Lab, strength: f32) -> Lab {
+    Lab {
+        l: lab.l,
+        a: lab.a + (target.a - lab.a) * strength,
+        b: lab.b + (target.b - lab.b) * strength,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lab::Lab;
+
+    use super::{apply_palette_grade, gradient_map, tint, transfer_colors, Grade};
+    use crate::palette::distil_palette;
+    use crate::{delta_e, gen, Color, Image, IntoLab};
+
+    fn grey_ramp() -> Image {
+        gen::gradient(16, 1, Color::new(0, 0, 0), Color::new(240, 240, 240))
+    }
+
+    #[test]
+    fn tints() {
+        let img = grey_ramp();
+        let orange = Color::new(230, 120, 30);
+
+        let untouched = tint(&img, orange, 0.0);
+        let tinted = tint(&img, orange, 1.0);
+
+        for (before, after) in img.pixels().zip(untouched.pixels()) {
+            assert!(delta_e([before[0], before[1], before[2]], [after[0], after[1], after[2]]) < 1.0);
+        }
+
+        let px = tinted.get_pixel(8, 0);
+        assert!(px[0] > px[2]);
+    }
+
+    #[test]
+    fn grades() {
+        let warm_shadows = Lab { l: 20.0, a: 30.0, b: 30.0 };
+        let cool_highlights = Lab { l: 90.0, a: -10.0, b: -30.0 };
+        let palette = distil_palette(vec![(warm_shadows, 1), (cool_highlights, 1)]);
+
+        let graded = apply_palette_grade(&grey_ramp(), &palette, 1.0);
+
+        let shadow = graded.get_pixel(3, 0);
+        let highlight = graded.get_pixel(15, 0);
+
+        assert!(shadow[0] > shadow[2]);
+        assert!(highlight[2] > highlight[0]);
+    }
+
+    #[test]
+    fn transfers() {
+        let source = gen::solid(4, 4, Color::new(200, 60, 40));
+        let result = transfer_colors(&source, &grey_ramp());
+
+        for px in result.pixels() {
+            assert!(delta_e([px[0], px[1], px[2]], [200, 60, 40]) < 1.0);
+        }
+    }
+
+    #[test]
+    fn gradient_maps() {
+        let navy = Color::new(20, 30, 90);
+        let peach = Color::new(250, 200, 160);
+        let palette = distil_palette(vec![(peach.into_lab(), 2), (navy.into_lab(), 1), (Color::new(200, 40, 40).into_lab(), 1)]);
+
+        let duotone = gradient_map(&grey_ramp(), &palette, 2);
+
+        let rgb = |x: u32| {
+            let px = duotone.get_pixel(x, 0);
+            [px[0], px[1], px[2]]
+        };
+
+        assert!(delta_e(rgb(0), navy) < 1.0);
+        assert!(delta_e(rgb(15), peach) < 10.0);
+        assert!(delta_e(rgb(8), navy) > 10.0 && delta_e(rgb(8), peach) > 10.0);
+
+        let graded = Grade::duotone(navy, peach).grade_lab(Lab { l: 100.0, a: 0.0, b: 0.0 });
+        assert!(delta_e(graded, peach) < 0.01);
+
+        let untouched = gradient_map(&grey_ramp(), &distil_palette(Vec::new()), 2);
+
+        for (before, after) in grey_ramp().pixels().zip(untouched.pixels()) {
+            assert!(delta_e([before[0], before[1], before[2]], [after[0], after[1], after[2]]) < 1.0);
+        }
+    }
+}
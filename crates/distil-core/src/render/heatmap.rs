@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use lab::Lab;
+
+use crate::palette::nearest_color;
+use crate::{Color, Distil, Image, IntoLab};
+
+/// Renders a heatmap of `img` highlighting the regions whose colors aren't
+/// within `tolerance` of any of the approved `brand` colors.
+///
+/// The image is split into `block_size` × `block_size` blocks and each
+/// block's average color is assigned to its nearest brand color. Blocks that
+/// are within tolerance are drawn as a dimmed greyscale version of the
+/// block, blocks that aren't are drawn in red, getting more saturated the
+/// further they are from the brand palette. The heatmap is opaque and has
+/// the same dimensions as `img`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::compliance_heatmap;
+/// use distil_core::{Color, Image};
+///
+/// let brand: Vec<Color> = vec!["#1d3557".parse().unwrap(), "#e63946".parse().unwrap()];
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let heatmap = compliance_heatmap(&img, &brand, 10.0, 16);
+///     // Save or display the heatmap…
+/// }
+/// ```
+pub fn compliance_heatmap(img: &Image, brand: &[Color], tolerance: f32, block_size: u32) -> Image {
+    let brand_labs: Vec<Lab> = brand.iter().map(|&color| color.into_lab()).collect();
+    let (width, height) = img.dimensions();
+    let block_size = block_size.max(1);
+
+    let mut heatmap = Image::new(width, height);
+
+    for block_y in (0..height).step_by(block_size as usize) {
+        for block_x in (0..width).step_by(block_size as usize) {
+            let block_width = block_size.min(width - block_x);
+            let block_height = block_size.min(height - block_y);
+
+            let mut sum = [0.0; 3];
+            let mut count = 0.0;
+
+            for y in block_y..block_y + block_height {
+                for x in block_x..block_x + block_width {
+                    let px = img.get_pixel(x, y);
+
+                    if px[3] == 0 {
+                        continue;
+                    }
+
+                    let lab = Lab::from_rgb(&[px[0], px[1], px[2]]);
+                    sum[0] += lab.l;
+                    sum[1] += lab.a;
+                    sum[2] += lab.b;
+                    count += 1.0;
+                }
+            }
+
+            let heat = if count == 0.0 {
+                [0, 0, 0, 255]
+            } else {
+                let average = Lab {
+                    l: sum[0] / count,
+                    a: sum[1] / count,
+                    b: sum[2] / count,
+                };
+
+                let distance = nearest_color(average, &brand_labs).map_or(f32::INFINITY, |(_, d)| d);
+                heat_color(average.l, distance, tolerance)
+            };
+
+            for y in block_y..block_y + block_height {
+                for x in block_x..block_x + block_width {
+                    heatmap.put_pixel(x, y, heat);
+                }
+            }
+        }
+    }
+
+    heatmap
+}
+
+/// Renders a heatmap of how well `distil` represents `img`, coloring each
+/// pixel by the CIEDE2000 distance to its nearest palette color: black for
+/// colors the palette has, through red and yellow, to white for colors
+/// `max_distance` or more away. It's meant for tuning palette sizes and
+/// thresholds by showing which regions the palette misses.
+///
+/// Transparent pixels are drawn black. The heatmap is opaque and has the
+/// same dimensions as `img`.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::distance_heatmap;
+/// use distil_core::{Distil, Image};
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let distilled = Distil::from_image(&img).unwrap();
+///     let heatmap = distance_heatmap(&img, &distilled, 30.0);
+///     // Save or display the heatmap…
+/// }
+/// ```
+pub fn distance_heatmap(img: &Image, distil: &Distil, max_distance: f32) -> Image {
+    let (width, height) = img.dimensions();
+    let mut heatmap = Image::new(width, height);
+    let mut distances = HashMap::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let px = img.get_pixel(x, y);
+
+            if px[3] == 0 {
+                heatmap.put_pixel(x, y, [0, 0, 0, 255]);
+                continue;
+            }
+
+            let rgb = [px[0], px[1], px[2]];
+            let distance = *distances.entry(rgb).or_insert_with(|| {
+                nearest_color(Lab::from_rgb(&rgb), &distil.lab_colors).map_or(f32::INFINITY, |(_, d)| d)
+            });
+
+            let heat = if max_distance > 0.0 { (distance / max_distance).min(1.0) } else { 1.0 };
+            heatmap.put_pixel(x, y, distance_color(heat));
+        }
+    }
+
+    heatmap
+}
+
+/// Maps `heat`, between `0.0` and `1.0`, onto a black, red, yellow and
+/// white ramp.
+fn distance_color(heat: f32) -> [u8; 4] {
+    let channel = |from: f32| ((heat * 3.0 - from).clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    [channel(0.0), channel(1.0), channel(2.0), 255]
+}
+
+/// Picks the heatmap color of a block with the passed lightness which is
+/// `distance` away from its nearest brand color.
+fn heat_color(lightness: f32, distance: f32, tolerance: f32) -> [u8; 4] {
+    let grey = (lightness / 100.0 * 255.0 * 0.6).clamp(0.0, 255.0);
+
+    if distance <= tolerance {
+        let grey = grey as u8;
+        return [grey, grey, grey, 255];
+    }
+
+    let severity = if tolerance > 0.0 {
+        ((distance - tolerance) / tolerance).min(1.0)
+    } else {
+        1.0
+    };
+    let mix = 0.5 + 0.5 * severity;
+
+    [
+        (grey + (255.0 - grey) * mix) as u8,
+        (grey * (1.0 - mix)) as u8,
+        (grey * (1.0 - mix)) as u8,
+        255,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{compliance_heatmap, distance_heatmap};
+    use crate::palette::distil_palette;
+    use crate::{gen, Color, Image};
+
+    #[test]
+    fn heatmap() {
+        let img = Image::open(Path::new("./images/img-1.jpg")).unwrap();
+        let brand = vec![Color::new(29, 53, 87)];
+
+        let lenient = compliance_heatmap(&img, &brand, 1000.0, 32);
+        assert_eq!(lenient.dimensions(), img.dimensions());
+        assert!(lenient.pixels().all(|px| px[0] == px[1] && px[1] == px[2]));
+
+        let strict = compliance_heatmap(&img, &[], 10.0, 32);
+        assert!(strict.pixels().all(|px| px[0] > px[1]));
+    }
+
+    #[test]
+    fn distances() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(240, 110, 90);
+        let mut img = gen::blocks(20, 10, &[(teal, 1), (coral, 1)]);
+        img.put_pixel(0, 0, [0, 0, 0, 0]);
+
+        let palette = distil_palette(vec![(teal.lab(), 1)]);
+        let heatmap = distance_heatmap(&img, &palette, 30.0);
+
+        assert_eq!(heatmap.dimensions(), img.dimensions());
+        assert_eq!(heatmap.get_pixel(0, 0), [0, 0, 0, 255]);
+        assert_eq!(heatmap.get_pixel(5, 5), [0, 0, 0, 255]);
+        assert_eq!(heatmap.get_pixel(15, 5), [255, 255, 255, 255]);
+    }
+}
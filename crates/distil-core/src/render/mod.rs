@@ -0,0 +1,15 @@
+//! Rendering palettes and grades into images, and palettes into SVG.
+
+pub use self::grade::{apply_palette_grade, gradient_map, tint, transfer_colors, Grade};
+pub use self::heatmap::{compliance_heatmap, distance_heatmap};
+pub use self::remap::{dither_to_palette, remap_to_palette};
+pub use self::swatch::{Orientation, PaletteRenderOptions};
+pub use self::terminal::{half_blocks, ColorDepth};
+
+mod font;
+mod grade;
+mod heatmap;
+mod remap;
+mod svg;
+mod swatch;
+mod terminal;
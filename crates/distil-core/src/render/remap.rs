@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use lab::Lab;
+
+use crate::palette::nearest_color;
+use crate::{lab_to_rgb, Distil, Image};
+
+/// Recolors `img` with the colors of `palette` alone: every pixel is
+/// replaced with the color of the palette nearest to it by CIEDE2000,
+/// keeping its alpha. Returns a copy of `img` if the palette is empty.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::remap_to_palette;
+/// use distil_core::{Distil, Image};
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let distilled = Distil::from_image(&img).unwrap();
+///     let posterized = remap_to_palette(&img, &distilled);
+///     // Save or display the recolored image…
+/// }
+/// ```
+pub fn remap_to_palette(img: &Image, palette: &Distil) -> Image {
+    let mut remapped = img.clone();
+    let mut nearest = HashMap::new();
+
+    for px in remapped.pixels_mut() {
+        let rgb = [px[0], px[1], px[2]];
+        let mapped = *nearest.entry(rgb).or_insert_with(|| {
+            nearest_color(Lab::from_rgb(&rgb), &palette.lab_colors).map_or(rgb, |(i, _)| lab_to_rgb(palette.lab_colors[i]))
+        });
+
+        px[..3].copy_from_slice(&mapped);
+    }
+
+    remapped
+}
+
+/// Like `remap_to_palette`, but with Floyd–Steinberg dithering: the
+/// difference between each pixel and the palette color it's replaced with
+/// is spread over the pixels to its right and below, so that gradients and
+/// colors in between those of the palette come out as a mix of them rather
+/// than as flat bands.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::render::dither_to_palette;
+/// use distil_core::{gen, Color, Distil};
+///
+/// let img = gen::gradient(64, 16, Color::new(0, 128, 128), Color::new(255, 127, 80));
+/// let distilled = Distil::from_image(&img).unwrap();
+/// let dithered = dither_to_palette(&img, &distilled);
+/// ```
+pub fn dither_to_palette(img: &Image, palette: &Distil) -> Image {
+    let mut dithered = img.clone();
+
+    if palette.lab_colors.is_empty() {
+        return dithered;
+    }
+
+    let (width, height) = img.dimensions();
+    let palette_rgb: Vec<[u8; 3]> = palette.lab_colors.iter().map(|&lab| lab_to_rgb(lab)).collect();
+    let mut nearest = HashMap::new();
+
+    // The errors carried into the current and the next row, with a column
+    // of padding on either side.
+    let mut errors = vec![[0.0f32; 3]; width as usize + 2];
+    let mut next_errors = vec![[0.0f32; 3]; width as usize + 2];
+
+    for y in 0..height {
+        for x in 0..width {
+            let rgba = dithered.get_pixel(x, y);
+            let i = x as usize + 1;
+            let wanted = [0, 1, 2].map(|c| rgba[c] as f32 + errors[i][c]);
+            let rgb = wanted.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+
+            let mapped = *nearest.entry(rgb).or_insert_with(|| {
+                nearest_color(Lab::from_rgb(&rgb), &palette.lab_colors).map_or(rgb, |(j, _)| palette_rgb[j])
+            });
+
+            dithered.put_pixel(x, y, [mapped[0], mapped[1], mapped[2], rgba[3]]);
+
+            let error = [0, 1, 2].map(|c| wanted[c] - mapped[c] as f32);
+
+            spread(&mut errors[i + 1], error, 7.0 / 16.0);
+            spread(&mut next_errors[i - 1], error, 3.0 / 16.0);
+            spread(&mut next_errors[i], error, 5.0 / 16.0);
+            spread(&mut next_errors[i + 1], error, 1.0 / 16.0);
+        }
+
+        errors = std::mem::replace(&mut next_errors, vec![[0.0; 3]; width as usize + 2]);
+    }
+
+    dithered
+}
+
+impl Distil {
+    /// `apply_to` recolors `img` with the colors of the palette alone, e.g.
+    /// to posterize related assets consistently, with Floyd–Steinberg
+    /// dithering if `dither` is `true`. See `remap_to_palette` and
+    /// `dither_to_palette`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::{Distil, Image};
+    ///
+    /// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+    ///     let distilled = Distil::from_image(&img).unwrap();
+    ///     let posterized = distilled.apply_to(&img, true);
+    /// }
+    /// ```
+    pub fn apply_to(&self, img: &Image, dither: bool) -> Image {
+        if dither {
+            dither_to_palette(img, self)
+        } else {
+            remap_to_palette(img, self)
+        }
+    }
+}
+
+/// Adds `share` of `error` to `target`.
+fn spread(target: &mut [f32; 3], error: [f32; 3], share: f32) {
+    for (channel, error) in target.iter_mut().zip(error) {
+        *channel += error * share;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dither_to_palette, remap_to_palette};
+    use crate::palette::distil_palette;
+    use crate::{gen, Color, IntoLab};
+
+    #[test]
+    fn remap() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let palette = distil_palette(vec![(teal.into_lab(), 1), (coral.into_lab(), 1)]);
+
+        let mut img = gen::gradient(16, 4, Color::new(0, 140, 120), Color::new(240, 120, 90));
+        img.put_pixel(0, 0, [0, 140, 120, 10]);
+
+        let remapped = remap_to_palette(&img, &palette);
+
+        assert_eq!(remapped.get_pixel(0, 0), [0, 128, 128, 10]);
+        assert_eq!(remapped.get_pixel(15, 3), [255, 127, 80, 255]);
+        assert!(remapped.pixels().all(|px| [teal, coral].contains(&Color::new(px[0], px[1], px[2]))));
+    }
+    #[test]
+    fn dither() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let palette = distil_palette(vec![(teal.into_lab(), 1), (coral.into_lab(), 1)]);
+
+        let mut img = gen::solid(16, 16, Color::new(128, 128, 104));
+        img.put_pixel(0, 0, [128, 128, 104, 10]);
+
+        let dithered = dither_to_palette(&img, &palette);
+        let mean_red = dithered.pixels().map(|px| px[0] as f32).sum::<f32>() / 256.0;
+
+        assert_eq!(dithered.get_pixel(0, 0)[3], 10);
+        assert!(dithered.pixels().all(|px| [teal, coral].contains(&Color::new(px[0], px[1], px[2]))));
+        assert!((mean_red - 128.0).abs() < 16.0);
+
+        let remapped = remap_to_palette(&img, &palette);
+        assert!(remapped.pixels().skip(1).all(|px| px == remapped.get_pixel(1, 0)));
+
+        assert_eq!(palette.apply_to(&img, true).as_raw(), dithered.as_raw());
+        assert_eq!(palette.apply_to(&img, false).as_raw(), remapped.as_raw());
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt::Write;
+
+use super::swatch::{label_color, label_lines};
+use super::PaletteRenderOptions;
+use crate::Distil;
+
+/// The height of a label relative to the shorter side of its swatch.
+static LABEL_SCALE: f32 = 0.16;
+
+/// The distance between the lines of a label, in ems.
+static LABEL_LINE_HEIGHT: f32 = 1.2;
+
+impl Distil {
+    /// Renders the palette as configured by `options` into an SVG document,
+    /// one `<rect>` per swatch and, with `options.labels`, `<text>` with
+    /// its hex code. Unlike the PNG of `render_palette`, it stays sharp at
+    /// any size, e.g. when embedded in a responsive web page, and its
+    /// `viewBox` lets it be scaled with CSS.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::render::PaletteRenderOptions;
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let distilled = Distil::from_image(&gen::solid(10, 10, Color::new(0, 128, 128))).unwrap();
+    /// let svg = distilled.as_svg(&PaletteRenderOptions { labels: true, ..PaletteRenderOptions::default() });
+    ///
+    /// assert!(svg.starts_with("<svg") && svg.contains("</text>"));
+    /// ```
+    pub fn as_svg(&self, options: &PaletteRenderOptions) -> String {
+        let layout = self.layout(options);
+        let mut svg = String::new();
+
+        // Writing to a `String` can't fail.
+        let _ = writeln!(svg,
+                         "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+                         layout.width,
+                         layout.height);
+
+        let [r, g, b, a] = options.background;
+
+        if a != 0 {
+            let _ = writeln!(svg,
+                             "  <rect width=\"100%\" height=\"100%\" fill=\"#{:02x}{:02x}{:02x}\" fill-opacity=\"{}\"/>",
+                             r,
+                             g,
+                             b,
+                             format_opacity(a));
+        }
+
+        for swatch in &layout.swatches {
+            let _ = writeln!(svg,
+                             "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                             swatch.x,
+                             swatch.y,
+                             swatch.width,
+                             swatch.height,
+                             swatch.color);
+        }
+
+        if options.labels {
+            for (swatch, share) in layout.swatches.iter().zip(self.shares()) {
+                let font_size = (swatch.width.min(swatch.height) as f32 * LABEL_SCALE).round().max(1.0);
+                let lines = label_lines(swatch.color, share, options.percentages);
+
+                for (i, line) in lines.iter().enumerate() {
+                    // Lines are spread around the center of the swatch.
+                    let offset = (i as f32 - (lines.len() - 1) as f32 / 2.0) * LABEL_LINE_HEIGHT;
+
+                    let _ = writeln!(svg,
+                                     "  <text x=\"{}\" y=\"{}\" dy=\"{}em\" font-family=\"monospace\" font-size=\"{}\" \
+                                      fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                                     swatch.x as f32 + swatch.width as f32 / 2.0,
+                                     swatch.y as f32 + swatch.height as f32 / 2.0,
+                                     offset,
+                                     font_size,
+                                     label_color(swatch.color),
+                                     line);
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Formats an alpha channel as an opacity with at most three decimals.
+fn format_opacity(alpha: u8) -> String {
+    let opacity = format!("{:.3}", alpha as f32 / 255.0);
+    opacity.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PaletteRenderOptions;
+    use crate::render::Orientation;
+    use crate::{Color, Distil, IntoLab};
+
+    #[test]
+    fn as_svg() {
+        let colors = [Color::new(20, 30, 60), Color::new(245, 235, 210)];
+        let distil = Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        };
+
+        let svg = distil.as_svg(&PaletteRenderOptions::default());
+
+        assert_eq!(svg,
+                   "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"160\" height=\"80\" viewBox=\"0 0 160 80\">\n  \
+                    <rect x=\"0\" y=\"0\" width=\"80\" height=\"80\" fill=\"#141e3c\"/>\n  \
+                    <rect x=\"80\" y=\"0\" width=\"80\" height=\"80\" fill=\"#f5ebd2\"/>\n\
+                    </svg>\n");
+
+        let labeled = distil.as_svg(&PaletteRenderOptions {
+            orientation: Orientation::Vertical,
+            border: 2,
+            background: [255, 255, 255, 128],
+            labels: true,
+            ..PaletteRenderOptions::default()
+        });
+
+        assert!(labeled.contains("viewBox=\"0 0 84 164\""));
+        assert!(labeled.contains("fill=\"#ffffff\" fill-opacity=\"0.502\""));
+        assert!(labeled.contains("<rect x=\"2\" y=\"82\" width=\"80\" height=\"80\" fill=\"#f5ebd2\"/>"));
+        assert!(labeled.contains("fill=\"#ffffff\" text-anchor=\"middle\" dominant-baseline=\"central\">#141e3c</text>"));
+        assert!(labeled.contains("fill=\"#000000\" text-anchor=\"middle\" dominant-baseline=\"central\">#f5ebd2</text>"));
+
+        let shares = distil.as_svg(&PaletteRenderOptions { labels: true, percentages: true, ..PaletteRenderOptions::default() });
+        assert!(shares.contains("dy=\"-0.6em\""));
+        assert!(shares.contains("dy=\"0.6em\" font-family=\"monospace\" font-size=\"13\" fill=\"#ffffff\""));
+        assert!(shares.contains(">75%</text>"));
+    }
+}
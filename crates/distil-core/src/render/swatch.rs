@@ -0,0 +1,388 @@
+use std::path::Path;
+
+use super::font;
+use crate::{Color, Distil, DistilError, Image};
+
+/// The size of a swatch, on either side, unless configured otherwise.
+static SWATCH_SIZE: u32 = 80;
+
+/// The share of either side of a swatch its label may take up, as a
+/// fraction.
+static LABEL_FILL: (u64, u64) = (4, 5);
+
+/// The gap between two lines of a label, in pixels of the font.
+static LABEL_LINE_GAP: u32 = 3;
+
+/// Which way `Distil::render_palette` lays out the swatches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    /// Side by side, left to right.
+    #[default]
+    Horizontal,
+
+    /// Stacked, top to bottom.
+    Vertical,
+}
+
+/// Configures `Distil::render_palette`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteRenderOptions {
+    /// The number of most frequent colors rendered. Defaults to all of them.
+    pub colors: usize,
+
+    /// The width of each swatch in pixels. Defaults to 80.
+    pub swatch_width: u32,
+
+    /// The height of each swatch in pixels. Defaults to 80.
+    pub swatch_height: u32,
+
+    /// Whether swatches are laid out side by side or stacked. Defaults to
+    /// `Orientation::Horizontal`.
+    pub orientation: Orientation,
+
+    /// The gap between two swatches in pixels. Defaults to 0.
+    pub spacing: u32,
+
+    /// The border around the swatches in pixels. Defaults to 0.
+    pub border: u32,
+
+    /// The RGBA color of the spacing and the border. Defaults to
+    /// transparent.
+    pub background: [u8; 4],
+
+    /// Whether each swatch is as long, along the strip, as its share of the
+    /// rendered colors' weight, the "weighted bar" of film-frame palettes.
+    /// The strip stays as long as it would be with equal swatches. Defaults
+    /// to `false`.
+    pub proportional: bool,
+
+    /// Whether each swatch is labeled with its hex code, in black or white,
+    /// whichever reads better on it. `render_palette` draws labels with a
+    /// built-in bitmap font, as large as fits, and leaves swatches too
+    /// small for one unlabeled. Defaults to `false`.
+    pub labels: bool,
+
+    /// Whether labels also give each color's share of the image, e.g.
+    /// `42%`, below its hex code. Defaults to `false`.
+    pub percentages: bool,
+}
+
+impl Default for PaletteRenderOptions {
+    fn default() -> PaletteRenderOptions {
+        PaletteRenderOptions {
+            colors: usize::MAX,
+            swatch_width: SWATCH_SIZE,
+            swatch_height: SWATCH_SIZE,
+            orientation: Orientation::Horizontal,
+            spacing: 0,
+            border: 0,
+            background: [0, 0, 0, 0],
+            proportional: false,
+            labels: false,
+            percentages: false,
+        }
+    }
+}
+
+impl Distil {
+    /// Export the distilled color palette as a PNG.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::env;
+    /// use distil_core::Distil;
+    ///
+    /// let path_str = "./images/img-1.jpg";
+    /// let out_path = env::temp_dir().join("img-1-palette.png");
+    /// let palette_size = 5;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str(path_str) {
+    ///     distilled.as_img(&out_path, palette_size).unwrap();
+    /// }
+    /// ```
+    pub fn as_img(&self, out_path: &Path, palette_size: u8) -> Result<(), DistilError> {
+        self.swatch_img(palette_size).save(out_path)
+    }
+
+    /// Renders the first `palette_size` colors of the palette side by side,
+    /// each as an 80 × 80 square, which is what `as_img` saves.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     let swatches = distilled.swatch_img(5);
+    /// }
+    /// ```
+    pub fn swatch_img(&self, palette_size: u8) -> Image {
+        self.render_palette(&PaletteRenderOptions {
+            colors: palette_size as usize,
+            ..PaletteRenderOptions::default()
+        })
+    }
+
+    /// Renders the palette as configured by `options` into an image, e.g.
+    /// to composite it into a larger image or to send it over HTTP with
+    /// `Image::to_png`, without touching the disk.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::render::PaletteRenderOptions;
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let distilled = Distil::from_image(&gen::solid(10, 10, Color::new(0, 128, 128))).unwrap();
+    /// let png = distilled.render_palette(&PaletteRenderOptions::default()).to_png().unwrap();
+    /// ```
+    pub fn render_palette(&self, options: &PaletteRenderOptions) -> Image {
+        let layout = self.layout(options);
+        let mut colors_img = Image::new(layout.width, layout.height);
+
+        for pixel in colors_img.pixels_mut() {
+            pixel.copy_from_slice(&options.background);
+        }
+
+        for swatch in &layout.swatches {
+            let [r, g, b] = swatch.color.rgb();
+
+            for y in swatch.y..swatch.y.saturating_add(swatch.height) {
+                for x in swatch.x..swatch.x.saturating_add(swatch.width) {
+                    colors_img.put_pixel(x, y, [r, g, b, 255]);
+                }
+            }
+        }
+
+        if options.labels {
+            for (swatch, share) in layout.swatches.iter().zip(self.shares()) {
+                draw_label(&mut colors_img, swatch, &label_lines(swatch.color, share, options.percentages));
+            }
+        }
+
+        colors_img
+    }
+
+    /// Lays out the swatches of the palette as configured by `options`.
+    pub(super) fn layout(&self, options: &PaletteRenderOptions) -> Layout {
+        let colors = &self.colors[..self.colors.len().min(options.colors)];
+        let (along, across) = match options.orientation {
+            Orientation::Horizontal => (options.swatch_width, options.swatch_height),
+            Orientation::Vertical => (options.swatch_height, options.swatch_width),
+        };
+
+        let lengths = swatch_lengths(&self.weights[..colors.len().min(self.weights.len())], colors.len(), along, options.proportional);
+        let border = options.border.saturating_mul(2);
+        let gaps = options.spacing.saturating_mul(colors.len().saturating_sub(1) as u32);
+        let length = lengths.iter().fold(gaps, |length, &swatch_length| length.saturating_add(swatch_length)).saturating_add(border);
+        let thickness = across.saturating_add(border);
+
+        let mut offset = options.border;
+        let swatches = colors.iter()
+            .zip(&lengths)
+            .map(|(&color, &swatch_length)| {
+                let start = offset;
+                offset = offset.saturating_add(swatch_length).saturating_add(options.spacing);
+
+                match options.orientation {
+                    Orientation::Horizontal => Swatch { color, x: start, y: options.border, width: swatch_length, height: across },
+                    Orientation::Vertical => Swatch { color, x: options.border, y: start, width: across, height: swatch_length },
+                }
+            })
+            .collect();
+
+        let (width, height) = match options.orientation {
+            Orientation::Horizontal => (length, thickness),
+            Orientation::Vertical => (thickness, length),
+        };
+
+        Layout { width, height, swatches }
+    }
+}
+
+/// The size of a rendered palette and where each of its swatches goes.
+pub(super) struct Layout {
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) swatches: Vec<Swatch>,
+}
+
+/// A swatch of a `Layout`, in pixels from the top left corner.
+pub(super) struct Swatch {
+    pub(super) color: Color,
+    pub(super) x: u32,
+    pub(super) y: u32,
+    pub(super) width: u32,
+    pub(super) height: u32,
+}
+
+/// Returns the lines of the label of a swatch of `color`, which makes up
+/// `share` of the image.
+pub(super) fn label_lines(color: Color, share: f32, percentages: bool) -> Vec<String> {
+    let mut lines = vec![color.hex()];
+
+    if percentages {
+        lines.push(format!("{:.0}%", share * 100.0));
+    }
+
+    lines
+}
+
+/// Draws `lines` centered on `swatch`, scaled up as far as they fit within
+/// `LABEL_FILL` of it, or not at all if they don't fit at their smallest.
+fn draw_label(img: &mut Image, swatch: &Swatch, lines: &[String]) {
+    let widest = lines.iter().map(|line| font::text_width(line)).max().unwrap_or(0);
+    let count = lines.len() as u32;
+    let text_height = count * font::GLYPH_HEIGHT + count.saturating_sub(1) * LABEL_LINE_GAP;
+
+    if widest == 0 || text_height == 0 {
+        return;
+    }
+
+    let fit = |available: u32, needed: u32| (available as u64 * LABEL_FILL.0 / LABEL_FILL.1 / needed as u64) as u32;
+    let scale = fit(swatch.width, widest).min(fit(swatch.height, text_height));
+
+    if scale == 0 {
+        return;
+    }
+
+    let rgba = {
+        let [r, g, b] = label_color(swatch.color).rgb();
+        [r, g, b, 255]
+    };
+
+    let mut y = swatch.y + (swatch.height - text_height * scale) / 2;
+
+    for line in lines {
+        let x = swatch.x + (swatch.width - font::text_width(line) * scale) / 2;
+
+        font::draw_text(img, x, y, scale, line, rgba);
+        y = y.saturating_add((font::GLYPH_HEIGHT + LABEL_LINE_GAP) * scale);
+    }
+}
+
+/// Returns black or white, whichever contrasts the most with `color`, for
+/// labels drawn over a swatch.
+pub(super) fn label_color(color: Color) -> Color {
+    let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+
+    match black.contrast_ratio(color) >= white.contrast_ratio(color) {
+        true => black,
+        false => white,
+    }
+}
+
+/// Returns the length of each of `count` swatches along the strip: `along`
+/// each, or the same total split by `weights` when `proportional`. Rounding
+/// happens on the running total, so the lengths add up exactly.
+fn swatch_lengths(weights: &[f32], count: usize, along: u32, proportional: bool) -> Vec<u32> {
+    let total_weight: f32 = weights.iter().sum();
+
+    if !proportional || weights.len() < count || total_weight <= 0.0 {
+        return vec![along; count];
+    }
+
+    let total = (along as usize * count) as f32;
+    let mut cumulative = 0.0;
+    let mut previous = 0;
+
+    weights.iter()
+        .map(|&weight| {
+            cumulative += weight;
+
+            let end = (total * cumulative / total_weight).round() as u32;
+            let length = end - previous;
+            previous = end;
+            length
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Orientation, PaletteRenderOptions};
+    use crate::palette::distil_palette;
+    use crate::{Color, Distil, IntoLab};
+
+    #[test]
+    fn render_palette() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let distilled = distil_palette(vec![(teal.into_lab(), 3), (coral.into_lab(), 1)]);
+
+        let rendered = distilled.render_palette(&PaletteRenderOptions::default());
+        assert_eq!(rendered.dimensions(), (160, 80));
+
+        let [r, g, b] = distilled.colors[1].rgb();
+        assert_eq!(rendered.get_pixel(80, 0), [r, g, b, 255]);
+        assert_eq!(rendered, distilled.swatch_img(2));
+
+        let first = distilled.render_palette(&PaletteRenderOptions { colors: 1, ..PaletteRenderOptions::default() });
+        assert_eq!(first.dimensions(), (80, 80));
+
+        let png = rendered.to_png().unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+
+    #[test]
+    fn layout() {
+        let teal = Color::new(0, 128, 128);
+        let coral = Color::new(255, 127, 80);
+        let distilled = distil_palette(vec![(teal.into_lab(), 3), (coral.into_lab(), 1)]);
+
+        let options = PaletteRenderOptions {
+            swatch_width: 20,
+            swatch_height: 10,
+            orientation: Orientation::Vertical,
+            spacing: 2,
+            border: 1,
+            background: [255, 255, 255, 255],
+            ..PaletteRenderOptions::default()
+        };
+
+        let vertical = distilled.render_palette(&options);
+        assert_eq!(vertical.dimensions(), (22, 24));
+        assert_eq!(vertical.get_pixel(0, 0), [255, 255, 255, 255]);
+        assert_eq!(vertical.get_pixel(1, 11), [255, 255, 255, 255]);
+        assert_eq!(vertical.get_pixel(1, 13)[3], 255);
+        assert_ne!(vertical.get_pixel(1, 13), [255, 255, 255, 255]);
+
+        let proportional = distilled.render_palette(&PaletteRenderOptions {
+            swatch_width: 50,
+            proportional: true,
+            ..PaletteRenderOptions::default()
+        });
+
+        let [r, g, b] = distilled.colors[0].rgb();
+        assert_eq!(proportional.dimensions(), (100, 80));
+        assert_eq!(proportional.get_pixel(74, 0), [r, g, b, 255]);
+        assert_ne!(proportional.get_pixel(76, 0), [r, g, b, 255]);
+    }
+
+    #[test]
+    fn labels() {
+        let colors = [Color::new(20, 30, 60), Color::new(245, 235, 210)];
+        let distil = Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        };
+
+        let labeled = distil.render_palette(&PaletteRenderOptions { labels: true, ..PaletteRenderOptions::default() });
+
+        // The hex code is 41 × 7 pixels, centered, and starts with a `#`.
+        assert_eq!(labeled.get_pixel(20, 36), [255, 255, 255, 255]);
+        assert_eq!(labeled.get_pixel(19, 36), [20, 30, 60, 255]);
+        assert_eq!(labeled.get_pixel(100, 36), [0, 0, 0, 255]);
+
+        let shares = distil.render_palette(&PaletteRenderOptions { labels: true, percentages: true, ..PaletteRenderOptions::default() });
+        assert_eq!(shares.get_pixel(20, 31), [255, 255, 255, 255]);
+        assert_ne!(shares, labeled);
+
+        let small = PaletteRenderOptions { swatch_width: 20, swatch_height: 20, ..PaletteRenderOptions::default() };
+        assert_eq!(distil.render_palette(&PaletteRenderOptions { labels: true, ..small.clone() }), distil.render_palette(&small));
+    }
+}
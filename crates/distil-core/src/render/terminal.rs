@@ -0,0 +1,222 @@
+use std::env;
+use std::fmt::Write;
+use std::io::{self, Write as _};
+
+use crate::{Distil, DistilError, Image};
+
+/// Pixels with an alpha below this are left blank.
+static MIN_VISIBLE_ALPHA: u8 = 128;
+
+/// The width of a swatch printed by `Distil::terminal_swatches`, in
+/// characters.
+static SWATCH_COLUMNS: usize = 6;
+
+/// The levels of each channel of the 6 × 6 × 6 color cube of 256-color
+/// terminals, which starts at index 16.
+static CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The colors a terminal can show, for `Distil::terminal_swatches`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit color, exact to the palette.
+    #[default]
+    TrueColor,
+
+    /// The 256 colors of xterm, approximating each color by the nearest
+    /// one of its color cube or grey ramp.
+    Ansi256,
+}
+
+impl ColorDepth {
+    /// Returns `ColorDepth::TrueColor` if the `COLORTERM` environment
+    /// variable is `truecolor` or `24bit`, the way terminals advertise it,
+    /// and `ColorDepth::Ansi256` otherwise.
+    pub fn detect() -> ColorDepth {
+        match env::var("COLORTERM").as_deref() {
+            Ok("truecolor") | Ok("24bit") => ColorDepth::TrueColor,
+            _ => ColorDepth::Ansi256,
+        }
+    }
+}
+
+impl Distil {
+    /// Renders the palette for a terminal, one color per line: a swatch
+    /// followed by the color's hex code and its share of the image.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::render::ColorDepth;
+    /// use distil_core::{gen, Color, Distil};
+    ///
+    /// let distilled = Distil::from_image(&gen::solid(10, 10, Color::new(0, 128, 128))).unwrap();
+    /// let swatches = distilled.terminal_swatches(ColorDepth::Ansi256);
+    ///
+    /// assert_eq!(swatches.lines().count(), distilled.colors.len());
+    /// ```
+    pub fn terminal_swatches(&self, depth: ColorDepth) -> String {
+        let mut out = String::new();
+
+        for entry in self.entries() {
+            let [r, g, b] = entry.color.rgb();
+
+            let _ = match depth {
+                ColorDepth::TrueColor => write!(out, "\x1b[48;2;{};{};{}m", r, g, b),
+                ColorDepth::Ansi256 => write!(out, "\x1b[48;5;{}m", ansi256([r, g, b])),
+            };
+
+            let _ = writeln!(out, "{}\x1b[0m {} {:>5.1}%", " ".repeat(SWATCH_COLUMNS), entry.color, entry.share * 100.0);
+        }
+
+        out
+    }
+
+    /// Prints `terminal_swatches` to stdout, in true color if the terminal
+    /// advertises it and in 256 colors otherwise, e.g. to check an
+    /// extraction over SSH.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("./images/img-1.jpg") {
+    ///     distilled.print_terminal().unwrap();
+    /// }
+    /// ```
+    pub fn print_terminal(&self) -> Result<(), DistilError> {
+        io::stdout()
+            .lock()
+            .write_all(self.terminal_swatches(ColorDepth::detect()).as_bytes())
+            .map_err(|err| DistilError::Output("stdout".to_owned(), err))
+    }
+}
+
+/// Returns the index of the color of the 256-color palette nearest to
+/// `rgb`: the nearest color of the color cube or of the grey ramp, whichever
+/// is closer.
+fn ansi256(rgb: [u8; 3]) -> u8 {
+    let nearest_level = |value: u8| {
+        (0..CUBE_LEVELS.len()).min_by_key(|&i| CUBE_LEVELS[i].abs_diff(value)).unwrap_or(0)
+    };
+
+    let distance = |other: [u8; 3]| -> u32 {
+        rgb.iter().zip(&other).map(|(&a, &b)| (a.abs_diff(b) as u32).pow(2)).sum()
+    };
+
+    let (r, g, b) = (nearest_level(rgb[0]), nearest_level(rgb[1]), nearest_level(rgb[2]));
+    let cube = [CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]];
+
+    // The grey ramp runs from 8 to 238 in steps of 10.
+    let average = rgb.iter().map(|&value| value as u32).sum::<u32>() / 3;
+    let grey_index = ((average.saturating_sub(3)) / 10).min(23);
+    let grey_value = (8 + 10 * grey_index) as u8;
+
+    if distance([grey_value; 3]) < distance(cube) {
+        232 + grey_index as u8
+    } else {
+        (16 + 36 * r + 6 * g + b) as u8
+    }
+}
+
+/// Renders `img` as Unicode half blocks (`▀` and `▄`) in 24-bit ANSI color,
+/// two pixels per character, scaled down to at most `columns` characters
+/// wide by sampling the nearest pixel. Each line is reset to the terminal's
+/// colors at its end, and pixels that are more than half transparent are
+/// left blank.
+///
+/// Combined with `remap_to_palette` it previews what an image looks like
+/// in its own palette, e.g. over SSH.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::render::{half_blocks, remap_to_palette};
+/// use distil_core::{Distil, Image};
+///
+/// if let Ok(img) = Image::open(Path::new("./images/img-1.jpg")) {
+///     let distilled = Distil::from_image(&img).unwrap();
+///     print!("{}", half_blocks(&remap_to_palette(&img, &distilled), 80));
+/// }
+/// ```
+pub fn half_blocks(img: &Image, columns: u32) -> String {
+    let (img_width, img_height) = img.dimensions();
+    let width = img_width.min(columns.max(1));
+    let height = match (img_width, img_height) {
+        (0, _) | (_, 0) => 0,
+        _ => ((img_height as u64 * width as u64 + img_width as u64 / 2) / img_width as u64).max(1) as u32,
+    };
+
+    // Sampling the pixel at the center of each cell, rather than filtering,
+    // keeps the colors of a remapped image to those of its palette.
+    let pixel = |x: u32, y: u32| {
+        let source_x = ((2 * x as u64 + 1) * img_width as u64 / (2 * width as u64)) as u32;
+        let source_y = ((2 * y as u64 + 1) * img_height as u64 / (2 * height as u64)) as u32;
+
+        Some(img.get_pixel(source_x, source_y)).filter(|px| px[3] >= MIN_VISIBLE_ALPHA)
+    };
+
+    let mut out = String::new();
+
+    for y in (0..height).step_by(2) {
+        for x in 0..width {
+            let top = pixel(x, y);
+            let bottom = Some(y + 1).filter(|&y| y < height).and_then(|y| pixel(x, y));
+
+            let _ = match (top, bottom) {
+                (Some(top), Some(bottom)) => write!(out,
+                                                    "\x1b[38;2;{};{};{};48;2;{};{};{}m▀",
+                                                    top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]),
+                (Some(top), None) => write!(out, "\x1b[0;38;2;{};{};{}m▀", top[0], top[1], top[2]),
+                (None, Some(bottom)) => write!(out, "\x1b[0;38;2;{};{};{}m▄", bottom[0], bottom[1], bottom[2]),
+                (None, None) => write!(out, "\x1b[0m "),
+            };
+        }
+
+        out += "\x1b[0m\n";
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ansi256, half_blocks, ColorDepth};
+    use crate::{Color, Distil, Image, IntoLab};
+
+    #[test]
+    fn renders_half_blocks() {
+        let mut img = Image::new(2, 3);
+        img.put_pixel(0, 0, [255, 0, 0, 255]);
+        img.put_pixel(0, 1, [0, 0, 255, 255]);
+        img.put_pixel(1, 1, [0, 255, 0, 255]);
+        img.put_pixel(0, 2, [9, 9, 9, 255]);
+
+        assert_eq!(half_blocks(&img, 80),
+                   "\x1b[38;2;255;0;0;48;2;0;0;255m▀\x1b[0;38;2;0;255;0m▄\x1b[0m\n\x1b[0;38;2;9;9;9m▀\x1b[0m \x1b[0m\n");
+        assert_eq!(half_blocks(&Image::new(400, 100), 40).lines().count(), 5);
+    }
+
+    #[test]
+    fn terminal_swatches() {
+        let colors = [Color::new(255, 0, 0), Color::new(128, 128, 128)];
+        let distil = Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        };
+
+        assert_eq!(distil.terminal_swatches(ColorDepth::TrueColor),
+                   "\x1b[48;2;255;0;0m      \x1b[0m #ff0000  75.0%\n\x1b[48;2;128;128;128m      \x1b[0m #808080  25.0%\n");
+        assert_eq!(distil.terminal_swatches(ColorDepth::Ansi256),
+                   "\x1b[48;5;196m      \x1b[0m #ff0000  75.0%\n\x1b[48;5;244m      \x1b[0m #808080  25.0%\n");
+
+        assert_eq!(ansi256([0, 0, 0]), 16);
+        assert_eq!(ansi256([255, 255, 255]), 231);
+        assert_eq!(ansi256([0, 135, 255]), 33);
+        assert_eq!(ansi256([30, 30, 30]), 234);
+    }
+}
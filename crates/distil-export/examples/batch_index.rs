@@ -0,0 +1,43 @@
+//! Writes an indexed PNG of each image, mapped onto the image's own
+//! distilled palette, into the system's temporary directory.
+//!
+//! ```sh
+//! cargo run -p distil-export --example batch_index -- a.jpg b.png c.webp
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use distil_core::{Distil, Image};
+use distil_export::PaletteExport;
+
+fn main() {
+    let mut paths: Vec<PathBuf> = env::args().skip(1).map(PathBuf::from).collect();
+
+    if paths.is_empty() {
+        let images = Path::new(env!("CARGO_MANIFEST_DIR")).join("../distil-core/images");
+
+        paths = fs::read_dir(images)
+            .expect("the bundled images are missing")
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "jpg"))
+            .collect();
+        paths.sort();
+    }
+
+    for path in &paths {
+        let out_path = env::temp_dir().join(format!("{}-indexed.png", path.file_stem().unwrap().to_string_lossy()));
+
+        let result = Distil::from_path(path).and_then(|distilled| {
+            let img = Image::open(path)?;
+            distilled.encode_indexed(&img, &out_path, true)?;
+            Ok(distilled.colors.len())
+        });
+
+        match result {
+            Ok(colors) => println!("{} → {} ({} colors)", path.display(), out_path.display(), colors),
+            Err(err) => eprintln!("{}: {}", path.display(), err),
+        }
+    }
+}
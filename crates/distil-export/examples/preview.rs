@@ -0,0 +1,34 @@
+//! Previews the palette of an image in a true color terminal, with the share
+//! of the image each color covers.
+//!
+//! ```sh
+//! cargo run -p distil-export --example preview -- path/to/image.jpg
+//! ```
+
+use std::env;
+use std::process;
+
+use distil_core::Distil;
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/../distil-core/images/img-1.jpg").to_owned());
+
+    let distilled = Distil::from_path_str(&path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+
+    for (color, share) in distilled.colors.iter().zip(distilled.shares()) {
+        let [r, g, b] = color.rgb();
+
+        println!("\x1b[48;2;{};{};{}m        \x1b[0m {}  {:>5.1}%  {}",
+                 r,
+                 g,
+                 b,
+                 color,
+                 share * 100.0,
+                 color.descriptive_name());
+    }
+}
@@ -0,0 +1,46 @@
+//! Recolors one image with the colors of another and saves the result along
+//! with a `.cube` LUT of the grade into the system's temporary directory.
+//!
+//! ```sh
+//! cargo run -p distil-export --example recolor -- source.jpg target.jpg
+//! ```
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use distil_core::render::Grade;
+use distil_core::Image;
+use distil_export::GradeExport;
+
+fn main() {
+    let images = concat!(env!("CARGO_MANIFEST_DIR"), "/../distil-core/images/");
+    let mut args = env::args().skip(1);
+    let source = args.next().unwrap_or_else(|| format!("{}img-3.jpg", images));
+    let target = args.next().unwrap_or_else(|| format!("{}img-1.jpg", images));
+
+    let open = |path: &str| {
+        Image::open(Path::new(path)).unwrap_or_else(|err| {
+            eprintln!("{}: {}", path, err);
+            process::exit(1);
+        })
+    };
+
+    let (source_img, target_img) = (open(&source), open(&target));
+    let grade = Grade::transfer(&source_img, &target_img);
+
+    let out_path = env::temp_dir().join("distil-recolored.png");
+    let lut_path = env::temp_dir().join("distil-recolored.cube");
+
+    if let Err(err) = grade.apply(&target_img).save(&out_path) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+
+    if let Err(err) = grade.to_lut(33).write_cube(&lut_path, &source) {
+        eprintln!("{}: {}", lut_path.display(), err);
+        process::exit(1);
+    }
+
+    println!("{}\n{}", out_path.display(), lut_path.display());
+}
@@ -0,0 +1,36 @@
+//! Generates a CSS theme from an image: a background, a primary and an
+//! accent color, and a text color that's readable on the background.
+//!
+//! ```sh
+//! cargo run -p distil-export --example theme -- path/to/image.jpg
+//! ```
+
+use std::env;
+use std::process;
+
+use distil_core::palette::Padding;
+use distil_core::{contrast_ratio, Color, Distil};
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/../distil-core/images/img-1.jpg").to_owned());
+
+    let distilled = Distil::from_path_str(&path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+
+    let [background, primary, accent] = distilled.top_n::<3>(Padding::Repeat).unwrap();
+
+    let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+    let text = if contrast_ratio(black, background) > contrast_ratio(white, background) { black } else { white };
+
+    println!("/* {} */", distilled.describe());
+    println!(":root {{");
+    println!("  --background: {};", background);
+    println!("  --primary: {};", primary);
+    println!("  --accent: {};", accent);
+    println!("  --text: {};", text);
+    println!("}}");
+}
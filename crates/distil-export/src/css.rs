@@ -0,0 +1,128 @@
+use distil_core::{Color, Distil};
+
+/// The prefix used when an empty one is passed, since neither CSS nor SCSS
+/// allow a variable name to start with a digit.
+static DEFAULT_PREFIX: &str = "color";
+
+/// Converts `Distil` palettes into stylesheet variables.
+pub trait StylesheetExport {
+    /// Returns a `:root` block declaring each color of the palette as a CSS
+    /// custom property named after `prefix` and its rank, from most to least
+    /// frequent, e.g. `--prefix-1: #aabbcc;`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::StylesheetExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let stylesheet = distilled.as_css("artwork");
+    /// }
+    /// ```
+    fn as_css(&self, prefix: &str) -> String;
+
+    /// Like `as_css`, but declares SCSS variables, e.g.
+    /// `$prefix-1: #aabbcc;`, one per line.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::StylesheetExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let partial = distilled.as_scss("artwork");
+    /// }
+    /// ```
+    fn as_scss(&self, prefix: &str) -> String;
+
+    /// Returns a CSS `linear-gradient` from left to right through the
+    /// `count` most frequent colors of the palette, from darkest to
+    /// lightest and evenly spaced, e.g.
+    /// `linear-gradient(90deg, #14205a 0%, #fac8a0 100%)` for a duotone.
+    /// They're the stops `Grade::gradient_map` maps lightness along, so the
+    /// gradient can go alongside an image recolored with it. A single color
+    /// is held throughout, and an empty palette returns `none`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::StylesheetExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let background = distilled.as_css_gradient(2);
+    /// }
+    /// ```
+    fn as_css_gradient(&self, count: usize) -> String;
+}
+
+impl StylesheetExport for Distil {
+    fn as_css(&self, prefix: &str) -> String {
+        format!(":root {{\n{}}}\n", declarations(self, "  --", prefix))
+    }
+
+    fn as_scss(&self, prefix: &str) -> String {
+        declarations(self, "$", prefix)
+    }
+
+    fn as_css_gradient(&self, count: usize) -> String {
+        let mut stops: Vec<Color> = self.gradient_stops(count).into_iter().map(Color::from).collect();
+
+        // A gradient needs two stops, so a single color is held throughout.
+        match stops.first() {
+            Some(&color) if stops.len() == 1 => stops.push(color),
+            Some(_) => {}
+            None => return String::from("none"),
+        }
+
+        let last = (stops.len() - 1) as f32;
+
+        let stops: Vec<String> = stops.iter()
+            .enumerate()
+            .map(|(i, color)| format!("{} {}%", color, (i as f32 / last * 100.0).round()))
+            .collect();
+
+        format!("linear-gradient(90deg, {})", stops.join(", "))
+    }
+}
+
+/// Returns a `<sigil><prefix>-<rank>: <hex>;` line for each color of
+/// `distil`, each starting with `sigil`.
+fn declarations(distil: &Distil, sigil: &str, prefix: &str) -> String {
+    let prefix = if prefix.is_empty() { DEFAULT_PREFIX } else { prefix };
+
+    distil.colors
+        .iter()
+        .enumerate()
+        .map(|(i, color)| format!("{}{}-{}: {};\n", sigil, prefix, i + 1, color))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{Color, Distil, IntoLab};
+
+    use super::StylesheetExport;
+
+    #[test]
+    fn stylesheets() {
+        let colors = [Color::new(0, 128, 128), Color::new(240, 110, 90)];
+        let distil = Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        };
+
+        assert_eq!(distil.as_css("art"), ":root {\n  --art-1: #008080;\n  --art-2: #f06e5a;\n}\n");
+        assert_eq!(distil.as_scss("art"), "$art-1: #008080;\n$art-2: #f06e5a;\n");
+        assert_eq!(distil.as_scss(""), "$color-1: #008080;\n$color-2: #f06e5a;\n");
+
+        assert_eq!(distil.as_css_gradient(2), "linear-gradient(90deg, #008080 0%, #f06e5a 100%)");
+        assert_eq!(distil.as_css_gradient(1), "linear-gradient(90deg, #008080 0%, #008080 100%)");
+        assert_eq!(distil.as_css_gradient(0), "none");
+    }
+}
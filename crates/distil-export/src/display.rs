@@ -0,0 +1,226 @@
+use distil_core::{delta_e, Color, Distil};
+
+/// The fixed palette of an e-ink or LED panel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Panel<'a> {
+    /// A human readable name for the panel.
+    pub name: &'a str,
+
+    /// The colors the panel can show, in the order the panel's driver
+    /// indexes them.
+    pub colors: &'a [Color],
+}
+
+impl Panel<'static> {
+    /// A black and white e-ink panel.
+    pub const EINK_BW: Panel<'static> = Panel {
+        name: "e-ink black/white",
+        colors: &[Color::new(0, 0, 0), Color::new(255, 255, 255)],
+    };
+
+    /// A black, white and red e-ink panel.
+    pub const EINK_BWR: Panel<'static> = Panel {
+        name: "e-ink black/white/red",
+        colors: &[Color::new(0, 0, 0), Color::new(255, 255, 255), Color::new(255, 0, 0)],
+    };
+
+    /// A 7-color ACeP e-ink panel.
+    pub const EINK_ACEP: Panel<'static> = Panel {
+        name: "e-ink 7-color ACeP",
+        colors: &[Color::new(0, 0, 0),
+                  Color::new(255, 255, 255),
+                  Color::new(0, 255, 0),
+                  Color::new(0, 0, 255),
+                  Color::new(255, 0, 0),
+                  Color::new(255, 255, 0),
+                  Color::new(255, 128, 0)],
+    };
+
+    /// A 6-color Spectra e-ink panel.
+    pub const EINK_SPECTRA_6: Panel<'static> = Panel {
+        name: "e-ink Spectra 6",
+        colors: &[Color::new(0, 0, 0),
+                  Color::new(255, 255, 255),
+                  Color::new(255, 255, 0),
+                  Color::new(255, 0, 0),
+                  Color::new(0, 0, 255),
+                  Color::new(0, 255, 0)],
+    };
+
+    /// A 3-bit RGB LED matrix, where each channel is either on or off.
+    pub const LED_RGB111: Panel<'static> = Panel {
+        name: "3-bit RGB LED matrix",
+        colors: &[Color::new(0, 0, 0),
+                  Color::new(0, 0, 255),
+                  Color::new(0, 255, 0),
+                  Color::new(0, 255, 255),
+                  Color::new(255, 0, 0),
+                  Color::new(255, 0, 255),
+                  Color::new(255, 255, 0),
+                  Color::new(255, 255, 255)],
+    };
+}
+
+/// A palette color packed into 16-bit RGB565.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb565 {
+    /// The packed color: 5 bits of red, 6 of green and 5 of blue.
+    pub value: u16,
+
+    /// The CIEDE2000 distance between the palette color and the packed one.
+    pub error: f32,
+}
+
+impl Rgb565 {
+    /// Unpacks the color back into 8-bit channels.
+    pub fn color(&self) -> Color {
+        let (r, g, b) = ((self.value >> 11) as u8, (self.value >> 5 & 0x3f) as u8, (self.value & 0x1f) as u8);
+        Color::new(r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2)
+    }
+}
+
+/// A palette color mapped onto the nearest color a panel can show.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelColor {
+    /// The index of the color in the panel's palette.
+    pub index: usize,
+
+    /// The panel color.
+    pub color: Color,
+
+    /// The CIEDE2000 distance between the palette color and the panel one.
+    pub error: f32,
+}
+
+/// Converts `Distil` palettes for embedded displays.
+pub trait DisplayExport {
+    /// Packs each color of the palette into RGB565, from most-frequent to
+    /// least-frequent.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::DisplayExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let theme: Vec<u16> = distilled.to_rgb565().iter().map(|color| color.value).collect();
+    /// }
+    /// ```
+    fn to_rgb565(&self) -> Vec<Rgb565>;
+
+    /// Maps each color of the palette onto the nearest color `panel` can
+    /// show, from most-frequent to least-frequent. Returns an empty `Vec` if
+    /// the panel has no colors.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::{DisplayExport, Panel};
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let accent = distilled.to_panel(&Panel::EINK_ACEP)[0].index;
+    /// }
+    /// ```
+    fn to_panel(&self, panel: &Panel) -> Vec<PanelColor>;
+
+    /// Returns the average CIEDE2000 error of `to_panel`, weighted by how
+    /// frequent each color is, which can be used to pick the panel that
+    /// suits a palette best.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::{DisplayExport, Panel};
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let is_worth_color = distilled.panel_error(&Panel::EINK_ACEP) <
+    ///                          distilled.panel_error(&Panel::EINK_BW);
+    /// }
+    /// ```
+    fn panel_error(&self, panel: &Panel) -> f32;
+}
+
+impl DisplayExport for Distil {
+    fn to_rgb565(&self) -> Vec<Rgb565> {
+        self.lab_colors.iter()
+            .zip(&self.colors)
+            .map(|(&lab, color)| {
+                let [r, g, b] = color.rgb();
+                let mut packed = Rgb565 {
+                    value: (r as u16 >> 3) << 11 | (g as u16 >> 2) << 5 | b as u16 >> 3,
+                    error: 0.0,
+                };
+
+                packed.error = delta_e(lab, packed.color());
+                packed
+            })
+            .collect()
+    }
+
+    fn to_panel(&self, panel: &Panel) -> Vec<PanelColor> {
+        self.lab_colors.iter()
+            .filter_map(|&lab| {
+                panel.colors.iter()
+                    .enumerate()
+                    .map(|(index, &color)| PanelColor { index, color, error: delta_e(lab, color) })
+                    .min_by(|x, y| x.error.total_cmp(&y.error))
+            })
+            .collect()
+    }
+
+    fn panel_error(&self, panel: &Panel) -> f32 {
+        let mut total_error = 0.0;
+        let mut total_count = 0.0;
+
+        for (mapped, &count) in self.to_panel(panel).iter().zip(&self.counts) {
+            let count = count as f32;
+            total_error += mapped.error * count;
+            total_count += count;
+        }
+
+        if total_count == 0.0 {
+            return 0.0;
+        }
+
+        total_error / total_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{Color, Distil, IntoLab};
+
+    use super::{DisplayExport, Panel};
+
+    fn distil(colors: &[(Color, usize)]) -> Distil {
+        Distil {
+            colors: colors.iter().map(|&(color, _)| color).collect(),
+            counts: colors.iter().map(|&(_, count)| count).collect(),
+            lab_colors: colors.iter().map(|&(color, _)| color.into_lab()).collect(),
+            spreads: vec![0.0; colors.len()],
+            weights: vec![1.0 / colors.len() as f32; colors.len()],
+        }
+    }
+
+    #[test]
+    fn rgb565() {
+        let distil = distil(&[(Color::new(255, 255, 255), 1), (Color::new(255, 0, 0), 1), (Color::new(0, 0, 255), 1)]);
+        let packed = distil.to_rgb565();
+
+        assert_eq!(packed.iter().map(|color| color.value).collect::<Vec<_>>(), vec![0xffff, 0xf800, 0x001f]);
+        assert!(packed.iter().all(|color| color.error < 1.0));
+        assert_eq!(packed[1].color(), Color::new(255, 0, 0));
+    }
+
+    #[test]
+    fn panel() {
+        let distil = distil(&[(Color::new(230, 20, 30), 3), (Color::new(250, 250, 245), 1)]);
+        let mapped = distil.to_panel(&Panel::EINK_BWR);
+
+        assert_eq!(mapped.iter().map(|color| color.index).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(distil.panel_error(&Panel::EINK_BWR) < distil.panel_error(&Panel::EINK_BW));
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt::Write as FmtWrite;
+
+use distil_core::{Color, Distil};
+
+/// The name used when an empty one is passed.
+static DEFAULT_NAME: &str = "palette";
+
+/// Converts `Distil` palettes into the assets of game engines, from
+/// most-frequent to least-frequent color.
+pub trait EngineExport {
+    /// Returns the palette as the contents of a Unity color preset library
+    /// (`.colors`), which the color picker lists once it's saved under an
+    /// `Editor` folder of a project.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::EngineExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let library = distilled.as_unity_colors();
+    /// }
+    /// ```
+    fn as_unity_colors(&self) -> String;
+
+    /// Returns the palette as JSON that `JsonUtility.FromJsonOverwrite` can
+    /// load into a ScriptableObject with a `string name` and a
+    /// `Color[] colors` field.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::EngineExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let json = distilled.as_unity_json("Level 1");
+    /// }
+    /// ```
+    fn as_unity_json(&self, name: &str) -> String;
+
+    /// Returns the palette as the contents of a Godot 4 `Gradient` resource
+    /// (`.tres`), with the colors evenly spaced along it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::EngineExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let gradient = distilled.as_godot_gradient();
+    /// }
+    /// ```
+    fn as_godot_gradient(&self) -> String;
+
+    /// Returns Rust source declaring the palette as a constant array of
+    /// Bevy `Color`s named after `name`, e.g. `pub const LEVEL_1: [Color; 5]`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::EngineExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let source = distilled.as_bevy("level 1");
+    /// }
+    /// ```
+    fn as_bevy(&self, name: &str) -> String;
+}
+
+impl EngineExport for Distil {
+    fn as_unity_colors(&self) -> String {
+        let mut library = String::from("%YAML 1.1\n%TAG !u! tag:unity3d.com,2011:\n--- !u!114 &1\nMonoBehaviour:\n");
+
+        for line in ["m_ObjectHideFlags: 52",
+                     "m_CorrespondingSourceObject: {fileID: 0}",
+                     "m_PrefabInstance: {fileID: 0}",
+                     "m_PrefabAsset: {fileID: 0}",
+                     "m_GameObject: {fileID: 0}",
+                     "m_Enabled: 1",
+                     "m_EditorHideFlags: 0",
+                     "m_Script: {fileID: 12323, guid: 0000000000000000e000000000000000, type: 0}",
+                     "m_Name: ",
+                     "m_EditorClassIdentifier: ",
+                     "m_Presets:"] {
+            let _ = writeln!(library, "  {}", line);
+        }
+
+        for color in &self.colors {
+            let [r, g, b] = channels(color);
+            let _ = writeln!(library, "  - m_Name: \n    m_Color: {{r: {}, g: {}, b: {}, a: 1}}", r, g, b);
+        }
+
+        library
+    }
+
+    fn as_unity_json(&self, name: &str) -> String {
+        let colors: Vec<String> = self.colors.iter()
+            .map(|color| {
+                let [r, g, b] = channels(color);
+                format!("{{\"r\":{},\"g\":{},\"b\":{},\"a\":1}}", r, g, b)
+            })
+            .collect();
+
+        format!("{{\"name\":{},\"colors\":[{}]}}\n", json_string(or_default(name)), colors.join(","))
+    }
+
+    fn as_godot_gradient(&self) -> String {
+        let last = self.colors.len().saturating_sub(1).max(1) as f32;
+        let offsets: Vec<String> = (0..self.colors.len()).map(|i| (i as f32 / last).to_string()).collect();
+        let colors: Vec<String> = self.colors.iter()
+            .map(|color| {
+                let [r, g, b] = channels(color);
+                format!("{}, {}, {}, 1", r, g, b)
+            })
+            .collect();
+
+        format!("[gd_resource type=\"Gradient\" format=3]\n\n[resource]\noffsets = PackedFloat32Array({})\ncolors = PackedColorArray({})\n",
+                offsets.join(", "),
+                colors.join(", "))
+    }
+
+    fn as_bevy(&self, name: &str) -> String {
+        let mut source = format!("use bevy::prelude::Color;\n\npub const {}: [Color; {}] = [\n", constant_name(name), self.colors.len());
+
+        for color in &self.colors {
+            let [r, g, b] = color.rgb();
+            let _ = writeln!(source, "    Color::srgb_u8({}, {}, {}),", r, g, b);
+        }
+
+        source + "];\n"
+    }
+}
+
+/// Returns the channels of `color` between `0.0` and `1.0`.
+fn channels(color: &Color) -> [f32; 3] {
+    color.rgb().map(|channel| channel as f32 / 255.0)
+}
+
+fn or_default(name: &str) -> &str {
+    match name.trim() {
+        "" => DEFAULT_NAME,
+        name => name,
+    }
+}
+
+/// Turns `name` into a Rust constant name, e.g. `level 1` into `LEVEL_1`.
+fn constant_name(name: &str) -> String {
+    let constant: String = or_default(name).chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+
+    match constant.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("_{}", constant),
+        false => constant,
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+
+    json + "\""
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{Color, Distil, IntoLab};
+
+    use super::EngineExport;
+
+    fn distil() -> Distil {
+        let colors = [Color::new(0, 0, 255), Color::new(255, 51, 0)];
+
+        Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        }
+    }
+
+    #[test]
+    fn unity() {
+        let library = distil().as_unity_colors();
+
+        assert!(library.starts_with("%YAML 1.1\n%TAG !u! tag:unity3d.com,2011:\n--- !u!114 &1\nMonoBehaviour:\n"));
+        assert!(library.ends_with("  m_Presets:\n  - m_Name: \n    m_Color: {r: 0, g: 0, b: 1, a: 1}\n  - m_Name: \n    m_Color: {r: 1, g: 0.2, b: 0, a: 1}\n"));
+        assert_eq!(distil().as_unity_json("Level \"1\""),
+                   "{\"name\":\"Level \\\"1\\\"\",\"colors\":[{\"r\":0,\"g\":0,\"b\":1,\"a\":1},{\"r\":1,\"g\":0.2,\"b\":0,\"a\":1}]}\n");
+    }
+
+    #[test]
+    fn godot_and_bevy() {
+        assert_eq!(distil().as_godot_gradient(),
+                   "[gd_resource type=\"Gradient\" format=3]\n\n[resource]\noffsets = PackedFloat32Array(0, 1)\ncolors = PackedColorArray(0, 0, 1, 1, 1, 0.2, 0, 1)\n");
+        assert_eq!(distil().as_bevy("1st level"),
+                   "use bevy::prelude::Color;\n\npub const _1ST_LEVEL: [Color; 2] = [\n    Color::srgb_u8(0, 0, 255),\n    Color::srgb_u8(255, 51, 0),\n];\n");
+    }
+}
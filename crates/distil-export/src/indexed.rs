@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use delta_e::DE2000;
+use distil_core::{lab_to_rgb, Distil, DistilError, Image};
+use lab::Lab;
+
+/// Pixels with an alpha below this are written as transparent.
+static MIN_OPAQUE_ALPHA: u8 = 128;
+
+/// Encodes images with a `Distil` palette.
+pub trait PaletteExport {
+    /// Writes `img` to `out_path` as an indexed PNG or GIF, depending on the
+    /// extension of `out_path`, with every pixel mapped to its nearest color
+    /// in the distilled palette.
+    ///
+    /// When `dither` is `true`, Floyd–Steinberg dithering is used to spread
+    /// the error of each mapped pixel onto its neighbours. Pixels that are
+    /// more than half transparent are written as fully transparent.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::env;
+    /// use std::path::Path;
+    /// use distil_core::{Distil, Image};
+    /// use distil_export::PaletteExport;
+    ///
+    /// let path = Path::new("../distil-core/images/img-1.jpg");
+    ///
+    /// if let (Ok(distilled), Ok(img)) = (Distil::from_path(path), Image::open(path)) {
+    ///     let out_path = env::temp_dir().join("img-1-indexed.gif");
+    ///     let _ = distilled.encode_indexed(&img, &out_path, true);
+    /// }
+    /// ```
+    fn encode_indexed(&self, img: &Image, out_path: &Path, dither: bool) -> Result<(), DistilError>;
+}
+
+impl PaletteExport for Distil {
+    fn encode_indexed(&self, img: &Image, out_path: &Path, dither: bool) -> Result<(), DistilError> {
+        let extension = out_path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+
+        let is_gif = match extension.as_deref() {
+            Some("gif") => true,
+            Some("png") => false,
+            _ => return Err(DistilError::UnsupportedFormat),
+        };
+
+        // GIF and PNG palettes are limited to 256 entries, one of which is
+        // kept for transparency.
+        let labs: Vec<Lab> = self.lab_colors.iter().take(255).cloned().collect();
+        let indices = index_pixels(img, &labs, dither);
+
+        let mut palette: Vec<u8> = labs.iter().flat_map(|&lab| lab_to_rgb(lab).to_vec()).collect();
+        let transparent = labs.len() as u8;
+        let has_transparency = indices.contains(&transparent);
+
+        if has_transparency {
+            palette.extend_from_slice(&[0, 0, 0]);
+        }
+
+        let (width, height) = img.dimensions();
+        let output_err = |err: io::Error| DistilError::Output(format!("{:?}", out_path), err);
+        let file = File::create(out_path).map_err(output_err)?;
+
+        let result = if is_gif {
+            write_gif(file, width, height, &palette, indices, has_transparency)
+        } else {
+            write_png(file, width, height, &palette, &indices, has_transparency)
+        };
+
+        result.map_err(output_err)
+    }
+}
+
+fn write_gif(file: File,
+             width: u32,
+             height: u32,
+             palette: &[u8],
+             indices: Vec<u8>,
+             has_transparency: bool)
+             -> io::Result<()> {
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "The image is too large for a GIF"));
+    }
+
+    let mut encoder = gif::Encoder::new(file, width as u16, height as u16, palette).map_err(gif_error)?;
+
+    let frame = gif::Frame {
+        width: width as u16,
+        height: height as u16,
+        transparent: if has_transparency { Some((palette.len() / 3 - 1) as u8) } else { None },
+        buffer: indices.into(),
+        ..gif::Frame::default()
+    };
+
+    encoder.write_frame(&frame).map_err(gif_error)
+}
+
+fn gif_error(err: gif::EncodingError) -> io::Error {
+    match err {
+        gif::EncodingError::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidInput, err.to_string()),
+    }
+}
+
+fn write_png(file: File,
+             width: u32,
+             height: u32,
+             palette: &[u8],
+             indices: &[u8],
+             has_transparency: bool)
+             -> io::Result<()> {
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(palette.to_vec());
+
+    if has_transparency {
+        let mut alphas = vec![255; palette.len() / 3];
+        alphas[palette.len() / 3 - 1] = 0;
+        encoder.set_trns(alphas);
+    }
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(indices)?;
+    Ok(())
+}
+
+/// Maps every pixel of `img` to the index of its nearest color in
+/// `palette`, optionally with Floyd–Steinberg dithering. Pixels that are
+/// more than half transparent are mapped to `palette.len()`.
+pub(crate) fn index_pixels(img: &Image, palette: &[Lab], dither: bool) -> Vec<u8> {
+    let (width, height) = img.dimensions();
+    let (width, height) = (width as usize, height as usize);
+
+    let mut nearest_cache: HashMap<[u8; 3], u8> = HashMap::new();
+    let mut nearest = |rgb: [u8; 3]| {
+        *nearest_cache.entry(rgb).or_insert_with(|| {
+            let lab = Lab::from_rgb(&rgb);
+
+            palette.iter()
+                .map(|&color| DE2000::new(lab, color))
+                .enumerate()
+                .fold((0, f32::INFINITY), |best, (i, delta)| if delta < best.1 { (i, delta) } else { best })
+                .0 as u8
+        })
+    };
+
+    let palette_rgb: Vec<[u8; 3]> = palette.iter().map(|&lab| lab_to_rgb(lab)).collect();
+    let mut errors = vec![[0.0f32; 3]; if dither { width * (height + 1) + 1 } else { 0 }];
+    let mut indices = Vec::with_capacity(width * height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let px = img.get_pixel(x as u32, y as u32);
+
+            if px[3] < MIN_OPAQUE_ALPHA || palette.is_empty() {
+                indices.push(palette.len() as u8);
+                continue;
+            }
+
+            if !dither {
+                indices.push(nearest([px[0], px[1], px[2]]));
+                continue;
+            }
+
+            let i = y * width + x;
+            let mut rgb = [0u8; 3];
+
+            for c in 0..3 {
+                rgb[c] = (px[c] as f32 + errors[i][c]).round().clamp(0.0, 255.0) as u8;
+            }
+
+            let index = nearest(rgb);
+            indices.push(index);
+
+            let chosen = palette_rgb[index as usize];
+
+            for c in 0..3 {
+                let error = rgb[c] as f32 - chosen[c] as f32;
+
+                if x + 1 < width {
+                    errors[i + 1][c] += error * 7.0 / 16.0;
+                }
+
+                if x > 0 {
+                    errors[i + width - 1][c] += error * 3.0 / 16.0;
+                }
+
+                errors[i + width][c] += error * 5.0 / 16.0;
+
+                if x + 1 < width {
+                    errors[i + width + 1][c] += error / 16.0;
+                }
+            }
+        }
+    }
+
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use distil_core::{gen, Color, Distil, Image};
+    use lab::Lab;
+
+    use super::index_pixels;
+    use crate::PaletteExport;
+
+    fn two_tone() -> (Distil, Image) {
+        let red = Lab::from_rgb(&[200, 40, 40]);
+        let blue = Lab::from_rgb(&[40, 40, 200]);
+
+        let palette = Distil {
+            colors: vec![Color::from(red), Color::from(blue)],
+            counts: vec![2, 1],
+            lab_colors: vec![red, blue],
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.5, 0.5],
+        };
+
+        let mut img = gen::blocks(8, 8, &[(Color::new(190, 50, 50), 1), (Color::new(50, 50, 190), 1)]);
+        img.put_pixel(0, 0, [0, 0, 0, 0]);
+
+        (palette, img)
+    }
+
+    #[test]
+    fn indices() {
+        let (palette, img) = two_tone();
+        let indices = index_pixels(&img, &palette.lab_colors, false);
+
+        assert_eq!(indices[0], 2);
+        assert_eq!(indices[1], 0);
+        assert_eq!(indices[7], 1);
+    }
+
+    #[test]
+    fn encode() {
+        let (palette, img) = two_tone();
+
+        for name in &["distil-indexed-test.png", "distil-indexed-test.gif"] {
+            let out_path = env::temp_dir().join(name);
+            palette.encode_indexed(&img, &out_path, true).unwrap();
+
+            let decoded = image::open(&out_path).unwrap().to_rgba8();
+            assert_eq!(decoded.get_pixel(1, 0).0, [200, 40, 40, 255]);
+            assert_eq!(decoded.get_pixel(0, 0)[3], 0);
+        }
+    }
+}
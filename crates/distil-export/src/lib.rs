@@ -0,0 +1,47 @@
+//! Exports Distil palettes and grades to other formats: `.cube` LUTs,
+//! indexed PNGs and GIFs, CSS gradient placeholders, CSS and SCSS variables,
+//! Adobe and GIMP swatches, RGB565 or e-ink panel palettes for embedded
+//! displays, Philips Hue or WLED payloads for ambient lighting, palette
+//! textures for GPU shaders, and Unity, Godot or Bevy assets.
+//!
+//! Each exporter sits behind a feature of the same name (`lut`, `indexed`,
+//! `placeholder`, `css`, `swatches`, `display`, `lighting`, `texture` and
+//! `engine`), all of which are enabled by default.
+
+#[cfg(feature = "css")]
+pub use crate::css::StylesheetExport;
+#[cfg(feature = "display")]
+pub use crate::display::{DisplayExport, Panel, PanelColor, Rgb565};
+#[cfg(feature = "engine")]
+pub use crate::engine::EngineExport;
+#[cfg(feature = "indexed")]
+pub use crate::indexed::PaletteExport;
+#[cfg(feature = "lighting")]
+pub use crate::lighting::{edge_strip, hue_state, wled_state};
+#[cfg(feature = "lut")]
+pub use crate::lut::{GradeExport, Lut};
+#[cfg(feature = "placeholder")]
+pub use crate::placeholder::{color_grid, GradientPlaceholder};
+#[cfg(feature = "swatches")]
+pub use crate::swatches::SwatchExport;
+#[cfg(feature = "texture")]
+pub use crate::texture::{TexelFormat, TextureExport};
+
+#[cfg(feature = "css")]
+mod css;
+#[cfg(feature = "display")]
+mod display;
+#[cfg(feature = "engine")]
+mod engine;
+#[cfg(feature = "indexed")]
+mod indexed;
+#[cfg(feature = "lighting")]
+mod lighting;
+#[cfg(feature = "lut")]
+mod lut;
+#[cfg(feature = "placeholder")]
+mod placeholder;
+#[cfg(feature = "swatches")]
+mod swatches;
+#[cfg(feature = "texture")]
+mod texture;
@@ -0,0 +1,172 @@
+use distil_core::{Color, Image, IntoLab};
+
+use crate::color_grid;
+
+/// The lightness under which a light is switched off rather than dimmed.
+static MIN_LIGHTNESS: f32 = 1.0;
+
+/// The chromaticity of the D65 white point, used for black.
+static D65_XY: [f32; 2] = [0.3127, 0.3290];
+
+/// Samples the edges of `img` for an LED strip running around a screen,
+/// with `columns` LEDs along the top and bottom and `rows` along the sides.
+/// The colors start at the top-left corner and go clockwise.
+///
+/// ## Example
+///
+/// ```
+/// use std::path::Path;
+/// use distil_core::Image;
+/// use distil_export::{edge_strip, wled_state};
+///
+/// if let Ok(img) = Image::open(Path::new("../distil-core/images/img-1.jpg")) {
+///     let strip = edge_strip(&img, 16, 9);
+///     assert_eq!(strip.len(), 46);
+///
+///     let body = wled_state(&strip);
+/// }
+/// ```
+pub fn edge_strip(img: &Image, columns: u32, rows: u32) -> Vec<Color> {
+    let (columns, rows) = (columns.max(1) as usize, rows.max(1) as usize);
+    let grid = color_grid(img, columns as u32, rows as u32);
+    let cell = |column: usize, row: usize| grid[row * columns + column];
+
+    if columns == 1 || rows == 1 {
+        return grid;
+    }
+
+    let top = (0..columns).map(|column| cell(column, 0));
+    let right = (1..rows - 1).map(|row| cell(columns - 1, row));
+    let bottom = (0..columns).rev().map(|column| cell(column, rows - 1));
+    let left = (1..rows - 1).rev().map(|row| cell(0, row));
+
+    top.chain(right).chain(bottom).chain(left).collect()
+}
+
+/// Returns the JSON body of a Philips Hue light state request which sets a
+/// light to `color`. The color becomes the light's CIE xy chromaticity and
+/// its Lab lightness becomes the light's brightness.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::Color;
+/// use distil_export::hue_state;
+///
+/// assert_eq!(hue_state(Color::new(255, 255, 255)), r#"{"on":true,"bri":254,"xy":[0.3127,0.3290]}"#);
+/// ```
+pub fn hue_state(color: Color) -> String {
+    let lightness = color.into_lab().l;
+
+    if lightness < MIN_LIGHTNESS {
+        return r#"{"on":false}"#.to_owned();
+    }
+
+    let bri = 1 + (lightness.min(100.0) / 100.0 * 253.0).round() as u8;
+    let [x, y] = chromaticity(color);
+
+    format!(r#"{{"on":true,"bri":{},"xy":[{:.4},{:.4}]}}"#, bri, x, y)
+}
+
+/// Returns the JSON body of a WLED state request which sets each LED of the
+/// first segment to the matching color in `colors`. The colors are sent at
+/// full intensity and the average Lab lightness of `colors` becomes the
+/// strip's brightness.
+///
+/// ## Example
+///
+/// ```
+/// use distil_core::Color;
+/// use distil_export::wled_state;
+///
+/// let body = wled_state(&[Color::new(255, 0, 0), Color::new(0, 0, 128)]);
+/// assert!(body.starts_with(r#"{"on":true,"#));
+/// ```
+pub fn wled_state(colors: &[Color]) -> String {
+    let lightness = colors.iter().map(|&color| color.into_lab().l).sum::<f32>() / colors.len().max(1) as f32;
+
+    if lightness < MIN_LIGHTNESS {
+        return r#"{"on":false}"#.to_owned();
+    }
+
+    let bri = (lightness.min(100.0) / 100.0 * 255.0).round() as u8;
+    let leds: Vec<String> = colors.iter()
+        .map(|color| {
+            let [r, g, b] = full_intensity(*color);
+            format!("[{},{},{}]", r, g, b)
+        })
+        .collect();
+
+    format!(r#"{{"on":true,"bri":{},"seg":[{{"i":[{}]}}]}}"#, bri, leds.join(","))
+}
+
+/// Scales `color` up until its brightest channel is 255, so that brightness
+/// is left to the light.
+fn full_intensity(color: Color) -> [u8; 3] {
+    let rgb = color.rgb();
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+
+    if max == 0 {
+        return rgb;
+    }
+
+    rgb.map(|channel| (channel as u32 * 255 / max as u32) as u8)
+}
+
+/// Converts `color` to its CIE 1931 xy chromaticity.
+fn chromaticity(color: Color) -> [f32; 2] {
+    let [r, g, b] = color.rgb().map(|channel| {
+        let channel = channel as f32 / 255.0;
+
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    });
+
+    let x = 0.4124 * r + 0.3576 * g + 0.1805 * b;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = 0.0193 * r + 0.1192 * g + 0.9505 * b;
+    let sum = x + y + z;
+
+    if sum == 0.0 {
+        return D65_XY;
+    }
+
+    [x / sum, y / sum]
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{Color, Image};
+
+    use super::{edge_strip, hue_state, wled_state};
+
+    #[test]
+    fn strip() {
+        let mut img = Image::new(3, 3);
+
+        for x in 0..3 {
+            for y in 0..3 {
+                img.put_pixel(x, y, [(x * 100) as u8, (y * 100) as u8, 0, 255]);
+            }
+        }
+
+        let strip = edge_strip(&img, 3, 3);
+        let expected: Vec<Color> = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1)]
+            .iter()
+            .map(|&(x, y)| Color::new(x * 100, y * 100, 0))
+            .collect();
+
+        assert_eq!(strip, expected);
+    }
+
+    #[test]
+    fn payloads() {
+        assert_eq!(hue_state(Color::new(0, 0, 0)), r#"{"on":false}"#);
+        assert_eq!(hue_state(Color::new(255, 0, 0)), r#"{"on":true,"bri":136,"xy":[0.6401,0.3300]}"#);
+        assert_eq!(wled_state(&[Color::new(128, 0, 0), Color::new(255, 255, 255)]),
+                   r#"{"on":true,"bri":160,"seg":[{"i":[[255,0,0],[255,255,255]]}]}"#);
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use distil_core::render::Grade;
+use distil_core::{lab_to_srgb, srgb_to_lab};
+
+/// A 3D lookup table baked from a `Grade`, which can be exported as an Adobe
+/// / Resolve `.cube` file for use in video editors.
+#[derive(Debug, Clone)]
+pub struct Lut {
+    size: usize,
+    table: Vec<[f32; 3]>,
+}
+
+impl Lut {
+    /// The number of entries along each axis of the table.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the output color, with channels between `0.0` and `1.0`, for
+    /// the grid point at the passed red, green and blue indices.
+    pub fn get(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.table[r + g * self.size + b * self.size * self.size]
+    }
+
+    /// Formats the table as the contents of a `.cube` file.
+    pub fn to_cube(&self, title: &str) -> String {
+        let mut cube = String::new();
+
+        let _ = writeln!(cube, "TITLE \"{}\"", title.replace('"', "'"));
+        let _ = writeln!(cube, "LUT_3D_SIZE {}", self.size);
+        let _ = writeln!(cube, "DOMAIN_MIN 0.0 0.0 0.0");
+        let _ = writeln!(cube, "DOMAIN_MAX 1.0 1.0 1.0");
+
+        for rgb in &self.table {
+            let _ = writeln!(cube, "{:.6} {:.6} {:.6}", rgb[0], rgb[1], rgb[2]);
+        }
+
+        cube
+    }
+
+    /// Writes the table to `out_path` as a `.cube` file.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::env;
+    /// use distil_core::render::Grade;
+    /// use distil_core::Distil;
+    /// use distil_export::GradeExport;
+    ///
+    /// if let Ok(reference) = Distil::from_path_str("../distil-core/images/img-3.jpg") {
+    ///     let lut = Grade::palette(&reference, 0.5).to_lut(33);
+    ///     let _ = lut.write_cube(&env::temp_dir().join("img-3-grade.cube"), "img-3");
+    /// }
+    /// ```
+    pub fn write_cube(&self, out_path: &Path, title: &str) -> io::Result<()> {
+        let mut file = File::create(out_path)?;
+        file.write_all(self.to_cube(title).as_bytes())
+    }
+}
+
+/// Bakes a `Grade` into a `Lut`.
+pub trait GradeExport {
+    /// Bakes the grade into a `size` × `size` × `size` 3D LUT. 33 is the
+    /// usual size for grading video; `size` is raised to at least 2.
+    fn to_lut(&self, size: usize) -> Lut;
+}
+
+impl GradeExport for Grade {
+    fn to_lut(&self, size: usize) -> Lut {
+        let size = size.max(2);
+        let step = 1.0 / (size - 1) as f32;
+        let mut table = Vec::with_capacity(size * size * size);
+
+        // `.cube` files list entries with red changing fastest.
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    let input = [r as f32 * step, g as f32 * step, b as f32 * step];
+                    table.push(lab_to_srgb(self.grade_lab(srgb_to_lab(input))));
+                }
+            }
+        }
+
+        Lut { size, table }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::render::Grade;
+    use distil_core::Color;
+
+    use crate::GradeExport;
+
+    #[test]
+    fn identity() {
+        let lut = Grade::tint(Color::new(255, 0, 0), 0.0).to_lut(5);
+
+        for b in 0..5 {
+            for g in 0..5 {
+                for r in 0..5 {
+                    let expected = [r as f32 / 4.0, g as f32 / 4.0, b as f32 / 4.0];
+                    let actual = lut.get(r, g, b);
+
+                    for i in 0..3 {
+                        assert!((expected[i] - actual[i]).abs() < 0.001);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cube() {
+        let cube = Grade::tint(Color::new(255, 0, 0), 0.5).to_lut(2).to_cube("red");
+        let lines: Vec<&str> = cube.lines().collect();
+
+        assert_eq!(lines[0], "TITLE \"red\"");
+        assert_eq!(lines[1], "LUT_3D_SIZE 2");
+        assert_eq!(lines.len(), 4 + 8);
+    }
+}
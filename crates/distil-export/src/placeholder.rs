@@ -0,0 +1,136 @@
+pub use distil_core::extract::color_grid;
+use distil_core::{Color, Image};
+
+/// A grid of the average colors of an image, rendered as a CSS gradient
+/// placeholder that can be shown while the image loads without any
+/// JavaScript.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientPlaceholder {
+    columns: usize,
+    colors: Vec<Color>,
+}
+
+impl GradientPlaceholder {
+    /// Averages `img` into a `columns` × `rows` grid, typically 3 × 3 or
+    /// 4 × 4.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use distil_core::Image;
+    /// use distil_export::GradientPlaceholder;
+    ///
+    /// if let Ok(img) = Image::open(Path::new("../distil-core/images/img-1.jpg")) {
+    ///     let placeholder = GradientPlaceholder::new(&img, 3, 3);
+    ///     let style = format!("<div style=\"{}\"></div>", placeholder.to_css());
+    /// }
+    /// ```
+    pub fn new(img: &Image, columns: u32, rows: u32) -> GradientPlaceholder {
+        GradientPlaceholder {
+            columns: columns.max(1) as usize,
+            colors: color_grid(img, columns, rows),
+        }
+    }
+
+    /// Returns the colors of the grid, row by row.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Returns the rows of the grid.
+    pub fn rows(&self) -> Vec<&[Color]> {
+        self.colors.chunks(self.columns).collect()
+    }
+
+    /// Renders the grid as CSS declarations: one horizontal
+    /// `linear-gradient` per row, stacked vertically.
+    pub fn to_css(&self) -> String {
+        let rows = self.rows();
+        let row_count = rows.len();
+
+        let gradients: Vec<String> = rows.iter().map(|row| linear_gradient(row)).collect();
+
+        let positions: Vec<String> = (0..row_count)
+            .map(|i| {
+                if row_count == 1 {
+                    "0 0".to_owned()
+                } else {
+                    format!("0 {}%", format_percentage(i as f32 * 100.0 / (row_count - 1) as f32))
+                }
+            })
+            .collect();
+
+        format!("background-image: {}; background-size: 100% {}%; background-position: {}; \
+                 background-repeat: no-repeat;",
+                gradients.join(", "),
+                format_percentage(100.0 / row_count as f32),
+                positions.join(", "))
+    }
+}
+
+fn linear_gradient(row: &[Color]) -> String {
+    if row.len() == 1 {
+        return format!("linear-gradient(90deg, {0}, {0})", row[0]);
+    }
+
+    let stops: Vec<String> = row.iter()
+        .enumerate()
+        .map(|(i, color)| {
+            format!("{} {}%", color, format_percentage(i as f32 * 100.0 / (row.len() - 1) as f32))
+        })
+        .collect();
+
+    format!("linear-gradient(90deg, {})", stops.join(", "))
+}
+
+/// Formats a percentage with at most two decimals and no trailing zeroes.
+fn format_percentage(value: f32) -> String {
+    let formatted = format!("{:.2}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{Color, Image};
+
+    use super::{color_grid, GradientPlaceholder};
+
+    fn quadrants() -> Image {
+        let mut img = Image::new(4, 4);
+
+        for x in 0..4 {
+            for y in 0..4 {
+                let px = match (x < 2, y < 2) {
+                    (true, true) => [255, 0, 0, 255],
+                    (false, true) => [0, 255, 0, 255],
+                    (true, false) => [0, 0, 255, 255],
+                    (false, false) => [255, 255, 255, 255],
+                };
+
+                img.put_pixel(x, y, px);
+            }
+        }
+
+        img
+    }
+
+    #[test]
+    fn grid() {
+        let grid = color_grid(&quadrants(), 2, 2);
+
+        assert_eq!(grid,
+                   vec![Color::new(255, 0, 0), Color::new(0, 255, 0), Color::new(0, 0, 255),
+                        Color::new(255, 255, 255)]);
+    }
+
+    #[test]
+    fn css() {
+        let css = GradientPlaceholder::new(&quadrants(), 2, 2).to_css();
+
+        assert_eq!(css,
+                   "background-image: linear-gradient(90deg, #ff0000 0%, #00ff00 100%), \
+                    linear-gradient(90deg, #0000ff 0%, #ffffff 100%); background-size: 100% 50%; \
+                    background-position: 0 0%, 0 100%; background-repeat: no-repeat;");
+    }
+}
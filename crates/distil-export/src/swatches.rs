@@ -0,0 +1,156 @@
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::path::Path;
+
+use distil_core::{Distil, DistilError};
+
+/// The ASE block type of a single color.
+static ASE_COLOR_ENTRY: u16 = 0x0001;
+
+/// The ASE color type of a process color, as opposed to a global or spot
+/// one.
+static ASE_NORMAL_COLOR: u16 = 2;
+
+/// Converts `Distil` palettes into the swatch files of design tools.
+///
+/// Each color is named after the string at the same index in `names`, or
+/// after its hex value when `names` runs out.
+pub trait SwatchExport {
+    /// Returns the palette as the contents of an Adobe Swatch Exchange
+    /// (`.ase`) file, which Photoshop, Illustrator and InDesign import.
+    fn to_ase(&self, names: &[&str]) -> Vec<u8>;
+
+    /// Returns the palette as the contents of a GIMP (`.gpl`) palette
+    /// titled `title`, which GIMP, Inkscape and Krita import.
+    fn to_gpl(&self, title: &str, names: &[&str]) -> String;
+
+    /// Writes `to_ase` to `out_path`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::env;
+    /// use distil_core::Distil;
+    /// use distil_export::SwatchExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let _ = distilled.as_ase(&env::temp_dir().join("img-1.ase"), &["Sky", "Sea"]);
+    /// }
+    /// ```
+    fn as_ase(&self, out_path: &Path, names: &[&str]) -> Result<(), DistilError>;
+
+    /// Writes `to_gpl` to `out_path`, titled after its file name.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::env;
+    /// use distil_core::Distil;
+    /// use distil_export::SwatchExport;
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let _ = distilled.as_gpl(&env::temp_dir().join("img-1.gpl"), &[]);
+    /// }
+    /// ```
+    fn as_gpl(&self, out_path: &Path, names: &[&str]) -> Result<(), DistilError>;
+}
+
+impl SwatchExport for Distil {
+    fn to_ase(&self, names: &[&str]) -> Vec<u8> {
+        let mut ase = b"ASEF".to_vec();
+        ase.extend_from_slice(&1u16.to_be_bytes());
+        ase.extend_from_slice(&0u16.to_be_bytes());
+        ase.extend_from_slice(&(self.colors.len() as u32).to_be_bytes());
+
+        for (i, color) in self.colors.iter().enumerate() {
+            // Names are null terminated UTF-16, with their length counted in
+            // code units including the terminator.
+            let name: Vec<u16> = swatch_name(names, i, &color.to_string()).encode_utf16().chain([0]).collect();
+
+            ase.extend_from_slice(&ASE_COLOR_ENTRY.to_be_bytes());
+            ase.extend_from_slice(&((2 + name.len() * 2 + 4 + 12 + 2) as u32).to_be_bytes());
+            ase.extend_from_slice(&(name.len() as u16).to_be_bytes());
+
+            for unit in name {
+                ase.extend_from_slice(&unit.to_be_bytes());
+            }
+
+            ase.extend_from_slice(b"RGB ");
+
+            for channel in color.rgb() {
+                ase.extend_from_slice(&(channel as f32 / 255.0).to_be_bytes());
+            }
+
+            ase.extend_from_slice(&ASE_NORMAL_COLOR.to_be_bytes());
+        }
+
+        ase
+    }
+
+    fn to_gpl(&self, title: &str, names: &[&str]) -> String {
+        let mut gpl = String::new();
+
+        let _ = writeln!(gpl, "GIMP Palette");
+        let _ = writeln!(gpl, "Name: {}", title.replace('\n', " "));
+        let _ = writeln!(gpl, "#");
+
+        for (i, color) in self.colors.iter().enumerate() {
+            let [r, g, b] = color.rgb();
+            let _ = writeln!(gpl, "{:3} {:3} {:3}\t{}", r, g, b, swatch_name(names, i, &color.to_string()));
+        }
+
+        gpl
+    }
+
+    fn as_ase(&self, out_path: &Path, names: &[&str]) -> Result<(), DistilError> {
+        fs::write(out_path, self.to_ase(names)).map_err(|err| DistilError::Output(format!("{:?}", out_path), err))
+    }
+
+    fn as_gpl(&self, out_path: &Path, names: &[&str]) -> Result<(), DistilError> {
+        let title = out_path.file_stem().map_or(String::new(), |stem| stem.to_string_lossy().into_owned());
+
+        fs::write(out_path, self.to_gpl(&title, names)).map_err(|err| DistilError::Output(format!("{:?}", out_path), err))
+    }
+}
+
+/// Returns the name of the swatch at `index`, falling back to `fallback`.
+fn swatch_name<'a>(names: &[&'a str], index: usize, fallback: &'a str) -> &'a str {
+    names.get(index).map_or(fallback, |name| name.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use distil_core::{Color, Distil, IntoLab};
+
+    use super::SwatchExport;
+
+    fn distil() -> Distil {
+        let colors = [Color::new(0, 128, 128), Color::new(240, 110, 90)];
+
+        Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        }
+    }
+
+    #[test]
+    fn gpl() {
+        assert_eq!(distil().to_gpl("Artwork", &["Teal"]),
+                   "GIMP Palette\nName: Artwork\n#\n  0 128 128\tTeal\n240 110  90\t#f06e5a\n");
+    }
+
+    #[test]
+    fn ase() {
+        let ase = distil().to_ase(&["Teal"]);
+
+        assert_eq!(&ase[..12], b"ASEF\x00\x01\x00\x00\x00\x00\x00\x02");
+        assert_eq!(&ase[12..18], &[0x00, 0x01, 0x00, 0x00, 0x00, 0x1e]);
+        assert_eq!(&ase[18..30], b"\x00\x05\x00T\x00e\x00a\x00l\x00\x00");
+        assert_eq!(&ase[30..34], b"RGB ");
+        assert_eq!(f32::from_be_bytes([ase[38], ase[39], ase[40], ase[41]]), 128.0 / 255.0);
+        assert_eq!(ase.len(), 12 + (6 + 0x1e) + (6 + 2 + 16 + 4 + 12 + 2));
+    }
+}
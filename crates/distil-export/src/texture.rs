@@ -0,0 +1,207 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use distil_core::{Distil, DistilError};
+
+/// The identifier every KTX 1.1 file starts with.
+static KTX_IDENTIFIER: [u8; 12] = [0xab, 0x4b, 0x54, 0x58, 0x20, 0x31, 0x31, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Written as is, so that loaders can tell the byte order of the header.
+static KTX_ENDIANNESS: u32 = 0x0403_0201;
+
+static GL_UNSIGNED_BYTE: u32 = 0x1401;
+static GL_FLOAT: u32 = 0x1406;
+static GL_RGBA: u32 = 0x1908;
+static GL_SRGB8_ALPHA8: u32 = 0x8c43;
+static GL_RGBA32F: u32 = 0x8814;
+
+/// The format of each texel of a palette texture. Every texel is opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TexelFormat {
+    /// 8-bit sRGB channels, 4 bytes per texel, for an sRGB texture that the
+    /// GPU linearizes when it's sampled.
+    Srgb8,
+
+    /// 32-bit linear-light float channels in little-endian, 16 bytes per
+    /// texel, computed from `lab_colors` so no precision is lost to
+    /// rounding into sRGB.
+    LinearF32,
+}
+
+/// Converts `Distil` palettes into Nx1 textures, one texel per color from
+/// most-frequent to least-frequent, for shaders that do palette-based
+/// post-processing, e.g. palette-mapping or posterizing a frame.
+pub trait TextureExport {
+    /// Returns the raw RGBA texels of the palette, ready to be uploaded
+    /// with e.g. `glTexImage2D` or `wgpu::Queue::write_texture`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use distil_core::Distil;
+    /// use distil_export::{TexelFormat, TextureExport};
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let texels = distilled.to_texels(TexelFormat::Srgb8);
+    ///     let width = texels.len() / 4;
+    /// }
+    /// ```
+    fn to_texels(&self, format: TexelFormat) -> Vec<u8>;
+
+    /// Returns the palette as the contents of a KTX 1.1 (`.ktx`) file of a
+    /// single Nx1 2D texture, without mipmaps.
+    fn to_ktx(&self, format: TexelFormat) -> Vec<u8>;
+
+    /// Returns the palette as the contents of an Nx1 8-bit RGBA PNG.
+    fn to_png_texture(&self) -> Result<Vec<u8>, DistilError>;
+
+    /// Writes the palette to `out_path` as a KTX file or a PNG, depending
+    /// on the extension of `out_path`, or as raw texels for any other
+    /// extension. PNGs only support `TexelFormat::Srgb8`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::env;
+    /// use distil_core::Distil;
+    /// use distil_export::{TexelFormat, TextureExport};
+    ///
+    /// if let Ok(distilled) = Distil::from_path_str("../distil-core/images/img-1.jpg") {
+    ///     let _ = distilled.as_texture(&env::temp_dir().join("img-1-palette.ktx"), TexelFormat::LinearF32);
+    /// }
+    /// ```
+    fn as_texture(&self, out_path: &Path, format: TexelFormat) -> Result<(), DistilError>;
+}
+
+impl TextureExport for Distil {
+    fn to_texels(&self, format: TexelFormat) -> Vec<u8> {
+        match format {
+            TexelFormat::Srgb8 => self.colors.iter().flat_map(|color| {
+                let [r, g, b] = color.rgb();
+                [r, g, b, 255]
+            }).collect(),
+            TexelFormat::LinearF32 => self.linear_colors().into_iter()
+                .flat_map(|[r, g, b]| [r, g, b, 1.0])
+                .flat_map(f32::to_le_bytes)
+                .collect(),
+        }
+    }
+
+    fn to_ktx(&self, format: TexelFormat) -> Vec<u8> {
+        let (gl_type, gl_type_size, gl_internal_format) = match format {
+            TexelFormat::Srgb8 => (GL_UNSIGNED_BYTE, 1, GL_SRGB8_ALPHA8),
+            TexelFormat::LinearF32 => (GL_FLOAT, 4, GL_RGBA32F),
+        };
+
+        let texels = self.to_texels(format);
+        let header = [
+            KTX_ENDIANNESS,
+            gl_type,
+            gl_type_size,
+            GL_RGBA,
+            gl_internal_format,
+            GL_RGBA,
+            self.colors.len() as u32,
+            1, // pixelHeight
+            0, // pixelDepth
+            0, // numberOfArrayElements
+            1, // numberOfFaces
+            1, // numberOfMipmapLevels
+            0, // bytesOfKeyValueData
+            texels.len() as u32, // imageSize
+        ];
+
+        let mut ktx = KTX_IDENTIFIER.to_vec();
+        ktx.extend(header.iter().flat_map(|field| field.to_le_bytes()));
+
+        // Rows of RGBA texels are always a multiple of 4 bytes long, so no
+        // padding is needed.
+        ktx.extend(texels);
+        ktx
+    }
+
+    fn to_png_texture(&self) -> Result<Vec<u8>, DistilError> {
+        let mut png = Vec::new();
+        let output_err = |err: io::Error| DistilError::Output("palette texture".to_string(), err);
+
+        {
+            let mut encoder = png::Encoder::new(&mut png, self.colors.len() as u32, 1);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+
+            let mut writer = encoder.write_header().map_err(|err| output_err(err.into()))?;
+            writer.write_image_data(&self.to_texels(TexelFormat::Srgb8)).map_err(|err| output_err(err.into()))?;
+        }
+
+        Ok(png)
+    }
+
+    fn as_texture(&self, out_path: &Path, format: TexelFormat) -> Result<(), DistilError> {
+        let extension = out_path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+
+        let contents = match (extension.as_deref(), format) {
+            (Some("ktx"), _) => self.to_ktx(format),
+            (Some("png"), TexelFormat::Srgb8) => self.to_png_texture()?,
+            (Some("png"), TexelFormat::LinearF32) => return Err(DistilError::UnsupportedFormat),
+            _ => self.to_texels(format),
+        };
+
+        fs::write(out_path, contents).map_err(|err| DistilError::Output(format!("{:?}", out_path), err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use distil_core::{Color, Distil, IntoLab};
+
+    use super::{TexelFormat, TextureExport};
+
+    fn distil() -> Distil {
+        let colors = [Color::new(255, 255, 255), Color::new(240, 110, 90)];
+
+        Distil {
+            colors: colors.to_vec(),
+            counts: vec![3, 1],
+            lab_colors: colors.iter().map(|&color| color.into_lab()).collect(),
+            spreads: vec![0.0, 0.0],
+            weights: vec![0.75, 0.25],
+        }
+    }
+
+    #[test]
+    fn texels() {
+        let distil = distil();
+        let linear = distil.to_texels(TexelFormat::LinearF32);
+        let channel = |i: usize| f32::from_le_bytes([linear[i * 4], linear[i * 4 + 1], linear[i * 4 + 2], linear[i * 4 + 3]]);
+
+        assert_eq!(distil.to_texels(TexelFormat::Srgb8), vec![255, 255, 255, 255, 240, 110, 90, 255]);
+        assert_eq!(linear.len(), 32);
+        assert!((channel(0) - 1.0).abs() < 1e-3);
+        assert!((channel(5) - 0.155).abs() < 1e-2);
+        assert_eq!(channel(7), 1.0);
+    }
+
+    #[test]
+    fn ktx_and_png() {
+        let distil = distil();
+        let ktx = distil.to_ktx(TexelFormat::Srgb8);
+        let field = |i: usize| u32::from_le_bytes([ktx[12 + i * 4], ktx[13 + i * 4], ktx[14 + i * 4], ktx[15 + i * 4]]);
+
+        assert_eq!(&ktx[..12], b"\xabKTX 11\xbb\r\n\x1a\n");
+        assert_eq!((field(0), field(4), field(6), field(7), field(13)), (0x0403_0201, 0x8c43, 2, 1, 8));
+        assert_eq!(&ktx[68..], &[255, 255, 255, 255, 240, 110, 90, 255]);
+
+        let png = distil.to_png_texture().unwrap();
+        let mut reader = png::Decoder::new(Cursor::new(&png[..])).read_info().unwrap();
+        let mut texels = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut texels).unwrap();
+
+        assert_eq!((reader.info().width, reader.info().height), (2, 1));
+        assert_eq!(texels, distil.to_texels(TexelFormat::Srgb8));
+    }
+}